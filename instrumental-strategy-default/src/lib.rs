@@ -48,10 +48,14 @@ pub mod pallet {
         traits::{
             AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Zero,
         },
-        DispatchError, DispatchResult,
+        DispatchError, DispatchResult, FixedU128, Percent,
     };
     use sp_std::fmt::Debug;
-    use traits::strategy::InstrumentalProtocolStrategy;
+    use traits::strategy::{
+        Apy, CircuitBreakerThresholds, CircuitBreakerTrip, InstrumentalProtocolStrategy,
+        InstrumentalStrategyMetadata, StrategyHealth, StrategyMetadata, SupportedAssets,
+        TripReason,
+    };
 
     use crate::weights::WeightInfo;
 
@@ -164,6 +168,26 @@ pub mod pallet {
     #[pallet::storage]
     pub type Halted<T: Config> = StorageValue<_, bool>;
 
+    /// The thresholds above which the strategy halts itself, the circuit breaker being disabled
+    /// when `None`. Funds never leave the vaults, so their value cannot drop through the strategy
+    /// and only [`max_consecutive_failures`](CircuitBreakerThresholds::max_consecutive_failures)
+    /// applies: a rebalance fails when an associated vault cannot be reached.
+    #[pallet::storage]
+    #[pallet::getter(fn breaker_thresholds)]
+    pub type BreakerThresholds<T: Config> =
+        StorageValue<_, CircuitBreakerThresholds<T::BlockNumber>>;
+
+    /// The tripped circuit breaker keeping the strategy halted, until governance resets it.
+    #[pallet::storage]
+    #[pallet::getter(fn breaker_trip)]
+    pub type BreakerTrip<T: Config> =
+        StorageValue<_, CircuitBreakerTrip<T::BlockNumber, T::VaultId>>;
+
+    /// The operational metrics of the strategy. Funds never leave the vaults, so only rebalances
+    /// are recorded.
+    #[pallet::storage]
+    pub type Health<T: Config> = StorageValue<_, StrategyHealth<T::BlockNumber, T::Balance>>;
+
     // ---------------------------------------------------------------------------------------------
     //                                           Genesis config
     // ---------------------------------------------------------------------------------------------
@@ -178,6 +202,7 @@ pub mod pallet {
     impl<T: Config> GenesisBuild<T> for GenesisConfig {
         fn build(&self) {
             Halted::<T>::put(self.is_halted);
+            AssociatedVaults::<T>::put(BoundedBTreeSet::new());
         }
     }
 
@@ -195,11 +220,52 @@ pub mod pallet {
             vault_id: T::VaultId,
         },
 
+        /// Vault successfully dissociated from this strategy.
+        DissociatedVault {
+            /// Vault ID of dissociated vault.
+            vault_id: T::VaultId,
+        },
+
         /// The event is deposited when the strategy is halted.
         Halted,
 
         /// The event is deposited when the strategy is started again after halting.
         Unhalted,
+
+        /// The event is deposited when a single vault of the strategy is halted.
+        VaultHalted {
+            /// Vault ID of halted vault.
+            vault_id: T::VaultId,
+        },
+
+        /// The event is deposited when a single vault of the strategy is started again after
+        /// halting.
+        VaultStarted {
+            /// Vault ID of started vault.
+            vault_id: T::VaultId,
+        },
+
+        /// The event is deposited when an associated vault could not be reached during a
+        /// rebalance.
+        UnableToRebalanceVault {
+            /// Vault ID of the vault.
+            vault_id: T::VaultId,
+        },
+
+        /// Governance updated the thresholds of the circuit breaker.
+        CircuitBreakerThresholdsUpdated {
+            /// The new thresholds, `None` if the circuit breaker is disabled.
+            thresholds: Option<CircuitBreakerThresholds<T::BlockNumber>>,
+        },
+
+        /// The circuit breaker halted the strategy.
+        CircuitBreakerTripped {
+            /// Why the circuit breaker tripped.
+            reason: TripReason<T::VaultId>,
+        },
+
+        /// Governance reset the tripped circuit breaker, the strategy can be started again.
+        CircuitBreakerReset,
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -222,6 +288,15 @@ pub mod pallet {
         /// Occurs when the strategy is halted, and someone is trying to perform any operations
         /// (only rebalancing actually) with it
         Halted,
+
+        /// The Vault is not associated with this strategy. See [`AssociatedVaults`] for details.
+        VaultNotAssociated,
+
+        /// The circuit breaker tripped, it must be reset before the strategy is started again.
+        CircuitBreakerTripped,
+
+        /// The circuit breaker is not tripped. See [`BreakerTrip`] for details.
+        CircuitBreakerNotTripped,
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -243,6 +318,32 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Set the thresholds above which the strategy halts itself, or disable the circuit
+        /// breaker when `thresholds` is `None`.
+        ///
+        /// Emits [`CircuitBreakerThresholdsUpdated`](Event::CircuitBreakerThresholdsUpdated)
+        /// event when successful.
+        #[pallet::weight(T::WeightInfo::set_circuit_breaker_thresholds())]
+        pub fn set_circuit_breaker_thresholds(
+            origin: OriginFor<T>,
+            thresholds: Option<CircuitBreakerThresholds<T::BlockNumber>>,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            BreakerThresholds::<T>::set(thresholds);
+            Self::deposit_event(Event::CircuitBreakerThresholdsUpdated { thresholds });
+            Ok(().into())
+        }
+
+        /// Reset the tripped circuit breaker, so that the strategy can be started again.
+        ///
+        /// Emits [`CircuitBreakerReset`](Event::CircuitBreakerReset) event when successful.
+        #[pallet::weight(T::WeightInfo::reset_circuit_breaker())]
+        pub fn reset_circuit_breaker(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Self::do_reset_circuit_breaker()?;
+            Ok(().into())
+        }
+
         /// Halt the strategy.
         ///
         /// Emits [`Halted`](Event::Halted) event when successful.
@@ -271,6 +372,8 @@ pub mod pallet {
     impl<T: Config> InstrumentalProtocolStrategy for Pallet<T> {
         type AccountId = T::AccountId;
         type AssetId = T::AssetId;
+        type Balance = T::Balance;
+        type BlockNumber = T::BlockNumber;
         type PoolId = T::PoolId;
         type VaultId = T::VaultId;
 
@@ -299,12 +402,77 @@ pub mod pallet {
             })
         }
 
+        #[transactional]
+        fn dissociate_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            AssociatedVaults::<T>::try_mutate(|vaults| {
+                let vaults = vaults.as_mut().ok_or(Error::<T>::StorageIsNotInitialized)?;
+                ensure!(vaults.remove(vault_id), Error::<T>::VaultNotAssociated);
+
+                Self::deposit_event(Event::DissociatedVault {
+                    vault_id: *vault_id,
+                });
+
+                Ok(())
+            })
+        }
+
         fn rebalance() -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            let vaults = AssociatedVaults::<T>::get().ok_or(Error::<T>::StorageIsNotInitialized)?;
+            let mut failed = false;
+            for vault_id in vaults.iter() {
+                if !Self::is_reachable(vault_id) {
+                    failed = true;
+                    Self::deposit_event(Event::UnableToRebalanceVault {
+                        vault_id: *vault_id,
+                    });
+                }
+            }
+
+            if failed {
+                Self::record_rebalance_failure();
+                Self::check_consecutive_failures();
+            } else {
+                Self::record_rebalance_success();
+            }
+            Ok(())
+        }
+
+        fn rebalance_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+
+            // The failure is kept by succeeding, so that it counts towards the circuit breaker
+            if !Self::is_reachable(vault_id) {
+                Self::record_rebalance_failure();
+                Self::deposit_event(Event::UnableToRebalanceVault {
+                    vault_id: *vault_id,
+                });
+                Self::check_consecutive_failures();
+                return Ok(());
+            }
+            Self::record_rebalance_success();
+            Ok(())
+        }
+
+        fn assets_under_management(vault_id: &Self::VaultId) -> Result<T::Balance, DispatchError> {
+            Self::ensure_associated(vault_id)?;
+            // Funds are never deployed anywhere, they all stay in the vault
+            Ok(T::Balance::zero())
+        }
+
+        fn allot(vault_id: &Self::VaultId, _amount: T::Balance) -> DispatchResult {
+            Self::ensure_associated(vault_id)?;
+            // The funds allotted to the strategy are the ones left in the vault
             Ok(())
         }
 
-        fn get_apy(_asset: Self::AssetId) -> Result<u128, DispatchError> {
-            Ok(0_u128)
+        fn get_apy(_asset: Self::AssetId) -> Result<Apy<T::BlockNumber>, DispatchError> {
+            // Funds never leave the vault, so the APY is exactly zero at any block
+            Ok(Apy::new(
+                FixedU128::zero(),
+                frame_system::Pallet::<T>::block_number(),
+            ))
         }
 
         #[transactional]
@@ -322,6 +490,10 @@ pub mod pallet {
 
         #[transactional]
         fn start() -> DispatchResult {
+            ensure!(
+                !BreakerTrip::<T>::exists(),
+                Error::<T>::CircuitBreakerTripped
+            );
             for vault_id in AssociatedVaults::<T>::get()
                 .ok_or(Error::<T>::StorageIsNotInitialized)?
                 .iter()
@@ -333,8 +505,133 @@ pub mod pallet {
             Ok(())
         }
 
+        #[transactional]
+        fn halt_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            Self::ensure_associated(vault_id)?;
+            <T::Vault as CapabilityVault>::stop(vault_id)?;
+            Self::deposit_event(Event::VaultHalted {
+                vault_id: *vault_id,
+            });
+            Ok(())
+        }
+
+        #[transactional]
+        fn start_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            // Vaults of a halted strategy are started along with it
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            <T::Vault as CapabilityVault>::start(vault_id)?;
+            Self::deposit_event(Event::VaultStarted {
+                vault_id: *vault_id,
+            });
+            Ok(())
+        }
+
         fn is_halted() -> Result<bool, DispatchError> {
-            Halted::<T>::get().ok_or_else(|| Error::<T>::StorageIsNotInitialized.into())
+            // A flag never set, e.g. after a runtime upgrade without genesis, is a running strategy
+            Ok(Halted::<T>::get().unwrap_or(false))
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    //                                         Strategy Metadata
+    // ---------------------------------------------------------------------------------------------
+
+    impl<T: Config> InstrumentalStrategyMetadata for Pallet<T> {
+        type AssetId = T::AssetId;
+        type Balance = T::Balance;
+
+        fn metadata() -> StrategyMetadata<T::AssetId, T::Balance> {
+            StrategyMetadata {
+                name: b"default".to_vec(),
+                supported_assets: SupportedAssets::All,
+                risk_score: Percent::zero(),
+                synchronous_unwind: true,
+                max_capacity: None,
+            }
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    //                                      Low Level Functionality
+    // ---------------------------------------------------------------------------------------------
+
+    impl<T: Config> Pallet<T> {
+        /// The operational metrics of the strategy, see [`Health`].
+        pub fn health() -> StrategyHealth<T::BlockNumber, T::Balance> {
+            Health::<T>::get().unwrap_or_default()
+        }
+
+        fn ensure_associated(vault_id: &T::VaultId) -> DispatchResult {
+            let vaults = AssociatedVaults::<T>::get().ok_or(Error::<T>::StorageIsNotInitialized)?;
+            ensure!(vaults.contains(vault_id), Error::<T>::VaultNotAssociated);
+            Ok(())
+        }
+
+        fn do_reset_circuit_breaker() -> DispatchResult {
+            ensure!(
+                BreakerTrip::<T>::take().is_some(),
+                Error::<T>::CircuitBreakerNotTripped
+            );
+            Health::<T>::mutate(|health| {
+                if let Some(health) = health {
+                    health.consecutive_failures = 0;
+                }
+            });
+            Self::deposit_event(Event::CircuitBreakerReset);
+            Ok(())
+        }
+
+        /// Whether the funds of `vault_id` can still be queried, the only thing a rebalance of
+        /// the strategy relies on.
+        fn is_reachable(vault_id: &T::VaultId) -> bool {
+            let account_id = <Self as InstrumentalProtocolStrategy>::account_id();
+            T::Vault::available_funds(vault_id, &account_id).is_ok()
+        }
+
+        fn record_rebalance_success() {
+            Health::<T>::mutate(|health| {
+                let health = health.get_or_insert_with(Default::default);
+                health.last_rebalance = Some(frame_system::Pallet::<T>::block_number());
+                health.consecutive_failures = 0;
+            });
+        }
+
+        fn record_rebalance_failure() {
+            Health::<T>::mutate(|health| {
+                let health = health.get_or_insert_with(Default::default);
+                health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+            });
+        }
+
+        fn check_consecutive_failures() {
+            if let Some(thresholds) = Self::breaker_thresholds() {
+                let max_consecutive_failures = thresholds.max_consecutive_failures;
+                if max_consecutive_failures > 0
+                    && Self::health().consecutive_failures >= max_consecutive_failures
+                {
+                    Self::trip_circuit_breaker(TripReason::ConsecutiveFailures);
+                }
+            }
+        }
+
+        /// Halts the strategy through its regular [`halt`](InstrumentalProtocolStrategy::halt)
+        /// path, and keeps it halted until governance resets the circuit breaker.
+        fn trip_circuit_breaker(reason: TripReason<T::VaultId>) {
+            if BreakerTrip::<T>::exists() {
+                return;
+            }
+            // A vault that cannot be stopped must not keep the strategy running
+            if <Self as InstrumentalProtocolStrategy>::halt().is_err() {
+                Halted::<T>::put(true);
+                Self::deposit_event(Event::Halted);
+            }
+
+            BreakerTrip::<T>::put(CircuitBreakerTrip {
+                at: frame_system::Pallet::<T>::block_number(),
+                reason,
+            });
+            Self::deposit_event(Event::CircuitBreakerTripped { reason });
         }
     }
 }
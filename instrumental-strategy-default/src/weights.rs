@@ -8,6 +8,8 @@ pub trait WeightInfo {
     fn associate_vault() -> Weight;
     fn halt() -> Weight;
     fn start() -> Weight;
+    fn set_circuit_breaker_thresholds() -> Weight;
+    fn reset_circuit_breaker() -> Weight;
 }
 
 /// Weights for the pallet using the Substrate node and recommended hardware.
@@ -24,6 +26,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWight<T> {
     fn start() -> Weight {
         10_000 as Weight
     }
+
+    fn set_circuit_breaker_thresholds() -> Weight {
+        10_000 as Weight
+    }
+
+    fn reset_circuit_breaker() -> Weight {
+        10_000 as Weight
+    }
 }
 
 // For backwards compatibility and tests
@@ -39,4 +49,12 @@ impl WeightInfo for () {
     fn start() -> Weight {
         10_000 as Weight
     }
+
+    fn set_circuit_breaker_thresholds() -> Weight {
+        10_000 as Weight
+    }
+
+    fn reset_circuit_breaker() -> Weight {
+        10_000 as Weight
+    }
 }
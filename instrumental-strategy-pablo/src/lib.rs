@@ -32,7 +32,7 @@ pub mod pallet {
     // ---------------------------------------------------------------------------------------------
 
     use codec::{Codec, FullCodec};
-    use composable_support::math::safe::{safe_multiply_by_rational, SafeDiv, SafeSub};
+    use composable_support::math::safe::{safe_multiply_by_rational, SafeDiv, SafeMul, SafeSub};
     use composable_traits::{
         dex::Amm,
         vault::{CapabilityVault, FundsAvailability, StrategicVault, Vault},
@@ -44,16 +44,23 @@ pub mod pallet {
         traits::fungibles::{Inspect, Mutate, MutateHold, Transfer},
         transactional, Blake2_128Concat, PalletId, RuntimeDebug,
     };
-    use frame_system::pallet_prelude::OriginFor;
+    use frame_system::{ensure_signed, pallet_prelude::OriginFor};
     use sp_runtime::{
         traits::{
             AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Convert,
-            Zero,
+            Saturating, Zero,
+        },
+        PerThing, Percent,
+    };
+    use sp_std::{fmt::Debug, vec::Vec};
+    use traits::{
+        instrumental::State,
+        strategy::{
+            Apy, CircuitBreakerThresholds, CircuitBreakerTrip, InstrumentalProtocolStrategy,
+            InstrumentalStrategyMetadata, StrategyHealth, StrategyMetadata, SupportedAssets,
+            TripReason,
         },
-        Percent,
     };
-    use sp_std::fmt::Debug;
-    use traits::{instrumental::State, strategy::InstrumentalProtocolStrategy};
 
     use crate::weights::WeightInfo;
 
@@ -167,6 +174,29 @@ pub mod pallet {
 
         /// Conversion function from [`Self::Balance`] to u128 and from u128 to [`Self::Balance`].
         type Convert: Convert<Self::Balance, u128> + Convert<u128, Self::Balance>;
+
+        /// The risk of providing liquidity to Pablo pools, reported in the
+        /// [`StrategyMetadata`](traits::strategy::StrategyMetadata) of the strategy.
+        #[pallet::constant]
+        type RiskScore: Get<Percent>;
+
+        /// The number of blocks after which any account may rebalance a vault through
+        /// [`poke_rebalance`](Pallet::poke_rebalance), even if it is within its target band.
+        #[pallet::constant]
+        type RebalanceInterval: Get<Self::BlockNumber>;
+
+        /// The amount of funds a vault must want to move for it to be considered out of its target
+        /// band.
+        #[pallet::constant]
+        type RebalanceThreshold: Get<Self::Balance>;
+
+        /// The share of the yield of a vault paid to the account poking its rebalance.
+        #[pallet::constant]
+        type KeeperBountyShare: Get<Percent>;
+
+        /// The maximum bounty paid for a single poked rebalance.
+        #[pallet::constant]
+        type MaxKeeperBounty: Get<Self::Balance>;
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -204,6 +234,48 @@ pub mod pallet {
     #[pallet::storage]
     pub type Halted<T: Config> = StorageValue<_, bool>;
 
+    /// The thresholds above which the strategy halts itself, the circuit breaker being disabled
+    /// when `None`.
+    #[pallet::storage]
+    #[pallet::getter(fn breaker_thresholds)]
+    pub type BreakerThresholds<T: Config> =
+        StorageValue<_, CircuitBreakerThresholds<T::BlockNumber>>;
+
+    /// The tripped circuit breaker keeping the strategy halted, until governance resets it.
+    #[pallet::storage]
+    #[pallet::getter(fn breaker_trip)]
+    pub type BreakerTrip<T: Config> =
+        StorageValue<_, CircuitBreakerTrip<T::BlockNumber, T::VaultId>>;
+
+    /// The value each vault held at the start of the current circuit breaker window, adjusted by
+    /// the funds moved between the vault and the strategy since.
+    #[pallet::storage]
+    pub type ValueCheckpoints<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::VaultId, (T::BlockNumber, T::Balance)>;
+
+    /// The funds taken from each vault and still deployed in Pablo, anything managed above it is
+    /// yield.
+    #[pallet::storage]
+    #[pallet::getter(fn principals)]
+    pub type Principals<T: Config> = StorageMap<_, Blake2_128Concat, T::VaultId, T::Balance>;
+
+    /// The operational metrics of the strategy.
+    #[pallet::storage]
+    pub type Health<T: Config> = StorageValue<_, StrategyHealth<T::BlockNumber, T::Balance>>;
+
+    /// The last block at which each vault was successfully rebalanced.
+    #[pallet::storage]
+    #[pallet::getter(fn last_rebalanced)]
+    pub type LastRebalanced<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::VaultId, T::BlockNumber>;
+
+    /// The funds allotted to each vault through
+    /// [`allot`](InstrumentalProtocolStrategy::allot), kept by the rebalances of the vault in
+    /// place of the allocation of the vault.
+    #[pallet::storage]
+    #[pallet::getter(fn allotments)]
+    pub type Allotments<T: Config> = StorageMap<_, Blake2_128Concat, T::VaultId, T::Balance>;
+
     // ---------------------------------------------------------------------------------------------
     //                                           Genesis config
     // ---------------------------------------------------------------------------------------------
@@ -235,6 +307,12 @@ pub mod pallet {
             vault_id: T::VaultId,
         },
 
+        /// Vault successfully dissociated from this strategy.
+        DissociatedVault {
+            /// Vault ID of dissociated vault.
+            vault_id: T::VaultId,
+        },
+
         /// Vault successfully rebalanced.
         RebalancedVault {
             /// Vault ID of rebalanced vault.
@@ -290,6 +368,63 @@ pub mod pallet {
 
         /// The event is deposited when the strategy is started again after halting.
         Unhalted,
+
+        /// The event is deposited when a single vault of the strategy is halted.
+        VaultHalted {
+            /// Vault ID of halted vault.
+            vault_id: T::VaultId,
+        },
+
+        /// The event is deposited when a single vault of the strategy is started again after
+        /// halting.
+        VaultStarted {
+            /// Vault ID of started vault.
+            vault_id: T::VaultId,
+        },
+
+        /// The event is deposited when an account is paid for poking the rebalance of a vault.
+        KeeperRewarded {
+            /// Vault ID of rebalanced vault.
+            vault_id: T::VaultId,
+            /// Account ID of the account that poked the rebalance.
+            keeper: T::AccountId,
+            /// The bounty paid out of the yield of the vault.
+            bounty: T::Balance,
+        },
+
+        /// The event is deposited when the bounty for poking the rebalance of a vault could not be
+        /// paid, the rebalance itself being kept.
+        KeeperRewardFailed {
+            /// Vault ID of rebalanced vault.
+            vault_id: T::VaultId,
+            /// Account ID of the account that poked the rebalance.
+            keeper: T::AccountId,
+            /// The error the payment failed with.
+            error: DispatchError,
+        },
+
+        /// The funds managed on behalf of a vault were set to an allotted amount.
+        VaultAllotted {
+            /// Vault ID of the allotted vault.
+            vault_id: T::VaultId,
+            /// The funds allotted to the vault.
+            amount: T::Balance,
+        },
+
+        /// Governance updated the thresholds of the circuit breaker.
+        CircuitBreakerThresholdsUpdated {
+            /// The new thresholds, `None` if the circuit breaker is disabled.
+            thresholds: Option<CircuitBreakerThresholds<T::BlockNumber>>,
+        },
+
+        /// The circuit breaker halted the strategy.
+        CircuitBreakerTripped {
+            /// Why the circuit breaker tripped.
+            reason: TripReason<T::VaultId>,
+        },
+
+        /// Governance reset the tripped circuit breaker, the strategy can be started again.
+        CircuitBreakerReset,
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -322,6 +457,19 @@ pub mod pallet {
 
         /// No strategy is associated with the Vault.
         NoStrategies,
+
+        /// The Vault is not associated with this strategy. See [`AssociatedVaults`] for details.
+        VaultNotAssociated,
+
+        /// The Vault is neither due for a rebalance nor out of its target band. See
+        /// [`Config::RebalanceInterval`] and [`Config::RebalanceThreshold`] for details.
+        RebalanceNotNeeded,
+
+        /// The circuit breaker tripped, it must be reset before the strategy is started again.
+        CircuitBreakerTripped,
+
+        /// The circuit breaker is not tripped. See [`BreakerTrip`] for details.
+        CircuitBreakerNotTripped,
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -376,6 +524,51 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Rebalance a single vault on behalf of any signed account, as long as the vault is due
+        /// or out of its target band.
+        ///
+        /// The caller is paid a bounty out of the yield of the vault when it was due and funds were
+        /// moved. A rebalance that fails does not fail the call, it emits
+        /// [`UnableToRebalanceVault`](Event::UnableToRebalanceVault) and counts towards the
+        /// circuit breaker instead.
+        ///
+        /// Emits [`RebalancedVault`](Event::RebalancedVault) event when successful.
+        #[pallet::weight(T::WeightInfo::poke_rebalance())]
+        pub fn poke_rebalance(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+        ) -> DispatchResultWithPostInfo {
+            let keeper = ensure_signed(origin)?;
+            Self::do_poke_rebalance(&keeper, &vault_id)?;
+            Ok(().into())
+        }
+
+        /// Set the thresholds above which the strategy halts itself, or disable the circuit
+        /// breaker when `thresholds` is `None`.
+        ///
+        /// Emits [`CircuitBreakerThresholdsUpdated`](Event::CircuitBreakerThresholdsUpdated)
+        /// event when successful.
+        #[pallet::weight(T::WeightInfo::set_circuit_breaker_thresholds())]
+        pub fn set_circuit_breaker_thresholds(
+            origin: OriginFor<T>,
+            thresholds: Option<CircuitBreakerThresholds<T::BlockNumber>>,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            BreakerThresholds::<T>::set(thresholds);
+            Self::deposit_event(Event::CircuitBreakerThresholdsUpdated { thresholds });
+            Ok(().into())
+        }
+
+        /// Reset the tripped circuit breaker, so that the strategy can be started again.
+        ///
+        /// Emits [`CircuitBreakerReset`](Event::CircuitBreakerReset) event when successful.
+        #[pallet::weight(T::WeightInfo::reset_circuit_breaker())]
+        pub fn reset_circuit_breaker(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Self::do_reset_circuit_breaker()?;
+            Ok(().into())
+        }
+
         /// Halt the strategy.
         ///
         /// Emits [`Halted`](Event::Halted) event when successful.
@@ -404,6 +597,8 @@ pub mod pallet {
     impl<T: Config> InstrumentalProtocolStrategy for Pallet<T> {
         type AccountId = T::AccountId;
         type AssetId = T::AssetId;
+        type Balance = T::Balance;
+        type BlockNumber = T::BlockNumber;
         type PoolId = T::PoolId;
         type VaultId = T::VaultId;
 
@@ -431,18 +626,42 @@ pub mod pallet {
             })
         }
 
+        #[transactional]
+        fn dissociate_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            AssociatedVaults::<T>::try_mutate(|vaults| -> DispatchResult {
+                ensure!(vaults.remove(vault_id), Error::<T>::VaultNotAssociated);
+                Allotments::<T>::remove(vault_id);
+
+                Self::deposit_event(Event::DissociatedVault {
+                    vault_id: *vault_id,
+                });
+
+                Ok(())
+            })
+        }
+
         #[transactional]
         fn rebalance() -> DispatchResult {
             if Self::is_halted()? {
                 return Err(Error::<T>::Halted.into());
             }
+            let mut failed = false;
+            let mut dropped = None;
             AssociatedVaults::<T>::try_mutate(|vaults| -> DispatchResult {
                 vaults.iter().for_each(|vault_id| {
                     if Self::do_rebalance(vault_id).is_ok() {
+                        LastRebalanced::<T>::insert(
+                            vault_id,
+                            frame_system::Pallet::<T>::block_number(),
+                        );
                         Self::deposit_event(Event::RebalancedVault {
                             vault_id: *vault_id,
                         });
+                        if Self::value_dropped(vault_id) && dropped.is_none() {
+                            dropped = Some(*vault_id);
+                        }
                     } else {
+                        failed = true;
                         Self::deposit_event(Event::UnableToRebalanceVault {
                             vault_id: *vault_id,
                         });
@@ -450,11 +669,84 @@ pub mod pallet {
                 });
 
                 Ok(())
-            })
+            })?;
+
+            if failed {
+                Self::record_rebalance_failure();
+            } else {
+                Self::record_rebalance_success();
+            }
+
+            // The strategy is halted once every vault had the chance to be rebalanced
+            if let Some(vault_id) = dropped {
+                Self::trip_circuit_breaker(TripReason::ValueDrop { vault_id });
+            } else if failed {
+                Self::check_consecutive_failures();
+            }
+            Ok(())
         }
 
-        fn get_apy(_asset: Self::AssetId) -> Result<u128, DispatchError> {
-            Ok(0)
+        fn rebalance_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+
+            // The failure is kept by succeeding, so that it counts towards the circuit breaker
+            // even when the caller rolls its own changes back
+            if Self::do_rebalance_vault(vault_id).is_err() {
+                Self::record_rebalance_failure();
+                Self::deposit_event(Event::UnableToRebalanceVault {
+                    vault_id: *vault_id,
+                });
+                Self::check_consecutive_failures();
+                return Ok(());
+            }
+            Self::record_rebalance_success();
+            Self::deposit_event(Event::RebalancedVault {
+                vault_id: *vault_id,
+            });
+            if Self::value_dropped(vault_id) {
+                Self::trip_circuit_breaker(TripReason::ValueDrop {
+                    vault_id: *vault_id,
+                });
+            }
+
+            Ok(())
+        }
+
+        fn assets_under_management(vault_id: &Self::VaultId) -> Result<T::Balance, DispatchError> {
+            Self::ensure_associated(vault_id)?;
+
+            let asset_id = T::Vault::asset_id(vault_id)?;
+            let pool_id = Self::pools(asset_id)
+                .ok_or(Error::<T>::PoolNotFound)?
+                .pool_id;
+            let strategy_vaults = T::Vault::get_strategies(vault_id)?;
+            let strategy_vault_account = strategy_vaults.last().ok_or(Error::<T>::NoStrategies)?.0;
+
+            let lp_token_id = T::Pablo::lp_token(pool_id)?;
+            let balance_of_lp_token = T::Currency::balance(lp_token_id, &strategy_vault_account);
+            let lp_price = T::Pablo::get_price_of_lp_token(pool_id)?;
+
+            Ok(balance_of_lp_token.safe_mul(&lp_price)?)
+        }
+
+        #[transactional]
+        fn allot(vault_id: &Self::VaultId, amount: T::Balance) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+
+            Allotments::<T>::insert(vault_id, amount);
+            Self::do_rebalance(vault_id)?;
+            Self::deposit_event(Event::VaultAllotted {
+                vault_id: *vault_id,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        fn get_apy(_asset: Self::AssetId) -> Result<Apy<T::BlockNumber>, DispatchError> {
+            Ok(Apy::zero())
         }
 
         #[transactional]
@@ -469,6 +761,10 @@ pub mod pallet {
 
         #[transactional]
         fn start() -> DispatchResult {
+            ensure!(
+                !BreakerTrip::<T>::exists(),
+                Error::<T>::CircuitBreakerTripped
+            );
             for vault_id in AssociatedVaults::<T>::get().iter() {
                 <T::Vault as CapabilityVault>::start(vault_id)?;
             }
@@ -477,8 +773,57 @@ pub mod pallet {
             Ok(())
         }
 
+        #[transactional]
+        fn halt_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            Self::ensure_associated(vault_id)?;
+            <T::Vault as CapabilityVault>::stop(vault_id)?;
+            Self::deposit_event(Event::VaultHalted {
+                vault_id: *vault_id,
+            });
+            Ok(())
+        }
+
+        #[transactional]
+        fn start_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            // Vaults of a halted strategy are started along with it
+            ensure!(
+                !BreakerTrip::<T>::exists(),
+                Error::<T>::CircuitBreakerTripped
+            );
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            <T::Vault as CapabilityVault>::start(vault_id)?;
+            Self::deposit_event(Event::VaultStarted {
+                vault_id: *vault_id,
+            });
+            Ok(())
+        }
+
         fn is_halted() -> Result<bool, DispatchError> {
-            Halted::<T>::get().ok_or_else(|| Error::<T>::StorageIsNotInitialized.into())
+            // A flag never set, e.g. after a runtime upgrade without genesis, is a running strategy
+            Ok(Halted::<T>::get().unwrap_or(false))
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    //                                         Strategy Metadata
+    // ---------------------------------------------------------------------------------------------
+
+    impl<T: Config> InstrumentalStrategyMetadata for Pallet<T> {
+        type AssetId = T::AssetId;
+        type Balance = T::Balance;
+
+        fn metadata() -> StrategyMetadata<T::AssetId, T::Balance> {
+            StrategyMetadata {
+                name: b"pablo".to_vec(),
+                // Any asset can be paired in a Pablo pool, the pool itself is set after the vault
+                // has been associated
+                supported_assets: SupportedAssets::All,
+                risk_score: T::RiskScore::get(),
+                // Liquidity is removed from the pool and deposited back into the vault in one call
+                synchronous_unwind: true,
+                max_capacity: None,
+            }
         }
     }
 
@@ -487,6 +832,141 @@ pub mod pallet {
     // ---------------------------------------------------------------------------------------------
 
     impl<T: Config> Pallet<T> {
+        /// The operational metrics of the strategy, see [`Health`].
+        pub fn health() -> StrategyHealth<T::BlockNumber, T::Balance> {
+            Health::<T>::get().unwrap_or_default()
+        }
+
+        /// Returns the pool each asset is deployed into, along with its transfer state.
+        pub fn pool_states() -> Vec<(T::AssetId, T::PoolId, State)> {
+            Pools::<T>::iter()
+                .map(|(asset_id, pool)| (asset_id, pool.pool_id, pool.state))
+                .collect()
+        }
+
+        fn ensure_associated(vault_id: &T::VaultId) -> DispatchResult {
+            ensure!(
+                AssociatedVaults::<T>::get().contains(vault_id),
+                Error::<T>::VaultNotAssociated
+            );
+            Ok(())
+        }
+
+        fn do_poke_rebalance(keeper: &T::AccountId, vault_id: &T::VaultId) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let is_due = Self::last_rebalanced(vault_id).map_or(true, |last| {
+                now >= last.saturating_add(T::RebalanceInterval::get())
+            });
+            let moves_funds = Self::is_out_of_band(vault_id)?;
+            ensure!(is_due || moves_funds, Error::<T>::RebalanceNotNeeded);
+
+            // The failure is kept by succeeding, so that it counts towards the circuit breaker
+            if Self::do_rebalance_vault(vault_id).is_err() {
+                Self::record_rebalance_failure();
+                Self::deposit_event(Event::UnableToRebalanceVault {
+                    vault_id: *vault_id,
+                });
+                Self::check_consecutive_failures();
+                return Ok(());
+            }
+            Self::record_rebalance_success();
+            Self::deposit_event(Event::RebalancedVault {
+                vault_id: *vault_id,
+            });
+
+            // A rebalance that moved nothing is not worth paying for, and one that is not due yet
+            // could have been provoked by the keeper depositing into the vault
+            if is_due && moves_funds {
+                // The keeper is not paid rather than the rebalance being undone
+                if let Err(error) = Self::pay_keeper_bounty(keeper, vault_id) {
+                    Self::deposit_event(Event::KeeperRewardFailed {
+                        vault_id: *vault_id,
+                        keeper: keeper.clone(),
+                        error,
+                    });
+                }
+            }
+            if Self::value_dropped(vault_id) {
+                Self::trip_circuit_breaker(TripReason::ValueDrop {
+                    vault_id: *vault_id,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Rebalances `vault_id` as a whole, so that a failure leaves nothing behind but the
+        /// failure counted by the caller.
+        #[transactional]
+        fn do_rebalance_vault(vault_id: &T::VaultId) -> DispatchResult {
+            Self::do_rebalance(vault_id)?;
+            LastRebalanced::<T>::insert(vault_id, frame_system::Pallet::<T>::block_number());
+            Ok(())
+        }
+
+        fn is_out_of_band(vault_id: &T::VaultId) -> Result<bool, DispatchError> {
+            Ok(match Self::funds_availability(vault_id)? {
+                FundsAvailability::Withdrawable(balance)
+                | FundsAvailability::Depositable(balance) => balance > T::RebalanceThreshold::get(),
+                FundsAvailability::MustLiquidate => true,
+                FundsAvailability::None => false,
+            })
+        }
+
+        /// Pays `keeper` a [`Config::KeeperBountyShare`] of the yield of `vault_id`, capped at
+        /// [`Config::MaxKeeperBounty`]. Vaults without a known principal have no yield to pay from.
+        #[transactional]
+        fn pay_keeper_bounty(keeper: &T::AccountId, vault_id: &T::VaultId) -> DispatchResult {
+            let principal = match Self::principals(vault_id) {
+                Some(principal) => principal,
+                None => return Ok(()),
+            };
+            let assets = <Self as InstrumentalProtocolStrategy>::assets_under_management(vault_id)?;
+            let vault_yield = assets.saturating_sub(principal);
+            let bounty = T::KeeperBountyShare::get()
+                .mul_floor(vault_yield)
+                .min(T::MaxKeeperBounty::get());
+            if bounty.is_zero() {
+                return Ok(());
+            }
+
+            let asset_id = T::Vault::asset_id(vault_id)?;
+            let pool_id = Self::pools(asset_id)
+                .ok_or(Error::<T>::PoolNotFound)?
+                .pool_id;
+            let strategy_vaults = T::Vault::get_strategies(vault_id)?;
+            let strategy_vault_account = strategy_vaults.last().ok_or(Error::<T>::NoStrategies)?.0;
+
+            let lp_price = T::Pablo::get_price_of_lp_token(pool_id)?;
+            let lp_redeem = bounty.safe_div(&lp_price)?;
+            if lp_redeem.is_zero() {
+                return Ok(());
+            }
+            let balance_before = T::Currency::balance(asset_id, &strategy_vault_account);
+            T::Pablo::remove_liquidity_single_asset(
+                &strategy_vault_account,
+                pool_id,
+                lp_redeem,
+                T::Balance::zero(),
+            )?;
+            let redeemed = T::Currency::balance(asset_id, &strategy_vault_account)
+                .saturating_sub(balance_before);
+            let bounty = bounty.min(redeemed);
+            T::Currency::transfer(asset_id, &strategy_vault_account, keeper, bounty, false)?;
+            Self::adjust_checkpoint(vault_id, T::Balance::zero(), bounty);
+
+            Self::deposit_event(Event::KeeperRewarded {
+                vault_id: *vault_id,
+                keeper: keeper.clone(),
+                bounty,
+            });
+
+            Ok(())
+        }
+
         #[transactional]
         fn do_set_pool_id_for_asset(
             asset_id: T::AssetId,
@@ -585,7 +1065,7 @@ pub mod pallet {
             let strategy_vault_account = strategy_vaults.last().ok_or(Error::<T>::NoStrategies)?.0;
             let pool_id_and_state = Self::pools(asset_id).ok_or(Error::<T>::PoolNotFound)?;
             let pool_id = pool_id_and_state.pool_id;
-            match T::Vault::available_funds(vault_id, &Self::account_id())? {
+            match Self::funds_availability(vault_id)? {
                 FundsAvailability::Withdrawable(balance) => {
                     Self::withdraw(vault_id, &strategy_vault_account, pool_id, balance)?;
                     Self::deposit_event(Event::WithdrawFunctionalityOccuredDuringRebalance {
@@ -613,6 +1093,33 @@ pub mod pallet {
             Ok(())
         }
 
+        /// The funds to move between `vault_id` and the strategy: the ones reaching the allotment
+        /// of the vault when it has one, see [`Allotments`], the ones the vault asks for otherwise.
+        /// A vault that must be liquidated is liquidated whatever its allotment.
+        fn funds_availability(
+            vault_id: &T::VaultId,
+        ) -> Result<FundsAvailability<T::Balance>, DispatchError> {
+            let availability = T::Vault::available_funds(vault_id, &Self::account_id())?;
+            let allotment = match Self::allotments(vault_id) {
+                Some(allotment) if !matches!(availability, FundsAvailability::MustLiquidate) => {
+                    allotment
+                }
+                _ => return Ok(availability),
+            };
+
+            let managed =
+                <Self as InstrumentalProtocolStrategy>::assets_under_management(vault_id)?;
+            Ok(if allotment.is_zero() && !managed.is_zero() {
+                FundsAvailability::MustLiquidate
+            } else if allotment > managed {
+                FundsAvailability::Withdrawable(allotment.saturating_sub(managed))
+            } else if allotment < managed {
+                FundsAvailability::Depositable(managed.saturating_sub(allotment))
+            } else {
+                FundsAvailability::None
+            })
+        }
+
         fn withdraw(
             vault_id: &T::VaultId,
             vault_strategy_account: &T::AccountId,
@@ -620,6 +1127,9 @@ pub mod pallet {
             balance: T::Balance,
         ) -> DispatchResult {
             <T::Vault as StrategicVault>::withdraw(vault_id, vault_strategy_account, balance)?;
+            Principals::<T>::mutate(vault_id, |principal| {
+                *principal = Some(principal.unwrap_or_default().saturating_add(balance));
+            });
             T::Pablo::add_liquidity(
                 vault_strategy_account,
                 pool_id,
@@ -627,7 +1137,13 @@ pub mod pallet {
                 T::Balance::zero(),
                 T::Balance::zero(),
                 true,
-            )
+            )?;
+            Health::<T>::mutate(|health| {
+                let health = health.get_or_insert_with(Default::default);
+                health.total_deposited = health.total_deposited.saturating_add(balance);
+            });
+            Self::adjust_checkpoint(vault_id, balance, T::Balance::zero());
+            Ok(())
         }
 
         fn deposit(
@@ -636,15 +1152,33 @@ pub mod pallet {
             pool_id: T::PoolId,
             balance: T::Balance,
         ) -> DispatchResult {
+            let asset_id = T::Vault::asset_id(vault_id)?;
             let lp_price = T::Pablo::get_price_of_lp_token(pool_id)?;
             let lp_redeem = balance.safe_div(&lp_price)?;
+            let balance_before = T::Currency::balance(asset_id, vault_strategy_account);
             T::Pablo::remove_liquidity_single_asset(
                 vault_strategy_account,
                 pool_id,
                 lp_redeem,
                 T::Balance::zero(),
             )?;
-            <T::Vault as StrategicVault>::deposit(vault_id, vault_strategy_account, balance)
+            let redeemed = T::Currency::balance(asset_id, vault_strategy_account)
+                .saturating_sub(balance_before);
+            // The principal expected back is the part of `balance` that had been taken from the
+            // vault, anything redeemed above it is profit and anything below it is a loss
+            let expected_principal =
+                balance.min(Principals::<T>::get(vault_id).unwrap_or_default());
+            Principals::<T>::mutate(vault_id, |principal| {
+                *principal = principal.map(|principal| principal.saturating_sub(balance));
+            });
+            <T::Vault as StrategicVault>::deposit(vault_id, vault_strategy_account, redeemed)?;
+            Self::record_withdrawal(
+                redeemed,
+                redeemed.min(expected_principal),
+                expected_principal,
+            );
+            Self::adjust_checkpoint(vault_id, T::Balance::zero(), redeemed);
+            Ok(())
         }
 
         fn liquidate(
@@ -662,7 +1196,134 @@ pub mod pallet {
             )?;
             let balance =
                 T::Currency::balance(T::Vault::asset_id(vault_id)?, vault_strategy_account);
-            <T::Vault as StrategicVault>::deposit(vault_id, vault_strategy_account, balance)
+            // Everything, yield included, goes back to the vault
+            let principal = Principals::<T>::take(vault_id).unwrap_or_default();
+            <T::Vault as StrategicVault>::deposit(vault_id, vault_strategy_account, balance)?;
+            Self::record_withdrawal(balance, balance.min(principal), principal);
+            Self::adjust_checkpoint(vault_id, T::Balance::zero(), balance);
+            Ok(())
+        }
+
+        /// Records `balance` withdrawn from Pablo and returned to a vault, out of which
+        /// `returned_principal` had been taken from the vault while `principal` was expected back.
+        fn record_withdrawal(
+            balance: T::Balance,
+            returned_principal: T::Balance,
+            principal: T::Balance,
+        ) {
+            Health::<T>::mutate(|health| {
+                let health = health.get_or_insert_with(Default::default);
+                health.total_withdrawn = health.total_withdrawn.saturating_add(balance);
+                health.realized_profit = health
+                    .realized_profit
+                    .saturating_add(balance.saturating_sub(returned_principal));
+                health.realized_loss = health
+                    .realized_loss
+                    .saturating_add(principal.saturating_sub(balance));
+            });
+        }
+
+        fn record_rebalance_success() {
+            Health::<T>::mutate(|health| {
+                let health = health.get_or_insert_with(Default::default);
+                health.last_rebalance = Some(frame_system::Pallet::<T>::block_number());
+                health.consecutive_failures = 0;
+            });
+        }
+
+        fn record_rebalance_failure() {
+            Health::<T>::mutate(|health| {
+                let health = health.get_or_insert_with(Default::default);
+                health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+            });
+        }
+
+        /// Whether the value held for `vault_id` dropped by more than the circuit breaker allows
+        /// within the current window. A new window starts once the current one is over.
+        fn value_dropped(vault_id: &T::VaultId) -> bool {
+            let thresholds = match Self::breaker_thresholds() {
+                Some(thresholds) => thresholds,
+                None => return false,
+            };
+            // A value that cannot be computed cannot be compared
+            let value =
+                match <Self as InstrumentalProtocolStrategy>::assets_under_management(vault_id) {
+                    Ok(value) => value,
+                    Err(_) => return false,
+                };
+
+            let now = frame_system::Pallet::<T>::block_number();
+            match ValueCheckpoints::<T>::get(vault_id) {
+                Some((since, expected)) if now.saturating_sub(since) < thresholds.window => {
+                    let floor =
+                        expected.saturating_sub(thresholds.max_value_drop.mul_floor(expected));
+                    value < floor
+                }
+                _ => {
+                    ValueCheckpoints::<T>::insert(vault_id, (now, value));
+                    false
+                }
+            }
+        }
+
+        /// Moves the value expected for `vault_id` by the funds `added` to and `removed` from it.
+        fn adjust_checkpoint(vault_id: &T::VaultId, added: T::Balance, removed: T::Balance) {
+            ValueCheckpoints::<T>::mutate(vault_id, |checkpoint| {
+                if let Some((_, value)) = checkpoint {
+                    *value = value.saturating_add(added).saturating_sub(removed);
+                }
+            });
+        }
+
+        fn check_consecutive_failures() {
+            if let Some(thresholds) = Self::breaker_thresholds() {
+                let max_consecutive_failures = thresholds.max_consecutive_failures;
+                if max_consecutive_failures > 0
+                    && Self::health().consecutive_failures >= max_consecutive_failures
+                {
+                    Self::trip_circuit_breaker(TripReason::ConsecutiveFailures);
+                }
+            }
+        }
+
+        /// Halts the strategy through its regular [`halt`](InstrumentalProtocolStrategy::halt)
+        /// path, and keeps it halted until governance resets the circuit breaker.
+        fn trip_circuit_breaker(reason: TripReason<T::VaultId>) {
+            if BreakerTrip::<T>::exists() {
+                return;
+            }
+            if let Err(error) = <Self as InstrumentalProtocolStrategy>::halt() {
+                log::error!(
+                    target: "runtime::instrumental-strategy-pablo",
+                    "circuit breaker failed to halt the strategy: {:?}",
+                    error
+                );
+                return;
+            }
+
+            BreakerTrip::<T>::put(CircuitBreakerTrip {
+                at: frame_system::Pallet::<T>::block_number(),
+                reason,
+            });
+            Self::deposit_event(Event::CircuitBreakerTripped { reason });
+        }
+
+        fn do_reset_circuit_breaker() -> DispatchResult {
+            ensure!(
+                BreakerTrip::<T>::take().is_some(),
+                Error::<T>::CircuitBreakerNotTripped
+            );
+            // Values are compared again from the moment the strategy is started
+            for vault_id in AssociatedVaults::<T>::get().iter() {
+                ValueCheckpoints::<T>::remove(vault_id);
+            }
+            Health::<T>::mutate(|health| {
+                if let Some(health) = health {
+                    health.consecutive_failures = 0;
+                }
+            });
+            Self::deposit_event(Event::CircuitBreakerReset);
+            Ok(())
         }
 
         #[transactional]
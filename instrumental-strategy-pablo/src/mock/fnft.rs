@@ -1,41 +1,134 @@
+use codec::Encode;
 use composable_traits::fnft::FinancialNft;
 use frame_support::{
     dispatch::DispatchResult,
-    traits::tokens::nonfungibles::{Create, Inspect, Mutate},
+    ensure,
+    storage::unhashed,
+    traits::tokens::nonfungibles::{Create, Inspect, Mutate, Transfer},
 };
-use sp_runtime::DispatchError;
+use primitives::currency::CurrencyId;
+use sp_core::sr25519::Public;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{DispatchError, TokenError};
 
 use super::account_id::AccountId;
 
+const OWNER_PREFIX: &[u8] = b"MockFnft::Owner";
+const NEXT_ID_PREFIX: &[u8] = b"MockFnft::NextId";
+const COLLECTION_PREFIX: &[u8] = b"MockFnft::Collection";
+const ASSET_ACCOUNT_PREFIX: &[u8] = b"MockFnft::AssetAccount";
+
+/// Keeps track of the collections and owners in the unhashed storage of the test externalities,
+/// so every test starts without any fNFT.
 pub struct MockFnft;
 
+impl MockFnft {
+    fn owner_key(collection: &CurrencyId, item: &u64) -> Vec<u8> {
+        (OWNER_PREFIX, collection, item).encode()
+    }
+
+    fn next_id_key(collection: &CurrencyId) -> Vec<u8> {
+        (NEXT_ID_PREFIX, collection).encode()
+    }
+
+    fn collection_key(collection: &CurrencyId) -> Vec<u8> {
+        (COLLECTION_PREFIX, collection).encode()
+    }
+}
+
 impl Inspect<AccountId> for MockFnft {
-    type CollectionId = ();
-    type ItemId = ();
+    type CollectionId = CurrencyId;
+    type ItemId = u64;
+
+    fn owner(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<AccountId> {
+        unhashed::get(&Self::owner_key(collection, item))
+    }
 
-    fn owner(_collection: &Self::CollectionId, _item: &Self::ItemId) -> Option<AccountId> {
-        unimplemented!()
+    fn collection_owner(collection: &Self::CollectionId) -> Option<AccountId> {
+        unhashed::get(&Self::collection_key(collection))
     }
 }
 
 impl FinancialNft<AccountId> for MockFnft {
-    fn asset_account(_collection: &Self::CollectionId, _instance: &Self::ItemId) -> AccountId {
-        unimplemented!()
+    fn asset_account(collection: &Self::CollectionId, instance: &Self::ItemId) -> AccountId {
+        Public((ASSET_ACCOUNT_PREFIX, collection, instance).using_encoded(blake2_256))
     }
 
-    fn get_next_nft_id(_collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError> {
-        unimplemented!()
+    fn get_next_nft_id(collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError> {
+        Ok(unhashed::get_or_default(&Self::next_id_key(collection)))
     }
 }
 
 impl Create<AccountId> for MockFnft {
     fn create_collection(
-        _collection: &Self::CollectionId,
+        collection: &Self::CollectionId,
         _who: &AccountId,
-        _admin: &AccountId,
+        admin: &AccountId,
     ) -> DispatchResult {
-        unimplemented!()
+        ensure!(
+            Self::collection_owner(collection).is_none(),
+            DispatchError::Other("collection already exists")
+        );
+
+        unhashed::put(&Self::collection_key(collection), admin);
+        Ok(())
     }
 }
 
-impl Mutate<AccountId> for MockFnft {}
+impl Mutate<AccountId> for MockFnft {
+    fn mint_into(
+        collection: &Self::CollectionId,
+        item: &Self::ItemId,
+        who: &AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            Self::collection_owner(collection).is_some(),
+            DispatchError::Token(TokenError::UnknownAsset)
+        );
+        ensure!(
+            Self::owner(collection, item).is_none(),
+            DispatchError::Other("item already exists")
+        );
+
+        unhashed::put(&Self::owner_key(collection, item), who);
+        let next_id = item
+            .checked_add(1)
+            .ok_or(DispatchError::Token(TokenError::Overflow))?;
+        if next_id > Self::get_next_nft_id(collection)? {
+            unhashed::put(&Self::next_id_key(collection), &next_id);
+        }
+
+        Ok(())
+    }
+
+    fn burn(
+        collection: &Self::CollectionId,
+        item: &Self::ItemId,
+        maybe_check_owner: Option<&AccountId>,
+    ) -> DispatchResult {
+        let owner =
+            Self::owner(collection, item).ok_or(DispatchError::Token(TokenError::UnknownAsset))?;
+        if let Some(check_owner) = maybe_check_owner {
+            ensure!(&owner == check_owner, DispatchError::BadOrigin);
+        }
+
+        unhashed::kill(&Self::owner_key(collection, item));
+        Ok(())
+    }
+}
+
+impl Transfer<AccountId> for MockFnft {
+    fn transfer(
+        collection: &Self::CollectionId,
+        item: &Self::ItemId,
+        destination: &AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            Self::owner(collection, item).is_some(),
+            DispatchError::Token(TokenError::UnknownAsset)
+        );
+
+        unhashed::put(&Self::owner_key(collection, item), destination);
+        Ok(())
+    }
+}
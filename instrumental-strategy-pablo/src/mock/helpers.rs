@@ -9,7 +9,9 @@ use sp_runtime::{
     traits::{BlakeTwo256, Hash},
     Percent, Permill, Perquintill,
 };
-use traits::instrumental::{Instrumental as InstrumentalTrait, InstrumentalVaultConfig};
+use traits::instrumental::{
+    Instrumental as InstrumentalTrait, InstrumentalVaultConfig, StrategyAllocation,
+};
 
 use super::runtime::{
     Call, CollectiveInstrumental, Event, Instrumental, MockRuntime, Origin, System, VaultId,
@@ -94,6 +96,7 @@ where
     let config = InstrumentalVaultConfig {
         asset_id,
         percent_deployable,
+        strategies: StrategyAllocation::Optimum,
     };
     let vault_id = <Instrumental as InstrumentalTrait>::create(config);
     assert_ok!(vault_id);
@@ -1,18 +1,24 @@
+use codec::Encode;
 use frame_support::{
     parameter_types,
     traits::{Everything, GenesisBuild},
+    weights::Weight,
     PalletId,
 };
-use frame_system::{EnsureRoot, EnsureSigned};
-use orml_traits::parameter_type_with_key;
+use frame_system::{offchain::SendTransactionTypes, EnsureNever, EnsureRoot, EnsureSigned};
+use orml_traits::{parameter_type_with_key, xcm_transfer::XcmTransfer};
 use pallet_collective::EnsureProportionAtLeast;
 use primitives::currency::{CurrencyId, ValidateCurrencyId};
-use sp_core::H256;
+use sp_core::{sr25519::Public, H256};
+use sp_io::hashing::blake2_256;
 use sp_runtime::{
-    testing::Header,
-    traits::{ConvertInto, IdentityLookup},
-    Permill,
+    testing::{Header, TestXt},
+    traits::{Convert, ConvertInto, IdentityLookup},
+    transaction_validity::TransactionPriority,
+    DispatchError, DispatchResult, FixedU128, Percent, Permill, Perquintill,
 };
+use traits::strategy::InstrumentalProtocolStrategy;
+use xcm::latest::{MultiAsset, MultiLocation};
 
 use super::fnft;
 use crate as pallet_instrumental_strategy_pablo;
@@ -28,6 +34,11 @@ pub type RewardPoolId = u16;
 pub type VaultId = u64;
 
 pub const MAX_ASSOCIATED_VAULTS: u32 = 10;
+pub const MAX_SNAPSHOTS: u32 = 31 * 24;
+pub const MAX_SCHEDULES_PER_BLOCK: u32 = 10;
+pub const MAX_SCHEDULES_PER_ACCOUNT: u32 = 3;
+// Room for two scheduled deposits per block.
+pub const MAX_SCHEDULED_DEPOSITS_WEIGHT: Weight = 20_000;
 const NATIVE_ASSET: CurrencyId = CurrencyId::PICA;
 // These time units are defined in number of blocks.
 pub const MILLISECS_PER_BLOCK: Moment = 3000;
@@ -313,6 +324,11 @@ impl pallet_pablo::Config for MockRuntime {
 parameter_types! {
     pub const MaxAssociatedVaults: u32 = MAX_ASSOCIATED_VAULTS;
     pub const InstrumentalPabloStrategyPalletId: PalletId = PalletId(*b"strmxpab");
+    pub const PabloRiskScore: Percent = Percent::from_percent(50);
+    pub const PabloRebalanceInterval: BlockNumber = 10;
+    pub const PabloRebalanceThreshold: Balance = 1_000;
+    pub const PabloKeeperBountyShare: Percent = Percent::from_percent(10);
+    pub const PabloMaxKeeperBounty: Balance = 1_000;
 }
 
 impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
@@ -322,10 +338,15 @@ impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
     type Currency = Tokens;
     type Event = Event;
     type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type KeeperBountyShare = PabloKeeperBountyShare;
     type MaxAssociatedVaults = MaxAssociatedVaults;
+    type MaxKeeperBounty = PabloMaxKeeperBounty;
     type Pablo = Pablo;
     type PalletId = InstrumentalPabloStrategyPalletId;
     type PoolId = PoolId;
+    type RebalanceInterval = PabloRebalanceInterval;
+    type RebalanceThreshold = PabloRebalanceThreshold;
+    type RiskScore = PabloRiskScore;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
@@ -337,38 +358,124 @@ impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
 
 parameter_types! {
     pub const InstrumentalStrategyPalletId: PalletId = PalletId(*b"dynamic_");
+    pub const MaxWhitelistedStrategies: u32 = 8;
+    pub const MaxReallocationPerBlock: Perquintill = Perquintill::from_percent(25);
+    // 1% of yearly yield
+    pub const MinApyImprovement: FixedU128 = FixedU128::from_inner(10_000_000_000_000_000);
+    pub const ReallocationDelay: BlockNumber = 10;
+    pub const ApySamplingPeriod: BlockNumber = 10;
+    pub const ApySmoothingFactor: Perquintill = Perquintill::from_percent(20);
+    pub const MaxApySamples: u32 = 24;
+    pub const InstrumentalStrategyUnsignedPriority: TransactionPriority = TransactionPriority::MAX;
+    pub const MaxRebalanceBackoff: u32 = 4;
 }
 
 impl pallet_instrumental_strategy::Config for MockRuntime {
+    type ApySamplingPeriod = ApySamplingPeriod;
+    type ApySmoothingFactor = ApySmoothingFactor;
     type AssetId = CurrencyId;
     type Balance = Balance;
+    type Currency = Tokens;
     type Event = Event;
+    type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type MaxApySamples = MaxApySamples;
     type MaxAssociatedVaults = MaxAssociatedVaults;
-    type PabloStrategy = PabloStrategy;
+    type MaxReallocationPerBlock = MaxReallocationPerBlock;
+    type MaxRebalanceBackoff = MaxRebalanceBackoff;
+    type MaxWhitelistedStrategies = MaxWhitelistedStrategies;
+    type MinApyImprovement = MinApyImprovement;
     type PalletId = InstrumentalStrategyPalletId;
     type PoolId = PoolId;
+    type ReallocationDelay = ReallocationDelay;
+    type Scoring = pallet_instrumental_strategy::scoring::RawApy;
+    type Strategies = (PabloStrategy,);
+    type UnsignedPriority = InstrumentalStrategyUnsignedPriority;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
 }
 
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for MockRuntime
+where
+    Call: From<LocalCall>,
+{
+    type Extrinsic = Extrinsic;
+    type OverarchingCall = Call;
+}
+
 // -------------------------------------------------------------------------------------------------
 //                                           Instrumental
 // -------------------------------------------------------------------------------------------------
 
 parameter_types! {
     pub const InstrumentalPalletId: PalletId = PalletId(*b"strm____");
+    pub const SnapshotInterval: BlockNumber = HOURS;
+    pub const MaxSnapshots: u32 = MAX_SNAPSHOTS;
+    pub const BlocksPerDay: BlockNumber = DAYS;
+    pub const ShareUnit: Balance = 1_000_000_000_000;
+    pub const XcmDestWeight: Weight = 1_000_000;
+    pub const MaxSchedulesPerBlock: u32 = MAX_SCHEDULES_PER_BLOCK;
+    pub const MaxSchedulesPerAccount: u32 = MAX_SCHEDULES_PER_ACCOUNT;
+    pub const MaxScheduledDepositsWeight: Weight = MAX_SCHEDULED_DEPOSITS_WEIGHT;
+}
+
+/// Cross-chain deposits and withdrawals are not exercised by the strategy tests.
+pub struct LocationToAccountId;
+
+impl Convert<MultiLocation, AccountId> for LocationToAccountId {
+    fn convert(location: MultiLocation) -> AccountId {
+        Public(("multiloc", location).using_encoded(blake2_256))
+    }
+}
+
+pub struct NoXcmTransfer;
+
+impl XcmTransfer<AccountId, Balance, CurrencyId> for NoXcmTransfer {
+    fn transfer(
+        _who: AccountId,
+        _currency_id: CurrencyId,
+        _amount: Balance,
+        _dest: MultiLocation,
+        _dest_weight: Weight,
+    ) -> DispatchResult {
+        Err(DispatchError::Other("XCM transfers are not supported"))
+    }
+
+    fn transfer_multi_asset(
+        _who: AccountId,
+        _asset: MultiAsset,
+        _dest: MultiLocation,
+        _dest_weight: Weight,
+    ) -> DispatchResult {
+        Err(DispatchError::Other("XCM transfers are not supported"))
+    }
 }
 
 impl pallet_instrumental::Config for MockRuntime {
     type AssetId = CurrencyId;
     type Balance = Balance;
+    type BlocksPerDay = BlocksPerDay;
+    type Currency = Assets;
     type Event = Event;
+    type FinancialNft = fnft::MockFnft;
+    type FinancialNftInstanceId = u64;
     type InstrumentalStrategy = InstrumentalStrategy;
+    type LocationToAccountId = LocationToAccountId;
+    type MaxScheduledDepositsWeight = MaxScheduledDepositsWeight;
+    type MaxSchedulesPerAccount = MaxSchedulesPerAccount;
+    type MaxSchedulesPerBlock = MaxSchedulesPerBlock;
+    type MaxSnapshots = MaxSnapshots;
     type PalletId = InstrumentalPalletId;
+    type ShareUnit = ShareUnit;
+    type SnapshotInterval = SnapshotInterval;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
+    type XcmDestWeight = XcmDestWeight;
+    type XcmOrigin = EnsureNever<MultiLocation>;
+    type XcmTransfer = NoXcmTransfer;
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -398,7 +505,8 @@ frame_support::construct_runtime!(
         CollectiveInstrumental:
             pallet_collective::<Instance1>::{Pallet, Call, Event<T>, Origin<T>, Config<T>},
 
-        InstrumentalStrategy: pallet_instrumental_strategy::{Pallet, Call, Storage, Event<T>},
+        InstrumentalStrategy:
+        pallet_instrumental_strategy::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
         Instrumental: pallet_instrumental::{Pallet, Call, Storage, Event<T>},
         PabloStrategy: pallet_instrumental_strategy_pablo::{Pallet, Call, Storage, Event<T>},
     }
@@ -438,6 +546,16 @@ impl ExtBuilder {
         .assimilate_storage(&mut storage)
         .unwrap();
 
+        // Whitelist every registered strategy, as governance would do on a live chain
+        pallet_instrumental_strategy::GenesisConfig::<MockRuntime> {
+            whitelisted_strategies: vec![
+                <PabloStrategy as InstrumentalProtocolStrategy>::account_id(),
+            ],
+            ..Default::default()
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
         storage.into()
     }
 
@@ -1,5 +1,8 @@
 use composable_traits::vault::CapabilityVault;
-use frame_support::{assert_noop, assert_ok, traits::fungibles::Mutate};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::fungibles::{Inspect, Mutate},
+};
 use primitives::currency::CurrencyId;
 use sp_core::H256;
 use sp_runtime::{
@@ -128,6 +131,34 @@ mod associate_vault {
             });
         });
     }
+
+    #[test]
+    fn dissociate_vault_emits_event() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = create_vault(CurrencyId::LAYR, None);
+            set_admin_members(vec![ALICE], 5);
+            associate_vault(vault_id);
+
+            assert_ok!(PabloStrategy::dissociate_vault(&vault_id));
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::DissociatedVault {
+                vault_id,
+            }));
+            assert!(!PabloStrategy::associated_vaults().contains(&vault_id));
+        });
+    }
+
+    #[test]
+    fn dissociating_a_not_associated_vault_throws_an_error() {
+        ExtBuilder::default().build().execute_with(|| {
+            let vault_id = create_vault(CurrencyId::LAYR, None);
+
+            assert_noop!(
+                PabloStrategy::dissociate_vault(&vault_id),
+                Error::<MockRuntime>::VaultNotAssociated
+            );
+        });
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -260,6 +291,183 @@ mod rebalance {
             ));
         });
     }
+
+    #[test]
+    fn rebalance_vault_emits_event() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, _pool_id, base_asset) =
+                prepare_for_rebalancing(Some(Perquintill::from_percent(50)));
+            assert_ok!(Tokens::mint_into(base_asset, &ALICE, 1_000_000));
+            assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 100_000));
+
+            assert_ok!(PabloStrategy::rebalance_vault(&vault_id));
+
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::RebalancedVault {
+                vault_id,
+            }));
+            assert!(PabloStrategy::assets_under_management(&vault_id).unwrap() > 0);
+        });
+    }
+
+    #[test]
+    fn rebalance_vault_of_not_associated_vault_throws_an_error() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            prepare_for_rebalancing(None);
+            let vault_id = create_vault(CurrencyId::CROWD_LOAN, None);
+
+            assert_noop!(
+                PabloStrategy::rebalance_vault(&vault_id),
+                Error::<MockRuntime>::VaultNotAssociated
+            );
+        });
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                           Poke Rebalance
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod poke_rebalance {
+    use sp_runtime::PerThing;
+
+    use super::*;
+    use crate::mock::runtime::{
+        PabloKeeperBountyShare, PabloMaxKeeperBounty, PabloRebalanceInterval,
+    };
+
+    fn prepare_for_poking() -> (VaultId, CurrencyId) {
+        let (vault_id, pool_id, base_asset) =
+            prepare_for_rebalancing(Some(Perquintill::from_percent(50)));
+        set_admin_members(vec![ALICE], 5);
+        set_pool_id_for_asset(base_asset, pool_id, vault_id, None);
+        assert_ok!(Tokens::mint_into(base_asset, &ALICE, 1_000_000_000));
+        assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 1_000_000));
+
+        (vault_id, base_asset)
+    }
+
+    #[test]
+    fn poking_an_out_of_band_vault_rebalances_it() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, _base_asset) = prepare_for_poking();
+
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+
+            System::assert_has_event(Event::PabloStrategy(
+                pallet::Event::WithdrawFunctionalityOccuredDuringRebalance { vault_id },
+            ));
+            assert_eq!(PabloStrategy::last_rebalanced(vault_id), Some(1));
+            assert!(PabloStrategy::principals(vault_id).unwrap() > 0);
+        });
+    }
+
+    #[test]
+    fn poking_a_balanced_vault_before_it_is_due_throws_an_error() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, _base_asset) = prepare_for_poking();
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+
+            assert_noop!(
+                PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id),
+                Error::<MockRuntime>::RebalanceNotNeeded
+            );
+        });
+    }
+
+    #[test]
+    fn poking_a_due_balanced_vault_pays_no_bounty() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, base_asset) = prepare_for_poking();
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+            // Any yield would be paid out if funds had moved
+            pallet::Principals::<MockRuntime>::insert(vault_id, 0);
+
+            let next_block = 1 + PabloRebalanceInterval::get();
+            System::set_block_number(next_block);
+            let balance_before = Tokens::balance(base_asset, &BOB);
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+
+            assert_eq!(PabloStrategy::last_rebalanced(vault_id), Some(next_block));
+            assert_eq!(Tokens::balance(base_asset, &BOB), balance_before);
+        });
+    }
+
+    #[test]
+    fn poking_pays_a_bounded_bounty_from_yield() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, base_asset) = prepare_for_poking();
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+            // Everything deployed so far is now yield
+            pallet::Principals::<MockRuntime>::insert(vault_id, 0);
+
+            assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 1_000_000));
+            // The share of the yield owed to the keeper is well above the cap
+            let vault_yield =
+                <PabloStrategy as InstrumentalProtocolStrategy>::assets_under_management(&vault_id)
+                    .unwrap();
+            assert!(
+                PabloKeeperBountyShare::get().mul_floor(vault_yield)
+                    > 10 * PabloMaxKeeperBounty::get()
+            );
+            System::set_block_number(1 + PabloRebalanceInterval::get());
+            let balance_before = Tokens::balance(base_asset, &BOB);
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+
+            // Capped, less the rounding of the liquidity redeemed to pay it
+            let bounty = Tokens::balance(base_asset, &BOB) - balance_before;
+            assert!(bounty <= PabloMaxKeeperBounty::get());
+            assert!(bounty >= PabloMaxKeeperBounty::get() * 99 / 100);
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::KeeperRewarded {
+                vault_id,
+                keeper: BOB,
+                bounty,
+            }));
+        });
+    }
+
+    #[test]
+    fn poking_a_vault_pushed_out_of_band_by_the_keeper_pays_no_bounty() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, base_asset) = prepare_for_poking();
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+            // Everything deployed so far is now yield
+            pallet::Principals::<MockRuntime>::insert(vault_id, 0);
+
+            // The keeper pushes the vault out of its target band before it is due
+            assert_ok!(Tokens::mint_into(base_asset, &BOB, 1_000_000));
+            assert_ok!(Vault::deposit(Origin::signed(BOB), vault_id, 1_000_000));
+            assert_ok!(PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id));
+            assert_ok!(Vault::withdraw(Origin::signed(BOB), vault_id, 500_000));
+
+            assert_eq!(PabloStrategy::last_rebalanced(vault_id), Some(1));
+            assert!(!System::events().iter().any(|record| matches!(
+                record.event,
+                Event::PabloStrategy(pallet::Event::KeeperRewarded { .. })
+            )));
+        });
+    }
+
+    #[test]
+    fn poking_a_halted_strategy_throws_an_error() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, _base_asset) = prepare_for_poking();
+            assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::halt());
+
+            assert_noop!(
+                PabloStrategy::poke_rebalance(Origin::signed(BOB), vault_id),
+                Error::<MockRuntime>::Halted
+            );
+        });
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -320,6 +528,27 @@ mod set_pool_id_for_asset {
     }
 }
 
+#[cfg(test)]
+mod pool_states {
+    use traits::instrumental::State;
+
+    use super::*;
+
+    #[test]
+    fn pool_states_list_the_pool_of_each_asset() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (_vault_id, _pool_id, base_asset) = prepare_for_rebalancing(None);
+            let pool_id = PabloStrategy::pools(base_asset).unwrap().pool_id;
+
+            assert_eq!(
+                PabloStrategy::pool_states(),
+                vec![(base_asset, pool_id, State::Normal)]
+            );
+        });
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 //                                              Halting
 // -------------------------------------------------------------------------------------------------
@@ -391,6 +620,431 @@ mod halt {
             });
         });
     }
+
+    #[test]
+    fn halt_and_start_single_vault() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, _pool_id, _base_asset) = prepare_for_rebalancing(None);
+
+            assert_ok!(PabloStrategy::halt_vault(&vault_id));
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::VaultHalted {
+                vault_id,
+            }));
+            assert!(Vault::is_stopped(&vault_id).unwrap());
+            assert!(!PabloStrategy::is_halted().unwrap());
+
+            assert_ok!(PabloStrategy::start_vault(&vault_id));
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::VaultStarted {
+                vault_id,
+            }));
+            assert!(!Vault::is_stopped(&vault_id).unwrap());
+        });
+    }
+
+    #[test]
+    fn vaults_of_a_halted_strategy_are_not_started() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, _pool_id, _base_asset) = prepare_for_rebalancing(None);
+            assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::halt());
+
+            assert_noop!(
+                PabloStrategy::start_vault(&vault_id),
+                Error::<MockRuntime>::Halted
+            );
+            assert!(Vault::is_stopped(&vault_id).unwrap());
+        });
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                               Health
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod health {
+    use super::*;
+
+    #[test]
+    fn successful_rebalances_record_deposits_into_pablo() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, pool_id, base_asset) =
+                prepare_for_rebalancing(Some(Perquintill::from_percent(50)));
+            set_admin_members(vec![ALICE], 5);
+            set_pool_id_for_asset(base_asset, pool_id, vault_id, None);
+            assert_ok!(Tokens::mint_into(base_asset, &ALICE, 1_000_000));
+            assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 100_000));
+
+            liquidity_rebalance();
+
+            let health = PabloStrategy::health();
+            assert_eq!(health.last_rebalance, Some(1));
+            assert_eq!(health.consecutive_failures, 0);
+            assert!(health.total_deposited > 0);
+            assert_eq!(health.total_withdrawn, 0);
+        });
+    }
+
+    #[test]
+    fn liquidations_record_withdrawals_from_pablo() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let (vault_id, pool_id, base_asset) =
+                prepare_for_rebalancing(Some(Perquintill::from_percent(50)));
+            set_admin_members(vec![ALICE], 5);
+            set_pool_id_for_asset(base_asset, pool_id, vault_id, None);
+            assert_ok!(Tokens::mint_into(base_asset, &ALICE, 1_000_000_000));
+            assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 1_000_000));
+            liquidity_rebalance();
+            assert_ok!(Vault::stop(&vault_id));
+
+            liquidity_rebalance();
+
+            let health = PabloStrategy::health();
+            assert!(health.total_withdrawn > 0);
+            assert_eq!(PabloStrategy::principals(vault_id), None);
+        });
+    }
+
+    fn deploy_into_pablo() -> VaultId {
+        let (vault_id, pool_id, base_asset) =
+            prepare_for_rebalancing(Some(Perquintill::from_percent(50)));
+        set_admin_members(vec![ALICE], 5);
+        set_pool_id_for_asset(base_asset, pool_id, vault_id, None);
+        assert_ok!(Tokens::mint_into(base_asset, &ALICE, 1_000_000_000));
+        assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 1_000_000));
+        liquidity_rebalance();
+        vault_id
+    }
+
+    #[test]
+    fn partial_deposits_realize_the_yield_above_the_principal() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = deploy_into_pablo();
+            // Only part of what is deployed was taken from the vault, the rest is yield
+            pallet::Principals::<MockRuntime>::insert(vault_id, 100_000);
+
+            // The vault asks for part of its funds back
+            assert_ok!(Vault::withdraw(Origin::signed(ALICE), vault_id, 400_000));
+            liquidity_rebalance();
+
+            let health = PabloStrategy::health();
+            assert!(health.total_withdrawn > 100_000);
+            assert_eq!(health.realized_profit, health.total_withdrawn - 100_000);
+            assert_eq!(health.realized_loss, 0);
+            assert_eq!(PabloStrategy::principals(vault_id), Some(0));
+        });
+    }
+
+    #[test]
+    fn liquidations_realize_the_yield_above_the_principal() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = deploy_into_pablo();
+            pallet::Principals::<MockRuntime>::insert(vault_id, 100_000);
+            assert_ok!(Vault::stop(&vault_id));
+
+            liquidity_rebalance();
+
+            let health = PabloStrategy::health();
+            assert!(health.total_withdrawn > 100_000);
+            assert_eq!(health.realized_profit, health.total_withdrawn - 100_000);
+            assert_eq!(health.realized_loss, 0);
+        });
+    }
+
+    #[test]
+    fn liquidations_realize_the_principal_not_returned_as_a_loss() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = deploy_into_pablo();
+            // More was taken from the vault than the strategy holds now
+            pallet::Principals::<MockRuntime>::insert(vault_id, 10_000_000);
+            assert_ok!(Vault::stop(&vault_id));
+
+            liquidity_rebalance();
+
+            let health = PabloStrategy::health();
+            assert!(health.total_withdrawn > 0);
+            assert_eq!(health.realized_profit, 0);
+            assert_eq!(health.realized_loss, 10_000_000 - health.total_withdrawn);
+        });
+    }
+
+    #[test]
+    fn failed_rebalances_are_counted_until_one_succeeds() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            // No pool is set for the asset of the vault, so it cannot be rebalanced
+            let vault_id = create_vault(CurrencyId::CROWD_LOAN, None);
+            assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id));
+
+            assert_ok!(PabloStrategy::rebalance());
+            assert_ok!(PabloStrategy::rebalance());
+            assert_eq!(PabloStrategy::health().consecutive_failures, 2);
+            assert_eq!(PabloStrategy::health().last_rebalance, None);
+
+            assert_ok!(
+                <PabloStrategy as InstrumentalProtocolStrategy>::dissociate_vault(&vault_id)
+            );
+            assert_ok!(PabloStrategy::rebalance());
+            assert_eq!(PabloStrategy::health().consecutive_failures, 0);
+            assert_eq!(PabloStrategy::health().last_rebalance, Some(1));
+        });
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                          Circuit Breaker
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod circuit_breaker {
+    use traits::strategy::{CircuitBreakerThresholds, CircuitBreakerTrip, TripReason};
+
+    use super::*;
+
+    fn propose_thresholds(max_consecutive_failures: u32) {
+        set_admin_members(vec![ALICE], 5);
+        let thresholds = Some(CircuitBreakerThresholds {
+            max_value_drop: Percent::from_percent(10),
+            window: 10,
+            max_consecutive_failures,
+        });
+        let proposal =
+            Call::PabloStrategy(crate::Call::set_circuit_breaker_thresholds { thresholds });
+        make_proposal(proposal, ALICE, 1, 0, None);
+        assert_has_event::<MockRuntime, _>(|e| {
+            matches!(
+                e.event,
+                Event::PabloStrategy(pallet::Event::CircuitBreakerThresholdsUpdated { .. })
+            )
+        });
+    }
+
+    fn set_thresholds(max_consecutive_failures: u32) -> VaultId {
+        propose_thresholds(max_consecutive_failures);
+
+        // No pool is set for the asset of the vault, so it cannot be rebalanced
+        let vault_id = create_vault(CurrencyId::CROWD_LOAN, None);
+        assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id));
+        vault_id
+    }
+
+    #[test]
+    fn consecutive_failures_halt_the_strategy() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            set_thresholds(2);
+
+            assert_ok!(PabloStrategy::rebalance());
+            assert!(!PabloStrategy::is_halted().unwrap());
+            assert_eq!(PabloStrategy::breaker_trip(), None);
+
+            assert_ok!(PabloStrategy::rebalance());
+            assert!(PabloStrategy::is_halted().unwrap());
+            assert_eq!(
+                PabloStrategy::breaker_trip(),
+                Some(CircuitBreakerTrip {
+                    at: 1,
+                    reason: TripReason::ConsecutiveFailures
+                })
+            );
+            System::assert_has_event(Event::PabloStrategy(pallet::Event::Halted));
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::CircuitBreakerTripped {
+                reason: TripReason::ConsecutiveFailures,
+            }));
+        });
+    }
+
+    #[test]
+    fn failed_vault_rebalances_are_counted_and_halt_the_strategy() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = set_thresholds(2);
+
+            // The failure is reported without failing, so that callers do not roll it back
+            assert_ok!(PabloStrategy::rebalance_vault(&vault_id));
+            System::assert_last_event(Event::PabloStrategy(
+                pallet::Event::UnableToRebalanceVault { vault_id },
+            ));
+            assert_eq!(PabloStrategy::health().consecutive_failures, 1);
+            assert!(!PabloStrategy::is_halted().unwrap());
+
+            assert_ok!(PabloStrategy::rebalance_vault(&vault_id));
+            assert!(PabloStrategy::is_halted().unwrap());
+            System::assert_last_event(Event::PabloStrategy(pallet::Event::CircuitBreakerTripped {
+                reason: TripReason::ConsecutiveFailures,
+            }));
+        });
+    }
+
+    #[test]
+    fn disabled_circuit_breaker_never_trips() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            set_thresholds(0);
+
+            for _ in 0..5 {
+                assert_ok!(PabloStrategy::rebalance());
+            }
+            assert!(!PabloStrategy::is_halted().unwrap());
+            assert_eq!(PabloStrategy::breaker_trip(), None);
+        });
+    }
+
+    #[test]
+    fn tripped_strategy_starts_only_once_reset() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = set_thresholds(1);
+            assert_ok!(PabloStrategy::rebalance());
+            assert!(PabloStrategy::is_halted().unwrap());
+
+            assert_noop!(
+                <PabloStrategy as InstrumentalProtocolStrategy>::start(),
+                Error::<MockRuntime>::CircuitBreakerTripped
+            );
+
+            let proposal = Call::PabloStrategy(crate::Call::reset_circuit_breaker {});
+            make_proposal(proposal, ALICE, 1, 0, None);
+            System::assert_has_event(Event::PabloStrategy(pallet::Event::CircuitBreakerReset));
+            assert_eq!(PabloStrategy::breaker_trip(), None);
+            assert_eq!(PabloStrategy::health().consecutive_failures, 0);
+
+            assert_ok!(
+                <PabloStrategy as InstrumentalProtocolStrategy>::dissociate_vault(&vault_id)
+            );
+            assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::start());
+            assert!(!PabloStrategy::is_halted().unwrap());
+        });
+    }
+
+    #[test]
+    fn vaults_of_a_tripped_strategy_are_not_started() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = set_thresholds(1);
+            assert_ok!(PabloStrategy::rebalance());
+            assert!(PabloStrategy::is_halted().unwrap());
+
+            assert_noop!(
+                PabloStrategy::start_vault(&vault_id),
+                Error::<MockRuntime>::CircuitBreakerTripped
+            );
+        });
+    }
+
+    /// Deploys funds of a vault into Pablo at block 1, which starts the value window of the vault.
+    fn deploy_with_thresholds() -> VaultId {
+        let (vault_id, pool_id, base_asset) =
+            prepare_for_rebalancing(Some(Perquintill::from_percent(50)));
+        propose_thresholds(0);
+        set_pool_id_for_asset(base_asset, pool_id, vault_id, None);
+        assert_ok!(Tokens::mint_into(base_asset, &ALICE, 1_000_000_000));
+        assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 1_000_000));
+        liquidity_rebalance();
+        assert!(pallet::ValueCheckpoints::<MockRuntime>::get(vault_id).is_some());
+        vault_id
+    }
+
+    #[test]
+    fn value_drops_within_the_window_halt_the_strategy() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = deploy_with_thresholds();
+            // The vault was worth twice as much at the start of the window
+            let value = PabloStrategy::assets_under_management(&vault_id).unwrap();
+            pallet::ValueCheckpoints::<MockRuntime>::insert(vault_id, (1, 2 * value));
+
+            System::set_block_number(5);
+            liquidity_rebalance();
+
+            assert!(PabloStrategy::is_halted().unwrap());
+            assert_eq!(
+                PabloStrategy::breaker_trip(),
+                Some(CircuitBreakerTrip {
+                    at: 5,
+                    reason: TripReason::ValueDrop { vault_id }
+                })
+            );
+            System::assert_has_event(Event::PabloStrategy(pallet::Event::CircuitBreakerTripped {
+                reason: TripReason::ValueDrop { vault_id },
+            }));
+        });
+    }
+
+    #[test]
+    fn value_drops_over_an_ended_window_start_a_new_window() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = deploy_with_thresholds();
+            let value = PabloStrategy::assets_under_management(&vault_id).unwrap();
+            pallet::ValueCheckpoints::<MockRuntime>::insert(vault_id, (1, 2 * value));
+
+            System::set_block_number(11);
+            liquidity_rebalance();
+
+            assert!(!PabloStrategy::is_halted().unwrap());
+            assert_eq!(PabloStrategy::breaker_trip(), None);
+            assert_eq!(
+                pallet::ValueCheckpoints::<MockRuntime>::get(vault_id),
+                Some((
+                    11,
+                    PabloStrategy::assets_under_management(&vault_id).unwrap()
+                ))
+            );
+        });
+    }
+
+    #[test]
+    fn funds_moved_by_the_vault_move_the_checkpoint_without_tripping() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            let vault_id = deploy_with_thresholds();
+            let (_, deployed) = pallet::ValueCheckpoints::<MockRuntime>::get(vault_id).unwrap();
+
+            // The vault asks for a large part of its funds back
+            assert_ok!(Vault::withdraw(Origin::signed(ALICE), vault_id, 400_000));
+            System::set_block_number(2);
+            liquidity_rebalance();
+
+            assert!(!PabloStrategy::is_halted().unwrap());
+            let (since, expected) = pallet::ValueCheckpoints::<MockRuntime>::get(vault_id).unwrap();
+            assert_eq!(since, 1);
+            assert!(expected < deployed - deployed / 10);
+
+            // And deposits them again
+            assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, 400_000));
+            System::set_block_number(3);
+            liquidity_rebalance();
+
+            assert!(!PabloStrategy::is_halted().unwrap());
+            assert_eq!(PabloStrategy::breaker_trip(), None);
+            let (since, redeployed) =
+                pallet::ValueCheckpoints::<MockRuntime>::get(vault_id).unwrap();
+            assert_eq!(since, 1);
+            assert!(redeployed > expected);
+        });
+    }
+
+    #[test]
+    fn reset_requires_a_tripped_circuit_breaker() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            set_thresholds(2);
+
+            let proposal = Call::PabloStrategy(crate::Call::reset_circuit_breaker {});
+            make_proposal(proposal, ALICE, 1, 0, None);
+            assert!(!System::events().iter().any(|e| matches!(
+                e.event,
+                Event::PabloStrategy(pallet::Event::CircuitBreakerReset)
+            )));
+        });
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -429,3 +1083,28 @@ mod transferring_funds {
         });
     }
 }
+
+// -------------------------------------------------------------------------------------------------
+//                                             Metadata
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod metadata {
+    use traits::strategy::{InstrumentalStrategyMetadata, SupportedAssets};
+
+    use super::*;
+    use crate::mock::runtime::PabloRiskScore;
+
+    #[test]
+    fn metadata_reports_configured_risk_score() {
+        ExtBuilder::default().build().execute_with(|| {
+            let metadata = PabloStrategy::metadata();
+
+            assert_eq!(metadata.name, b"pablo".to_vec());
+            assert_eq!(metadata.supported_assets, SupportedAssets::All);
+            assert_eq!(metadata.risk_score, PabloRiskScore::get());
+            assert!(metadata.synchronous_unwind);
+            assert!(PabloStrategy::supports_asset(&CurrencyId::LAYR));
+        });
+    }
+}
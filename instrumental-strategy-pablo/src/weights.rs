@@ -7,10 +7,13 @@ pub trait WeightInfo {
     fn test() -> Weight;
     fn set_pool_id_for_asset() -> Weight;
     fn liquidity_rebalance() -> Weight;
+    fn poke_rebalance() -> Weight;
     fn associate_vault() -> Weight;
     fn halt() -> Weight;
     fn start() -> Weight;
     fn transferring_funds() -> Weight;
+    fn set_circuit_breaker_thresholds() -> Weight;
+    fn reset_circuit_breaker() -> Weight;
 }
 
 /// Weights for instrumental_strategy_pablo using the Substrate node and recommended hardware.
@@ -28,6 +31,10 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
         10_000 as Weight
     }
 
+    fn poke_rebalance() -> Weight {
+        10_000 as Weight
+    }
+
     fn associate_vault() -> Weight {
         10_000 as Weight
     }
@@ -43,6 +50,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn transferring_funds() -> Weight {
         10_000 as Weight
     }
+
+    fn set_circuit_breaker_thresholds() -> Weight {
+        10_000 as Weight
+    }
+
+    fn reset_circuit_breaker() -> Weight {
+        10_000 as Weight
+    }
 }
 
 // For backwards compatibility and tests
@@ -59,6 +74,10 @@ impl WeightInfo for () {
         10_000 as Weight
     }
 
+    fn poke_rebalance() -> Weight {
+        10_000 as Weight
+    }
+
     fn associate_vault() -> Weight {
         10_000 as Weight
     }
@@ -74,4 +93,12 @@ impl WeightInfo for () {
     fn transferring_funds() -> Weight {
         10_000 as Weight
     }
+
+    fn set_circuit_breaker_thresholds() -> Weight {
+        10_000 as Weight
+    }
+
+    fn reset_circuit_breaker() -> Weight {
+        10_000 as Weight
+    }
 }
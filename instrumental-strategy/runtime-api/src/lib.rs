@@ -0,0 +1,38 @@
+//! # Instrumental Strategy Runtime API
+//!
+//! Strategy-level introspection for operators: the registered strategies, the associated vaults
+//! and how their funds are spread, and the Pablo pools the vaults are deployed into.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use pallet_instrumental_strategy::{StrategyInfo, VaultInfo};
+use sp_std::vec::Vec;
+use traits::instrumental::State;
+
+sp_api::decl_runtime_apis! {
+    pub trait InstrumentalStrategyRuntimeApi<AccountId, AssetId, Balance, BlockNumber, PoolId, VaultId>
+    where
+        AccountId: Codec,
+        AssetId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+        PoolId: Codec,
+        VaultId: Codec,
+    {
+        /// Whether the aggregator is halted.
+        fn is_halted() -> bool;
+
+        /// The registered strategies, see
+        /// [`strategy_infos`](pallet_instrumental_strategy::Pallet::strategy_infos).
+        fn strategies() -> Vec<StrategyInfo<AccountId, AssetId, BlockNumber, VaultId>>;
+
+        /// The associated vaults, see
+        /// [`vault_infos`](pallet_instrumental_strategy::Pallet::vault_infos).
+        fn vaults() -> Vec<VaultInfo<AccountId, Balance, BlockNumber, VaultId>>;
+
+        /// The Pablo pool each asset is deployed into, and whether funds are being transferred to
+        /// a new pool.
+        fn pablo_pools() -> Vec<(AssetId, PoolId, State)>;
+    }
+}
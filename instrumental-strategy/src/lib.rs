@@ -19,6 +19,7 @@
 
 #[cfg(test)]
 mod mock;
+pub mod scoring;
 #[cfg(test)]
 mod tests;
 mod weights;
@@ -32,19 +33,45 @@ pub mod pallet {
     // ---------------------------------------------------------------------------------------------
 
     use codec::{Codec, FullCodec};
-    use composable_traits::vault::StrategicVault;
+    use composable_traits::vault::{StrategicVault, Vault};
     use frame_support::{
-        pallet_prelude::*, storage::bounded_btree_set::BoundedBTreeSet, transactional, PalletId,
+        pallet_prelude::*,
+        storage::{
+            bounded_btree_map::BoundedBTreeMap, bounded_btree_set::BoundedBTreeSet,
+            with_transaction, TransactionOutcome,
+        },
+        traits::fungibles::Inspect,
+        transactional, PalletId,
+    };
+    use frame_system::{
+        ensure_none,
+        offchain::{SendTransactionTypes, SubmitTransaction},
+        pallet_prelude::OriginFor,
+    };
+    use sp_runtime::{
+        traits::{
+            AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub,
+            SaturatedConversion, Saturating, Zero,
+        },
+        ArithmeticError, FixedU128, Percent, Perquintill,
     };
-    use sp_runtime::traits::{
-        AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Zero,
+    use sp_std::{
+        cmp::Ordering,
+        collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+        fmt::Debug,
+        vec::Vec,
     };
-    use sp_std::fmt::Debug;
     use traits::{
-        instrumental::InstrumentalDynamicStrategy, strategy::InstrumentalProtocolStrategy,
+        instrumental::{InstrumentalDynamicStrategy, State},
+        strategy::{
+            Apy, InstrumentalProtocolStrategy, RegistryError, StrategyMetadata, StrategyRegistry,
+        },
     };
 
-    use crate::weights::WeightInfo;
+    use crate::{
+        scoring::{ScoreInputs, StrategyScoring},
+        weights::WeightInfo,
+    };
 
     // ---------------------------------------------------------------------------------------------
     //                                  Declaration Of The Pallet Type
@@ -60,10 +87,13 @@ pub mod pallet {
 
     // Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
         #[allow(missing_docs)]
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+        /// Some sort of check on the origin is performed by this object.
+        type ExternalOrigin: EnsureOrigin<Self::Origin>;
+
         type WeightInfo: WeightInfo;
 
         /// The type used by the pallet for bookkeeping.
@@ -111,6 +141,9 @@ pub mod pallet {
             VaultId = Self::VaultId,
         >;
 
+        /// Currency is used to value the funds left in the associated vaults.
+        type Currency: Inspect<Self::AccountId, Balance = Self::Balance, AssetId = Self::AssetId>;
+
         /// Type representing the unique ID of a pool.
         type PoolId: FullCodec
             + MaxEncodedLen
@@ -122,14 +155,13 @@ pub mod pallet {
             + Ord
             + Copy;
 
-        // TODO: (Nevin)
-        //  - try to make the connection to substrategies a vec of InstrumentalProtocolStrategy
-        //  - ideally something like: type WhitelistedStrategies: Get<[dyn
-        //    InstrumentalProtocolStrategy]>;
-
-        type PabloStrategy: InstrumentalProtocolStrategy<
+        /// The strategies the funds of the associated vaults are dispatched to, as a tuple of
+        /// strategy pallets (e.g. `(PabloStrategy, DefaultStrategy)`).
+        type Strategies: StrategyRegistry<
             AccountId = Self::AccountId,
             AssetId = Self::AssetId,
+            Balance = Self::Balance,
+            BlockNumber = Self::BlockNumber,
             VaultId = Self::VaultId,
         >;
 
@@ -137,6 +169,48 @@ pub mod pallet {
         #[pallet::constant]
         type MaxAssociatedVaults: Get<u32>;
 
+        /// The maximum number of strategies that can be whitelisted at the same time.
+        #[pallet::constant]
+        type MaxWhitelistedStrategies: Get<u32>;
+
+        /// The minimum score gain a strategy must offer over the current strategy of a vault for
+        /// the vault to be reallocated to it, see [`Config::Scoring`].
+        #[pallet::constant]
+        type MinApyImprovement: Get<FixedU128>;
+
+        /// The number of blocks the APY gain must be sustained for before the reallocation starts.
+        #[pallet::constant]
+        type ReallocationDelay: Get<Self::BlockNumber>;
+
+        /// The maximum share of a vault moved from one strategy to another in a single block.
+        #[pallet::constant]
+        type MaxReallocationPerBlock: Get<Perquintill>;
+
+        /// The number of blocks between two samples of the APYs of the whitelisted strategies.
+        #[pallet::constant]
+        type ApySamplingPeriod: Get<Self::BlockNumber>;
+
+        /// The maximum number of APY samples kept per strategy and asset.
+        #[pallet::constant]
+        type MaxApySamples: Get<u32>;
+
+        /// The weight of the latest sample in the exponential moving average of the APYs.
+        #[pallet::constant]
+        type ApySmoothingFactor: Get<Perquintill>;
+
+        /// The priority of the target allocations proposed by the off-chain worker.
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+
+        /// The maximum number of times the rebalance interval of a strategy is doubled after
+        /// consecutive failed scheduled rebalances.
+        #[pallet::constant]
+        type MaxRebalanceBackoff: Get<u32>;
+
+        /// The policy strategies are ranked with, see [`scoring`](crate::scoring) for the
+        /// available ones.
+        type Scoring: StrategyScoring;
+
         /// The id used as the
         /// [`AccountId`](traits::instrumental::Instrumental::AccountId) of the vault.
         /// This should be unique across all pallets to avoid name collisions with other pallets and
@@ -149,6 +223,103 @@ pub mod pallet {
     //                                           Pallet Types
     // ---------------------------------------------------------------------------------------------
 
+    /// A move of the share of a vault routed to a strategy to a better performing one.
+    #[derive(Encode, Decode, MaxEncodedLen, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub struct Reallocation<AccountId, BlockNumber> {
+        /// The strategy the share of the vault is moved away from.
+        pub from: AccountId,
+        /// The strategy the share of the vault is moved to.
+        pub to: AccountId,
+        /// The block since which `to` has been outperforming `from`.
+        pub since: BlockNumber,
+        /// The last block at which a part of the share was moved, `None` until the move starts.
+        pub last_moved: Option<BlockNumber>,
+    }
+
+    /// A governance-initiated move of a whole vault to a single strategy, see [`Migrations`].
+    #[derive(Encode, Decode, MaxEncodedLen, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub struct Migration<AccountId, BlockNumber> {
+        /// The strategy the vault is moved to.
+        pub to: AccountId,
+        /// The share of the vault moved on each block.
+        pub chunk: Perquintill,
+        /// [`State::Transferring`] while the vault is being moved, [`State::Normal`] once a chunk
+        /// failed to move, until the migration is resumed.
+        pub state: State,
+        /// The block at which the migration was started.
+        pub since: BlockNumber,
+    }
+
+    /// The schedule of the rebalances of a strategy run in `on_idle`, see [`RebalanceSchedules`].
+    #[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub struct RebalanceSchedule<BlockNumber> {
+        /// The number of blocks between two rebalances.
+        pub interval: BlockNumber,
+        /// The first block at which the next rebalance may run.
+        pub next_run: BlockNumber,
+        /// The last block at which a rebalance ran, successfully or not.
+        pub last_run: Option<BlockNumber>,
+        /// The number of rebalances that failed in a row, each one doubling the interval up to
+        /// [`Config::MaxRebalanceBackoff`] times.
+        pub consecutive_failures: u32,
+    }
+
+    /// The governance limits the target allocations of the vaults are computed under, see
+    /// [`CurrentAllocationLimits`] and [`TargetAllocations`].
+    #[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub struct AllocationLimits {
+        /// The minimum number of strategies a vault is spread across. No strategy may manage more
+        /// than `1 / min_strategies` of a vault.
+        pub min_strategies: u32,
+        /// The highest risk score of a strategy eligible for the funds of the vaults.
+        pub max_risk_score: Percent,
+    }
+
+    impl Default for AllocationLimits {
+        fn default() -> Self {
+            Self {
+                min_strategies: 1,
+                max_risk_score: Percent::one(),
+            }
+        }
+    }
+
+    /// A registered strategy, as reported by [`Pallet::strategy_infos`].
+    #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub struct StrategyInfo<AccountId, AssetId, BlockNumber, VaultId> {
+        /// Account ID of the strategy.
+        pub account: AccountId,
+        /// Human readable name of the strategy, empty if its metadata cannot be read.
+        pub name: Vec<u8>,
+        /// Whether the strategy is whitelisted, see [`WhitelistedStrategies`].
+        pub whitelisted: bool,
+        /// Whether the strategy is halted, `None` if its status cannot be read.
+        pub is_halted: Option<bool>,
+        /// The APY the strategy is selected on for the assets of its vaults, see
+        /// [`Pallet::selection_apy`].
+        pub apys: Vec<(AssetId, Apy<BlockNumber>)>,
+        /// The vaults routed to the strategy.
+        pub vaults: Vec<VaultId>,
+    }
+
+    /// An associated vault, as reported by [`Pallet::vault_infos`].
+    #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub struct VaultInfo<AccountId, Balance, BlockNumber, VaultId> {
+        /// Vault ID of the vault.
+        pub vault_id: VaultId,
+        /// The funds each strategy of the vault manages for it, `None` if they cannot be computed.
+        pub assets_under_management: Vec<(AccountId, Option<Balance>)>,
+        /// The current allocation of the vault, see [`VaultStrategy`].
+        pub allocation: Vec<(AccountId, Perquintill)>,
+        /// The allocation the vault converges to, see [`TargetAllocations`].
+        pub target_allocation: Option<Vec<(AccountId, Perquintill)>>,
+        /// The ongoing migration of the vault, see [`Migrations`].
+        pub migration: Option<Migration<AccountId, BlockNumber>>,
+    }
+
+    /// The custom [`InvalidTransaction`] code of a target allocation proposal out of bounds.
+    pub const INVALID_ALLOCATION: u8 = 1;
+
     // ---------------------------------------------------------------------------------------------
     //                                          Runtime Storage
     // ---------------------------------------------------------------------------------------------
@@ -163,8 +334,194 @@ pub mod pallet {
     pub type AssociatedVaults<T: Config> =
         StorageValue<_, BoundedBTreeSet<T::VaultId, T::MaxAssociatedVaults>, ValueQuery>;
 
-    // TODO: (Nevin)
-    //  - we need a way of mapping a vault_id to its associated strategy
+    /// The registered strategies currently eligible to receive the funds of the associated vaults.
+    #[pallet::storage]
+    pub type WhitelistedStrategies<T: Config> =
+        StorageValue<_, BoundedBTreeSet<T::AccountId, T::MaxWhitelistedStrategies>>;
+
+    /// The whitelisted strategies each associated vault is routed to, with the share of the vault
+    /// each of them manages.
+    #[pallet::storage]
+    #[pallet::getter(fn vault_strategy)]
+    pub type VaultStrategy<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::VaultId,
+        BoundedBTreeMap<T::AccountId, Perquintill, T::MaxWhitelistedStrategies>,
+        OptionQuery,
+    >;
+
+    /// The associated vaults routed to each strategy, the reverse index of [`VaultStrategy`].
+    #[pallet::storage]
+    #[pallet::getter(fn strategy_vaults)]
+    pub type StrategyVaults<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedBTreeSet<T::VaultId, T::MaxAssociatedVaults>,
+        OptionQuery,
+    >;
+
+    /// Whether the strategy is halted, in which case no vault can be associated nor reallocated.
+    #[pallet::storage]
+    #[pallet::getter(fn halted)]
+    pub type Halted<T: Config> = StorageValue<_, bool>;
+
+    /// The registered strategies halted along with the strategy, the only ones started again
+    /// with it. Strategies halted on their own, e.g. by their circuit breaker, are left to
+    /// governance.
+    #[pallet::storage]
+    pub type HaltedStrategies<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// The governance limits the target allocations are computed under, the default ones until
+    /// governance sets them.
+    #[pallet::storage]
+    pub type CurrentAllocationLimits<T: Config> = StorageValue<_, AllocationLimits>;
+
+    /// The maximum share of a vault each strategy may manage, strategies without a cap may manage
+    /// whole vaults.
+    #[pallet::storage]
+    #[pallet::getter(fn allocation_caps)]
+    pub type AllocationCaps<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Perquintill, OptionQuery>;
+
+    /// The allocations the vaults converge toward on every rebalance, see [`VaultStrategy`] for
+    /// their current allocations.
+    #[pallet::storage]
+    #[pallet::getter(fn target_allocations)]
+    pub type TargetAllocations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::VaultId,
+        BoundedBTreeMap<T::AccountId, Perquintill, T::MaxWhitelistedStrategies>,
+        OptionQuery,
+    >;
+
+    /// The ongoing migrations of the associated vaults, moving a chunk of the vault to its new
+    /// strategy on every block until none is left on the other ones.
+    #[pallet::storage]
+    #[pallet::getter(fn migrations)]
+    pub type Migrations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::VaultId,
+        Migration<T::AccountId, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The last block at which each vault moved toward its target allocation.
+    #[pallet::storage]
+    #[pallet::getter(fn last_converged)]
+    pub type LastConverged<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::VaultId, T::BlockNumber, OptionQuery>;
+
+    /// The latest APY samples of each whitelisted strategy for the assets of the associated
+    /// vaults, kept as a ring once full: a new sample overwrites the oldest one, see
+    /// [`ApyHistoryHead`].
+    #[pallet::storage]
+    pub type ApyHistory<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AssetId,
+        BoundedVec<Apy<T::BlockNumber>, T::MaxApySamples>,
+        OptionQuery,
+    >;
+
+    /// The position of the oldest sample in each full [`ApyHistory`], the next one overwritten.
+    #[pallet::storage]
+    pub type ApyHistoryHead<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AssetId,
+        u32,
+        OptionQuery,
+    >;
+
+    /// The exponential moving average of the APY samples of each whitelisted strategy, used in
+    /// place of the instantaneous APY to select strategies. See [`ApyHistory`] for the samples.
+    #[pallet::storage]
+    #[pallet::getter(fn averaged_apy)]
+    pub type AveragedApy<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AssetId,
+        Apy<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The schedules of the rebalances of the registered strategies.
+    #[pallet::storage]
+    #[pallet::getter(fn rebalance_schedules)]
+    pub type RebalanceSchedules<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        RebalanceSchedule<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The pending and ongoing reallocations of the associated vaults.
+    #[pallet::storage]
+    #[pallet::getter(fn reallocations)]
+    pub type Reallocations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::VaultId,
+        Reallocation<T::AccountId, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    // ---------------------------------------------------------------------------------------------
+    //                                           Genesis config
+    // ---------------------------------------------------------------------------------------------
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// The registered strategies whitelisted from genesis.
+        pub whitelisted_strategies: Vec<T::AccountId>,
+        /// Whether the strategy starts halted. Unlike [`halt`](Pallet::halt), it is not
+        /// propagated to the registered strategies, which start halted through their own genesis
+        /// config, as halting them stops their vaults, which may not exist yet at genesis.
+        pub is_halted: bool,
+        /// The vaults associated from genesis, routed to the whitelisted strategies.
+        pub associated_vaults: Vec<T::VaultId>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                whitelisted_strategies: Vec::new(),
+                is_halted: false,
+                associated_vaults: Vec::new(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            // An inconsistent genesis is a misconfiguration of the chain spec, it must not start
+            for strategy in &self.whitelisted_strategies {
+                Pallet::<T>::do_whitelist_strategy(strategy)
+                    .expect("genesis strategies must be registered and not exceed the whitelist");
+            }
+            // Vaults are associated before halting, as a halted strategy rejects new vaults
+            Halted::<T>::put(false);
+            for vault_id in &self.associated_vaults {
+                <Pallet<T> as InstrumentalProtocolStrategy>::associate_vault(vault_id)
+                    .expect("genesis vaults must be unique and not exceed the associated vaults");
+            }
+            Halted::<T>::put(self.is_halted);
+        }
+    }
 
     // ---------------------------------------------------------------------------------------------
     //                                          Runtime Events
@@ -179,6 +536,229 @@ pub mod pallet {
             /// Vault ID of associated vault.
             vault_id: T::VaultId,
         },
+
+        /// Vault successfully dissociated from this strategy.
+        DissociatedVault {
+            /// Vault ID of dissociated vault.
+            vault_id: T::VaultId,
+        },
+
+        /// Strategy added to the whitelist.
+        StrategyWhitelisted {
+            /// Account ID of the whitelisted strategy.
+            strategy: T::AccountId,
+        },
+
+        /// Strategy removed from the whitelist.
+        StrategyDelisted {
+            /// Account ID of the delisted strategy.
+            strategy: T::AccountId,
+        },
+
+        /// Vault moved away from a delisted strategy.
+        VaultMigratedAway {
+            /// Vault ID of migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the delisted strategy.
+            strategy: T::AccountId,
+        },
+
+        /// The strategy and every registered strategy were halted.
+        Halted,
+
+        /// The strategy and the registered strategies it halted were started after halting.
+        Unhalted,
+
+        /// A registered strategy could not be halted along with the strategy, which was halted
+        /// nonetheless.
+        StrategyNotHalted {
+            /// Account ID of the strategy still running.
+            strategy: T::AccountId,
+            /// The error the strategy failed to halt with.
+            error: DispatchError,
+        },
+
+        /// A registered strategy was left halted as the strategy started, since it had not been
+        /// halted along with it or could not be started, e.g. after its circuit breaker tripped.
+        StrategyLeftHalted {
+            /// Account ID of the strategy left halted.
+            strategy: T::AccountId,
+        },
+
+        /// A strategy started outperforming the current strategy of a vault, the vault will be
+        /// reallocated once the gain has been sustained for [`Config::ReallocationDelay`] blocks.
+        ReallocationProposed {
+            /// Vault ID of the vault to reallocate.
+            vault_id: T::VaultId,
+            /// Account ID of the current strategy.
+            from: T::AccountId,
+            /// Account ID of the outperforming strategy.
+            to: T::AccountId,
+        },
+
+        /// A pending or ongoing reallocation was dropped as the gain no longer holds.
+        ReallocationCancelled {
+            /// Vault ID of the vault that was being reallocated.
+            vault_id: T::VaultId,
+            /// Account ID of the current strategy.
+            from: T::AccountId,
+            /// Account ID of the formerly outperforming strategy.
+            to: T::AccountId,
+        },
+
+        /// A part of the share of a vault was moved to the outperforming strategy.
+        ReallocationProgressed {
+            /// Vault ID of the vault being reallocated.
+            vault_id: T::VaultId,
+            /// Account ID of the current strategy.
+            from: T::AccountId,
+            /// Account ID of the outperforming strategy.
+            to: T::AccountId,
+            /// The share of the vault moved in this block.
+            moved: Perquintill,
+        },
+
+        /// The whole share of a vault was moved to the outperforming strategy.
+        ReallocationCompleted {
+            /// Vault ID of the reallocated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the former strategy.
+            from: T::AccountId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+        },
+
+        /// Governance updated the limits the target allocations are computed under.
+        AllocationLimitsUpdated {
+            /// The new limits.
+            limits: AllocationLimits,
+        },
+
+        /// Governance updated the maximum share of a vault a strategy may manage.
+        AllocationCapUpdated {
+            /// Account ID of the capped strategy.
+            strategy: T::AccountId,
+            /// The new cap, `None` if the strategy is no longer capped.
+            cap: Option<Perquintill>,
+        },
+
+        /// A new target allocation was computed for a vault. See [`TargetAllocations`] for
+        /// details.
+        TargetAllocationUpdated {
+            /// Vault ID of the vault.
+            vault_id: T::VaultId,
+        },
+
+        /// A vault moved toward its target allocation.
+        TargetAllocationProgressed {
+            /// Vault ID of the vault.
+            vault_id: T::VaultId,
+            /// The share of the vault moved in this block.
+            moved: Perquintill,
+        },
+
+        /// Governance started migrating a vault to a single strategy.
+        MigrationStarted {
+            /// Vault ID of the migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+            /// The share of the vault moved on each block.
+            chunk: Perquintill,
+        },
+
+        /// A chunk of a migrating vault was moved.
+        MigrationProgressed {
+            /// Vault ID of the migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+            /// The share of the vault moved.
+            moved: Perquintill,
+        },
+
+        /// A chunk of a migrating vault failed to move, the migration waits to be resumed.
+        MigrationInterrupted {
+            /// Vault ID of the migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+        },
+
+        /// Governance resumed an interrupted migration.
+        MigrationResumed {
+            /// Vault ID of the migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+        },
+
+        /// A migration was stopped before completion, the vault keeps the allocation reached so
+        /// far.
+        MigrationAborted {
+            /// Vault ID of the migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+        },
+
+        /// The whole vault was moved to its new strategy.
+        MigrationCompleted {
+            /// Vault ID of the migrated vault.
+            vault_id: T::VaultId,
+            /// Account ID of the new strategy.
+            to: T::AccountId,
+        },
+
+        /// Governance set or cleared the rebalance schedule of a strategy.
+        RebalanceScheduleUpdated {
+            /// Account ID of the strategy.
+            strategy: T::AccountId,
+            /// The new interval between two rebalances, `None` if no longer scheduled.
+            interval: Option<T::BlockNumber>,
+        },
+
+        /// A scheduled rebalance of a strategy succeeded.
+        ScheduledRebalanceSucceeded {
+            /// Account ID of the strategy.
+            strategy: T::AccountId,
+        },
+
+        /// A scheduled rebalance of a strategy failed, the next one is delayed accordingly.
+        ScheduledRebalanceFailed {
+            /// Account ID of the strategy.
+            strategy: T::AccountId,
+            /// The number of rebalances that failed in a row.
+            consecutive_failures: u32,
+            /// The first block at which the next rebalance may run.
+            next_run: T::BlockNumber,
+        },
+
+        /// A vault could not be rebalanced, its changes were rolled back while the other vaults
+        /// were rebalanced.
+        UnableToRebalanceVault {
+            /// Vault ID of the vault.
+            vault_id: T::VaultId,
+            /// The error the rebalance of the vault failed with.
+            error: DispatchError,
+        },
+
+        /// A strategy of a vault could not rebalance it, its changes were rolled back while the
+        /// other strategies of the vault were rebalanced.
+        StrategyNotRebalanced {
+            /// Vault ID of the vault.
+            vault_id: T::VaultId,
+            /// Account ID of the strategy.
+            strategy: T::AccountId,
+            /// The error the rebalance of the strategy failed with.
+            error: DispatchError,
+        },
+
+        /// The target allocation of a vault was dropped as one of its strategies was delisted.
+        TargetAllocationCleared {
+            /// Vault ID of the vault.
+            vault_id: T::VaultId,
+        },
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -192,6 +772,87 @@ pub mod pallet {
         VaultAlreadyAssociated,
 
         TooManyAssociatedStrategies,
+
+        /// The Vault is not associated with this strategy. See [`AssociatedVaults`] for details.
+        VaultNotAssociated,
+
+        /// None of the strategies is able to deploy the asset.
+        NoStrategySupportsAsset,
+
+        /// Every strategy able to deploy the asset is halted.
+        AllStrategiesHalted,
+
+        /// Every running strategy able to deploy the asset has reached its
+        /// [`max_capacity`](traits::strategy::StrategyMetadata::max_capacity).
+        NoStrategyHasCapacity,
+
+        /// None of the eligible strategies was able to report its APY for the asset.
+        NoStrategyApyAvailable,
+
+        /// The strategy is not part of [`Config::Strategies`].
+        StrategyNotRegistered,
+
+        /// The strategy is already whitelisted. See [`WhitelistedStrategies`] for details.
+        StrategyAlreadyWhitelisted,
+
+        /// The strategy is not whitelisted. See [`WhitelistedStrategies`] for details.
+        StrategyNotWhitelisted,
+
+        /// Exceeds the maximum number of strategies that can be whitelisted. See
+        /// [`Config::MaxWhitelistedStrategies`] for details.
+        TooManyWhitelistedStrategies,
+
+        /// The strategy is halted. See [`Halted`] for details.
+        Halted,
+
+        /// The strategy is not able to deploy the asset of the vault.
+        AssetNotSupported,
+
+        /// The strategy has reached its
+        /// [`max_capacity`](traits::strategy::StrategyMetadata::max_capacity).
+        StrategyAtCapacity,
+
+        /// Storage is not initialized (have `None` value).
+        StorageIsNotInitialized,
+
+        /// The allocation limits are inconsistent, e.g. no strategy is required.
+        InvalidAllocationLimits,
+
+        /// Fewer strategies than [`AllocationLimits::min_strategies`] are eligible for the asset
+        /// of the vault.
+        InsufficientDiversification,
+
+        /// The caps of the eligible strategies do not add up to the whole vault. See
+        /// [`AllocationCaps`] for details.
+        AllocationCapsTooLow,
+
+        /// The proposed allocation does not cover the share of the vault routed to strategies, or
+        /// gives a share to a strategy unable to deploy the asset of the vault.
+        InvalidProposedAllocation,
+
+        /// The risk score of a strategy exceeds [`AllocationLimits::max_risk_score`].
+        StrategyTooRisky,
+
+        /// A strategy would manage more than its cap. See [`AllocationCaps`] for details.
+        AllocationCapExceeded,
+
+        /// Strategies cannot be rebalanced on every block, the interval must not be zero.
+        InvalidRebalanceInterval,
+
+        /// The vault is already being migrated. See [`Migrations`] for details.
+        MigrationInProgress,
+
+        /// The vault is not being migrated. See [`Migrations`] for details.
+        NoMigration,
+
+        /// The migration of the vault is running, only interrupted migrations can be resumed.
+        MigrationNotInterrupted,
+
+        /// The vault is already entirely routed to the strategy it would be migrated to.
+        VaultAlreadyOnStrategy,
+
+        /// Migrations must move a non-zero share of the vault on each block.
+        InvalidMigrationChunk,
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -199,14 +860,293 @@ pub mod pallet {
     // ---------------------------------------------------------------------------------------------
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let weight = Self::progress_migrations();
+
+            let period = T::ApySamplingPeriod::get();
+            if period.is_zero() || !(now % period).is_zero() {
+                return weight;
+            }
+            weight.saturating_add(T::WeightInfo::sample_apys(Self::sample_apys(now)))
+        }
+
+        fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            Self::run_scheduled_rebalances(now, remaining_weight)
+        }
+
+        fn offchain_worker(now: T::BlockNumber) {
+            // Proposals are computed right after the APYs were sampled
+            let period = T::ApySamplingPeriod::get();
+            if period.is_zero() || !(now % period).is_zero() {
+                return;
+            }
+            Self::propose_allocations(now);
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    //                                        Unsigned Validation
+    // ---------------------------------------------------------------------------------------------
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let (vault_id, allocation, block_number) = match call {
+                Call::propose_allocation {
+                    vault_id,
+                    allocation,
+                    block_number,
+                } => (vault_id, allocation, block_number),
+                _ => return InvalidTransaction::Call.into(),
+            };
+
+            // Only proposals computed during the current sampling period are accepted
+            let now = frame_system::Pallet::<T>::block_number();
+            let period = T::ApySamplingPeriod::get();
+            if *block_number > now {
+                return InvalidTransaction::Future.into();
+            }
+            if now.saturating_sub(*block_number) >= period {
+                return InvalidTransaction::Stale.into();
+            }
+            if Self::ensure_valid_allocation(vault_id, &allocation.clone().into_inner()).is_err() {
+                return InvalidTransaction::Custom(INVALID_ALLOCATION).into();
+            }
+
+            ValidTransaction::with_tag_prefix("InstrumentalStrategyAllocation")
+                .priority(T::UnsignedPriority::get())
+                .and_provides((vault_id, block_number))
+                .longevity(period.saturated_into::<u64>())
+                .propagate(true)
+                .build()
+        }
+    }
 
     // ---------------------------------------------------------------------------------------------
     //                                            Extrinsics
     // ---------------------------------------------------------------------------------------------
 
     #[pallet::call]
-    impl<T: Config> Pallet<T> {}
+    impl<T: Config> Pallet<T> {
+        /// Make a registered strategy eligible for the funds of the associated vaults.
+        ///
+        /// Emits [`StrategyWhitelisted`](Event::StrategyWhitelisted) event when successful.
+        #[pallet::weight(T::WeightInfo::whitelist_strategy())]
+        pub fn whitelist_strategy(
+            origin: OriginFor<T>,
+            strategy: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Self::do_whitelist_strategy(&strategy)?;
+            Ok(().into())
+        }
+
+        /// Remove a strategy from the whitelist. Every associated vault is dissociated from it, its
+        /// funds and weight moving to the remaining whitelisted strategies supporting the asset of
+        /// the vault, or back to the vault when there is none.
+        ///
+        /// Emits [`StrategyDelisted`](Event::StrategyDelisted) event when successful.
+        #[pallet::weight(T::WeightInfo::delist_strategy(T::MaxAssociatedVaults::get()))]
+        pub fn delist_strategy(
+            origin: OriginFor<T>,
+            strategy: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Self::do_delist_strategy(&strategy)?;
+            Ok(().into())
+        }
+
+        /// Add [`Config::VaultId`] to [`AssociatedVaults`] storage.
+        ///
+        /// Emits [`Event::AssociatedVault`] event when successful.
+        #[pallet::weight(T::WeightInfo::associate_vault())]
+        pub fn associate_vault(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            <Self as InstrumentalProtocolStrategy>::associate_vault(&vault_id)?;
+            Ok(().into())
+        }
+
+        /// Reallocate the associated vaults to the best performing strategies.
+        #[pallet::weight(T::WeightInfo::liquidity_rebalance())]
+        pub fn liquidity_rebalance(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            <Self as InstrumentalProtocolStrategy>::rebalance()?;
+            Ok(().into())
+        }
+
+        /// Set the limits the target allocations of the vaults are computed under.
+        ///
+        /// Emits [`AllocationLimitsUpdated`](Event::AllocationLimitsUpdated) event when successful.
+        #[pallet::weight(T::WeightInfo::set_allocation_limits())]
+        pub fn set_allocation_limits(
+            origin: OriginFor<T>,
+            limits: AllocationLimits,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            ensure!(
+                limits.min_strategies > 0,
+                Error::<T>::InvalidAllocationLimits
+            );
+            CurrentAllocationLimits::<T>::put(limits);
+            Self::deposit_event(Event::AllocationLimitsUpdated { limits });
+            Ok(().into())
+        }
+
+        /// Set the maximum share of a vault `strategy` may manage, `None` to lift the cap.
+        ///
+        /// Emits [`AllocationCapUpdated`](Event::AllocationCapUpdated) event when successful.
+        #[pallet::weight(T::WeightInfo::set_allocation_cap())]
+        pub fn set_allocation_cap(
+            origin: OriginFor<T>,
+            strategy: T::AccountId,
+            cap: Option<Perquintill>,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            ensure!(
+                T::Strategies::contains(&strategy),
+                Error::<T>::StrategyNotRegistered
+            );
+            AllocationCaps::<T>::set(&strategy, cap);
+            Self::deposit_event(Event::AllocationCapUpdated { strategy, cap });
+            Ok(().into())
+        }
+
+        /// Compute the target allocation of `vault_id` maximizing its expected APY under the
+        /// allocation limits and caps. The vault converges toward it on every rebalance.
+        ///
+        /// Emits [`TargetAllocationUpdated`](Event::TargetAllocationUpdated) event when
+        /// successful.
+        #[pallet::weight(T::WeightInfo::optimize_allocation(T::MaxWhitelistedStrategies::get()))]
+        pub fn optimize_allocation(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            let target = Self::compute_target_allocation(&vault_id)?;
+            Self::set_target_allocation(&vault_id, target)?;
+            Ok(().into())
+        }
+
+        /// Store the target allocation of `vault_id` computed by the off-chain worker at
+        /// `block_number`, once checked against the allocation limits, caps and strategies.
+        ///
+        /// Emits [`TargetAllocationUpdated`](Event::TargetAllocationUpdated) event when
+        /// successful.
+        #[pallet::weight(T::WeightInfo::propose_allocation(T::MaxWhitelistedStrategies::get()))]
+        pub fn propose_allocation(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+            allocation: BoundedBTreeMap<T::AccountId, Perquintill, T::MaxWhitelistedStrategies>,
+            _block_number: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            let allocation = allocation.into_inner();
+            Self::ensure_valid_allocation(&vault_id, &allocation)?;
+            Self::set_target_allocation(&vault_id, allocation)?;
+            Ok(().into())
+        }
+
+        /// Move the whole `vault_id` to `to_strategy`, `chunk` of the vault on every block.
+        ///
+        /// Emits [`MigrationStarted`](Event::MigrationStarted) event when successful.
+        #[pallet::weight(T::WeightInfo::migrate_vault_strategy())]
+        pub fn migrate_vault_strategy(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+            to_strategy: T::AccountId,
+            chunk: Perquintill,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Self::do_migrate_vault_strategy(&vault_id, to_strategy, chunk)?;
+            Ok(().into())
+        }
+
+        /// Resume the interrupted migration of `vault_id`.
+        ///
+        /// Emits [`MigrationResumed`](Event::MigrationResumed) event when successful.
+        #[pallet::weight(T::WeightInfo::resume_vault_migration())]
+        pub fn resume_vault_migration(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Migrations::<T>::try_mutate(vault_id, |migration| -> DispatchResult {
+                let migration = migration.as_mut().ok_or(Error::<T>::NoMigration)?;
+                ensure!(
+                    migration.state == State::Normal,
+                    Error::<T>::MigrationNotInterrupted
+                );
+                migration.state = State::Transferring;
+                Self::deposit_event(Event::MigrationResumed {
+                    vault_id,
+                    to: migration.to.clone(),
+                });
+                Ok(())
+            })?;
+            Ok(().into())
+        }
+
+        /// Stop migrating `vault_id`, leaving it with the allocation reached so far.
+        ///
+        /// Emits [`MigrationAborted`](Event::MigrationAborted) event when successful.
+        #[pallet::weight(T::WeightInfo::abort_vault_migration())]
+        pub fn abort_vault_migration(
+            origin: OriginFor<T>,
+            vault_id: T::VaultId,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            ensure!(
+                Migrations::<T>::contains_key(vault_id),
+                Error::<T>::NoMigration
+            );
+            Self::abort_migration(&vault_id);
+            Ok(().into())
+        }
+
+        /// Rebalance `strategy` every `interval` blocks from `on_idle`, or stop rebalancing it
+        /// when `interval` is `None`.
+        ///
+        /// Emits [`RebalanceScheduleUpdated`](Event::RebalanceScheduleUpdated) event when
+        /// successful.
+        #[pallet::weight(T::WeightInfo::set_rebalance_schedule())]
+        pub fn set_rebalance_schedule(
+            origin: OriginFor<T>,
+            strategy: T::AccountId,
+            interval: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            Self::do_set_rebalance_schedule(&strategy, interval)?;
+            Ok(().into())
+        }
+
+        /// Halt the strategy and every registered strategy.
+        ///
+        /// Emits [`StrategyNotHalted`](Event::StrategyNotHalted) event for each registered strategy
+        /// failing to halt, and [`Halted`](Event::Halted) event when successful.
+        #[pallet::weight(T::WeightInfo::halt())]
+        pub fn halt(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            <Self as InstrumentalProtocolStrategy>::halt()?;
+            Ok(().into())
+        }
+
+        /// Continue the strategy and the registered strategies it halted after halting.
+        ///
+        /// Emits [`StrategyLeftHalted`](Event::StrategyLeftHalted) event for each registered
+        /// strategy that stays halted, and [`Unhalted`](Event::Unhalted) event when successful.
+        #[pallet::weight(T::WeightInfo::start())]
+        pub fn start(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            T::ExternalOrigin::ensure_origin(origin)?;
+            <Self as InstrumentalProtocolStrategy>::start()?;
+            Ok(().into())
+        }
+    }
 
     // ---------------------------------------------------------------------------------------------
     //                                   Instrumental Dynamic Strategy
@@ -218,19 +1158,34 @@ pub mod pallet {
     impl<T: Config> InstrumentalDynamicStrategy for Pallet<T> {
         type AccountId = T::AccountId;
         type AssetId = T::AssetId;
+        type MaxStrategies = T::MaxWhitelistedStrategies;
+
+        fn get_optimum_strategy_for(asset: T::AssetId) -> Result<T::AccountId, DispatchError> {
+            Self::ranked_strategies(asset, &[])?
+                .into_iter()
+                .next()
+                .map(|(strategy, _, _)| strategy)
+                .ok_or_else(|| Error::<T>::NoStrategyApyAvailable.into())
+        }
 
-        // TODO: (Nevin)
-        //  - we need a way to store a vector of all strategies that are whitelisted
+        fn get_diversified_allocation_for(
+            asset: T::AssetId,
+            percent_deployable: Perquintill,
+        ) -> Result<BTreeMap<T::AccountId, Perquintill>, DispatchError> {
+            let strategies = Self::supporting_strategies(&asset);
+            ensure!(!strategies.is_empty(), Error::<T>::NoStrategySupportsAsset);
 
-        // fn get_strategies() -> [dyn InstrumentalProtocolStrategy<
-        // 	AssetId = T::AssetId,
-        // 	VaultId = T::VaultId
-        // >] {
-        // 	vec![&T::PabloStrategy]
-        // }
+            Ok(Self::split_evenly(
+                strategies
+                    .into_iter()
+                    .map(|(strategy, _)| strategy)
+                    .collect(),
+                percent_deployable,
+            ))
+        }
 
-        fn get_optimum_strategy_for(_asset: T::AssetId) -> Result<T::AccountId, DispatchError> {
-            Ok(T::PabloStrategy::account_id())
+        fn is_eligible(strategy: &T::AccountId) -> bool {
+            T::Strategies::contains(strategy) && Self::whitelisted_strategies().contains(strategy)
         }
     }
 
@@ -241,6 +1196,8 @@ pub mod pallet {
     impl<T: Config> InstrumentalProtocolStrategy for Pallet<T> {
         type AccountId = T::AccountId;
         type AssetId = T::AssetId;
+        type Balance = T::Balance;
+        type BlockNumber = T::BlockNumber;
         type PoolId = T::PoolId;
         type VaultId = T::VaultId;
 
@@ -250,17 +1207,7 @@ pub mod pallet {
 
         #[transactional]
         fn associate_vault(vault_id: &Self::VaultId) -> DispatchResult {
-            // TODO: (Nevin)
-            //  - cycle through all whitelisted strategies and associate the vault with the strategy
-            //    with the highest earning apy
-
-            // let asset_id = T::Vault::asset_id(vault_id)?;
-
-            // let optimum_strategy = Self::get_strategies().iter()
-            // 	.max_by_key(|strategy| strategy.get_apy(asset_id)?);
-
-            // optimum_strategy.associate_vault(vault_id)?;
-
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
             AssociatedVaults::<T>::try_mutate(|vaults| {
                 ensure!(
                     !vaults.contains(vault_id),
@@ -271,7 +1218,12 @@ pub mod pallet {
                     .try_insert(*vault_id)
                     .map_err(|_| Error::<T>::TooManyAssociatedStrategies)?;
 
-                T::PabloStrategy::associate_vault(vault_id)?;
+                let allocation = Self::allocation_of(vault_id)?;
+                for strategy in allocation.keys() {
+                    T::Strategies::associate_vault(strategy, vault_id)
+                        .map_err(Self::registry_error)?;
+                }
+                Self::set_vault_strategy(vault_id, Some(allocation))?;
 
                 Self::deposit_event(Event::AssociatedVault {
                     vault_id: *vault_id,
@@ -281,34 +1233,1519 @@ pub mod pallet {
             })
         }
 
+        #[transactional]
+        fn dissociate_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            AssociatedVaults::<T>::try_mutate(|vaults| {
+                ensure!(vaults.remove(vault_id), Error::<T>::VaultNotAssociated);
+
+                for strategy in Self::strategies_of(vault_id) {
+                    T::Strategies::dissociate_vault(&strategy, vault_id)
+                        .map_err(Self::registry_error)?;
+                }
+                Self::set_vault_strategy(vault_id, None)?;
+                TargetAllocations::<T>::remove(vault_id);
+                LastConverged::<T>::remove(vault_id);
+                Self::cancel_reallocation(vault_id);
+                Self::abort_migration(vault_id);
+
+                Self::deposit_event(Event::DissociatedVault {
+                    vault_id: *vault_id,
+                });
+
+                Ok(())
+            })
+        }
+
+        #[transactional]
         fn rebalance() -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            let now = frame_system::Pallet::<T>::block_number();
+            for vault_id in Self::associated_vaults() {
+                // Migrating vaults are moved by governance, not by performance
+                if Migrations::<T>::contains_key(vault_id) {
+                    continue;
+                }
+                // A vault failing to rebalance must neither leave its partial changes behind nor
+                // block the other vaults
+                let result = with_transaction(|| {
+                    let result = match Self::target_allocations(vault_id) {
+                        Some(target) => Self::converge_allocation(&vault_id, target, now),
+                        None => Self::do_reallocate(&vault_id, now),
+                    };
+                    match result {
+                        Ok(()) => TransactionOutcome::Commit(Ok(())),
+                        Err(error) => TransactionOutcome::Rollback(Err(error)),
+                    }
+                });
+                if let Err(error) = result {
+                    Self::deposit_event(Event::UnableToRebalanceVault { vault_id, error });
+                }
+            }
             Ok(())
         }
 
-        fn get_apy(asset: Self::AssetId) -> Result<u128, DispatchError> {
-            // TODO: (Nevin)
-            //  - cycle through all whitelisted strategies and return highest available apy
+        #[transactional]
+        fn rebalance_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            // Strategies keep their allotment, which must follow the funds deposited since
+            let value = Self::value_of(vault_id)?;
+            let amounts = Self::vault_strategy(vault_id)
+                .ok_or(Error::<T>::VaultNotAssociated)?
+                .into_iter()
+                .map(|(strategy, weight)| (strategy, Self::share_of(weight, value)))
+                .collect();
+            Self::allot_amounts(vault_id, amounts)?;
+            // A strategy failing to rebalance the vault must not undo the rebalance of the others
+            for strategy in Self::strategies_of(vault_id) {
+                let result = with_transaction(|| {
+                    match T::Strategies::rebalance_vault(&strategy, vault_id) {
+                        Ok(()) => TransactionOutcome::Commit(Ok(())),
+                        Err(error) => TransactionOutcome::Rollback(Err(error)),
+                    }
+                });
+                if let Err(error) = result {
+                    Self::deposit_event(Event::StrategyNotRebalanced {
+                        vault_id: *vault_id,
+                        strategy,
+                        error: Self::registry_error(error),
+                    });
+                }
+            }
+            Ok(())
+        }
 
-            // let optimum_apy = Self::get_strategies()
-            // 	.iter()
-            // 	.map(|strategy| strategy.get_apy(asset))
-            // 	.max();
+        fn assets_under_management(vault_id: &Self::VaultId) -> Result<T::Balance, DispatchError> {
+            Self::ensure_associated(vault_id)?;
+            Self::strategies_of(vault_id)
+                .iter()
+                .try_fold(T::Balance::zero(), |total, strategy| {
+                    let assets = T::Strategies::assets_under_management(strategy, vault_id)
+                        .map_err(Self::registry_error)?;
+                    total
+                        .checked_add(&assets)
+                        .ok_or_else(|| ArithmeticError::Overflow.into())
+                })
+        }
 
-            // Ok(optimum_apy)
+        #[transactional]
+        fn allot(vault_id: &Self::VaultId, amount: T::Balance) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            // Each strategy is allotted the part of `amount` its weight is of the routed share
+            let routed = Self::routed_share_of(vault_id)?;
+            let amounts = Self::vault_strategy(vault_id)
+                .ok_or(Error::<T>::VaultNotAssociated)?
+                .into_iter()
+                .map(|(strategy, weight)| {
+                    let share =
+                        Perquintill::from_rational(weight.deconstruct(), routed.deconstruct());
+                    (strategy, Self::share_of(share, amount))
+                })
+                .collect();
+            Self::allot_amounts(vault_id, amounts)
+        }
 
-            T::PabloStrategy::get_apy(asset)
+        fn get_apy(asset: Self::AssetId) -> Result<Apy<T::BlockNumber>, DispatchError> {
+            Self::whitelisted_strategies()
+                .iter()
+                .try_fold(Apy::zero(), |optimum_apy, strategy| {
+                    let apy =
+                        T::Strategies::get_apy(strategy, asset).map_err(Self::registry_error)?;
+                    Ok(if apy.is_better_than(&optimum_apy) {
+                        apy
+                    } else {
+                        optimum_apy
+                    })
+                })
         }
 
+        #[transactional]
         fn halt() -> DispatchResult {
-            unimplemented!()
+            // A strategy failing to halt must not keep the other ones, nor the strategy, running
+            for strategy in T::Strategies::accounts() {
+                let halted = T::Strategies::is_halted(&strategy).and_then(|halted| {
+                    if halted {
+                        Ok(false)
+                    } else {
+                        T::Strategies::halt(&strategy).map(|()| true)
+                    }
+                });
+                match halted {
+                    Ok(true) => HaltedStrategies::<T>::insert(&strategy, ()),
+                    Ok(false) => (),
+                    Err(error) => Self::deposit_event(Event::StrategyNotHalted {
+                        strategy,
+                        error: Self::registry_error(error),
+                    }),
+                }
+            }
+            Halted::<T>::put(true);
+            Self::deposit_event(Event::Halted);
+            Ok(())
         }
 
+        #[transactional]
         fn start() -> DispatchResult {
-            unimplemented!()
+            for strategy in T::Strategies::accounts() {
+                let started = HaltedStrategies::<T>::take(&strategy).is_some()
+                    && T::Strategies::start(&strategy).is_ok();
+                if !started && T::Strategies::is_halted(&strategy).unwrap_or(true) {
+                    Self::deposit_event(Event::StrategyLeftHalted { strategy });
+                }
+            }
+            Halted::<T>::put(false);
+            Self::deposit_event(Event::Unhalted);
+            Ok(())
+        }
+
+        fn halt_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            for strategy in Self::strategies_of(vault_id) {
+                T::Strategies::halt_vault(&strategy, vault_id).map_err(Self::registry_error)?;
+            }
+            Ok(())
+        }
+
+        fn start_vault(vault_id: &Self::VaultId) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            for strategy in Self::strategies_of(vault_id) {
+                T::Strategies::start_vault(&strategy, vault_id).map_err(Self::registry_error)?;
+            }
+            Ok(())
         }
 
         fn is_halted() -> Result<bool, DispatchError> {
-            unimplemented!()
+            Halted::<T>::get().ok_or_else(|| Error::<T>::StorageIsNotInitialized.into())
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    //                                      Low Level Functionality
+    // ---------------------------------------------------------------------------------------------
+
+    impl<T: Config> Pallet<T> {
+        /// Returns the account and metadata of every whitelisted strategy able to deploy `asset`.
+        fn supporting_strategies(
+            asset: &T::AssetId,
+        ) -> Vec<(T::AccountId, StrategyMetadata<T::AssetId, T::Balance>)> {
+            Self::whitelisted_strategies()
+                .into_iter()
+                .filter_map(|strategy| {
+                    T::Strategies::metadata(&strategy)
+                        .ok()
+                        .map(|metadata| (strategy, metadata))
+                })
+                .filter(|(_, metadata)| metadata.supported_assets.contains(asset))
+                .collect()
+        }
+
+        /// Whether `strategy` can receive more funds, i.e. the funds it manages for the associated
+        /// vaults are below its [`max_capacity`](StrategyMetadata::max_capacity). A strategy
+        /// whose funds cannot be computed is considered full.
+        fn has_capacity(
+            strategy: &T::AccountId,
+            metadata: &StrategyMetadata<T::AssetId, T::Balance>,
+        ) -> bool {
+            let max_capacity = match metadata.max_capacity {
+                Some(max_capacity) => max_capacity,
+                None => return true,
+            };
+
+            Self::vaults_of(strategy)
+                .iter()
+                .try_fold(T::Balance::zero(), |total, vault_id| {
+                    T::Strategies::assets_under_management(strategy, vault_id)
+                        .ok()
+                        .and_then(|balance| total.checked_add(&balance))
+                })
+                .map_or(false, |total| total < max_capacity)
+        }
+
+        /// Returns the whitelisted strategies able to receive `asset`, i.e. supporting it, running,
+        /// with capacity left and reporting an APY, from the most to the least preferred one.
+        ///
+        /// The funds being placed are considered already deployed into the `current` strategies.
+        fn ranked_strategies(
+            asset: T::AssetId,
+            current: &[T::AccountId],
+        ) -> Result<Vec<(T::AccountId, Percent, Apy<T::BlockNumber>)>, DispatchError> {
+            let strategies = Self::supporting_strategies(&asset);
+            ensure!(!strategies.is_empty(), Error::<T>::NoStrategySupportsAsset);
+
+            // A strategy whose status cannot be read is treated as halted
+            let strategies: Vec<_> = strategies
+                .into_iter()
+                .filter(|(strategy, _)| matches!(T::Strategies::is_halted(strategy), Ok(false)))
+                .collect();
+            ensure!(!strategies.is_empty(), Error::<T>::AllStrategiesHalted);
+
+            let strategies: Vec<_> = strategies
+                .into_iter()
+                .filter(|(strategy, metadata)| Self::has_capacity(strategy, metadata))
+                .collect();
+            ensure!(!strategies.is_empty(), Error::<T>::NoStrategyHasCapacity);
+
+            // A strategy failing to report its APY is skipped rather than failing the selection
+            let total_value_locked = Self::total_value_locked();
+            let mut strategies: Vec<_> = strategies
+                .into_iter()
+                .filter_map(|(strategy, metadata)| {
+                    Self::selection_apy(&strategy, asset).ok().map(|apy| {
+                        let score = Self::score_of(
+                            &strategy,
+                            metadata.risk_score,
+                            &apy,
+                            total_value_locked,
+                            current.contains(&strategy),
+                        );
+                        (strategy, metadata.risk_score, apy, score)
+                    })
+                })
+                .collect();
+            ensure!(!strategies.is_empty(), Error::<T>::NoStrategyApyAvailable);
+
+            strategies.sort_by(Self::preference);
+            Ok(strategies
+                .into_iter()
+                .map(|(strategy, risk_score, apy, _)| (strategy, risk_score, apy))
+                .collect())
+        }
+
+        /// Orders strategies from the most to the least preferred one: the higher score wins, then
+        /// the measured APY, then the lower risk score, then the lower account ID, so that the
+        /// selection is deterministic.
+        fn preference(
+            a: &(T::AccountId, Percent, Apy<T::BlockNumber>, FixedU128),
+            b: &(T::AccountId, Percent, Apy<T::BlockNumber>, FixedU128),
+        ) -> Ordering {
+            let (a_strategy, a_risk, a_apy, a_score) = a;
+            let (b_strategy, b_risk, b_apy, b_score) = b;
+
+            b_score
+                .cmp(a_score)
+                .then_with(|| {
+                    b_apy
+                        .last_updated
+                        .is_some()
+                        .cmp(&a_apy.last_updated.is_some())
+                })
+                .then_with(|| (a_risk, a_strategy).cmp(&(b_risk, b_strategy)))
+        }
+
+        /// Scores `strategy` with [`Config::Scoring`], `is_current` telling whether the funds being
+        /// placed are already deployed into it.
+        fn score_of(
+            strategy: &T::AccountId,
+            risk_score: Percent,
+            apy: &Apy<T::BlockNumber>,
+            total_value_locked: T::Balance,
+            is_current: bool,
+        ) -> FixedU128 {
+            let tvl_share = if total_value_locked.is_zero() {
+                Perquintill::zero()
+            } else {
+                Perquintill::from_rational(
+                    Self::value_locked_in(strategy).saturated_into::<u128>(),
+                    total_value_locked.saturated_into::<u128>(),
+                )
+            };
+
+            T::Scoring::score(&ScoreInputs {
+                apy: apy.rate,
+                risk_score,
+                tvl_share,
+                is_current,
+            })
+        }
+
+        /// The funds `strategy` manages for the vaults routed to it. Vaults whose funds cannot be
+        /// computed are not counted.
+        fn value_locked_in(strategy: &T::AccountId) -> T::Balance {
+            Self::vaults_of(strategy)
+                .iter()
+                .filter_map(|vault_id| {
+                    T::Strategies::assets_under_management(strategy, vault_id).ok()
+                })
+                .fold(T::Balance::zero(), |total, balance| {
+                    total.saturating_add(balance)
+                })
+        }
+
+        /// The funds managed by all the whitelisted strategies.
+        fn total_value_locked() -> T::Balance {
+            Self::whitelisted_strategies()
+                .iter()
+                .map(Self::value_locked_in)
+                .fold(T::Balance::zero(), |total, balance| {
+                    total.saturating_add(balance)
+                })
+        }
+
+        #[transactional]
+        fn do_whitelist_strategy(strategy: &T::AccountId) -> DispatchResult {
+            ensure!(
+                T::Strategies::contains(strategy),
+                Error::<T>::StrategyNotRegistered
+            );
+
+            WhitelistedStrategies::<T>::try_mutate(|strategies| -> DispatchResult {
+                let strategies = strategies.get_or_insert_with(BoundedBTreeSet::new);
+                ensure!(
+                    !strategies.contains(strategy),
+                    Error::<T>::StrategyAlreadyWhitelisted
+                );
+                strategies
+                    .try_insert(strategy.clone())
+                    .map_err(|_| Error::<T>::TooManyWhitelistedStrategies)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::StrategyWhitelisted {
+                strategy: strategy.clone(),
+            });
+
+            Ok(())
+        }
+
+        #[transactional]
+        fn do_delist_strategy(strategy: &T::AccountId) -> DispatchResult {
+            WhitelistedStrategies::<T>::try_mutate(|strategies| -> DispatchResult {
+                ensure!(
+                    strategies
+                        .as_mut()
+                        .map_or(false, |strategies| strategies.remove(strategy)),
+                    Error::<T>::StrategyNotWhitelisted
+                );
+                Ok(())
+            })?;
+
+            // Reallocations, migrations and target allocations only exist for associated vaults
+            for vault_id in Self::associated_vaults() {
+                if Self::reallocations(vault_id).map_or(false, |reallocation| {
+                    &reallocation.from == strategy || &reallocation.to == strategy
+                }) {
+                    Self::cancel_reallocation(&vault_id);
+                }
+                if Self::migrations(vault_id).map_or(false, |migration| &migration.to == strategy) {
+                    Self::abort_migration(&vault_id);
+                }
+                if Self::target_allocations(vault_id)
+                    .map_or(false, |target| target.contains_key(strategy))
+                {
+                    TargetAllocations::<T>::remove(vault_id);
+                    Self::deposit_event(Event::TargetAllocationCleared { vault_id });
+                }
+            }
+
+            for vault_id in Self::vaults_of(strategy) {
+                Self::migrate_away(&vault_id, strategy)?;
+                Self::deposit_event(Event::VaultMigratedAway {
+                    vault_id,
+                    strategy: strategy.clone(),
+                });
+            }
+
+            Self::deposit_event(Event::StrategyDelisted {
+                strategy: strategy.clone(),
+            });
+
+            Ok(())
+        }
+
+        /// Moves the funds `strategy` manages for `vault_id` to the whitelisted strategies
+        /// supporting the asset of the vault, the weight of `strategy` being split evenly between
+        /// them. Without such strategies the funds return to the vault and the weight is dropped,
+        /// so that the vault routes less than its deployable share until governance reallocates
+        /// it.
+        fn migrate_away(vault_id: &T::VaultId, strategy: &T::AccountId) -> DispatchResult {
+            let mut target = Self::vault_strategy(vault_id)
+                .ok_or(Error::<T>::VaultNotAssociated)?
+                .into_inner();
+            let weight = target.remove(strategy).unwrap_or_default();
+            let receivers: Vec<_> = T::Vault::asset_id(vault_id)
+                .map(|asset| Self::supporting_strategies(&asset))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(receiver, _)| receiver)
+                .collect();
+
+            if receivers.is_empty() {
+                T::Strategies::allot(strategy, vault_id, T::Balance::zero())
+                    .map_err(Self::registry_error)?;
+                T::Strategies::dissociate_vault(strategy, vault_id)
+                    .map_err(Self::registry_error)?;
+                let mut allocation =
+                    Self::vault_strategy(vault_id).ok_or(Error::<T>::VaultNotAssociated)?;
+                allocation.remove(strategy);
+                return Self::set_vault_strategy(vault_id, Some(allocation));
+            }
+
+            for (receiver, share) in Self::split_evenly(receivers, weight) {
+                let current = target.get(&receiver).copied().unwrap_or_default();
+                target.insert(receiver, current.saturating_add(share));
+            }
+            Self::stage_transfer(vault_id, &target, Perquintill::one())?;
+            Ok(())
+        }
+
+        fn do_migrate_vault_strategy(
+            vault_id: &T::VaultId,
+            to: T::AccountId,
+            chunk: Perquintill,
+        ) -> DispatchResult {
+            ensure!(!Self::is_halted()?, Error::<T>::Halted);
+            Self::ensure_associated(vault_id)?;
+            ensure!(
+                Self::whitelisted_strategies().contains(&to),
+                Error::<T>::StrategyNotWhitelisted
+            );
+            ensure!(!chunk.is_zero(), Error::<T>::InvalidMigrationChunk);
+            ensure!(
+                !Migrations::<T>::contains_key(vault_id),
+                Error::<T>::MigrationInProgress
+            );
+            ensure!(
+                Self::strategies_of(vault_id)
+                    .iter()
+                    .any(|strategy| strategy != &to),
+                Error::<T>::VaultAlreadyOnStrategy
+            );
+            // The vault must be able to stay on the strategy once migrated
+            Self::ensure_eligible(&to, &T::Vault::asset_id(vault_id)?)?;
+
+            // The migration supersedes any move driven by performance
+            Self::cancel_reallocation(vault_id);
+            if TargetAllocations::<T>::take(vault_id).is_some() {
+                LastConverged::<T>::remove(vault_id);
+                Self::deposit_event(Event::TargetAllocationCleared {
+                    vault_id: *vault_id,
+                });
+            }
+
+            Migrations::<T>::insert(
+                vault_id,
+                Migration {
+                    to: to.clone(),
+                    chunk,
+                    state: State::Transferring,
+                    since: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+            Self::deposit_event(Event::MigrationStarted {
+                vault_id: *vault_id,
+                to,
+                chunk,
+            });
+
+            Ok(())
+        }
+
+        /// Ensures `strategy` would be selected by [`compute_target_allocation`] for `asset`: it
+        /// supports the asset, is running, has capacity left and is within
+        /// [`AllocationLimits::max_risk_score`].
+        ///
+        /// [`compute_target_allocation`]: Pallet::compute_target_allocation
+        fn ensure_eligible(strategy: &T::AccountId, asset: &T::AssetId) -> DispatchResult {
+            let metadata = T::Strategies::metadata(strategy).map_err(Self::registry_error)?;
+            ensure!(
+                metadata.supported_assets.contains(asset),
+                Error::<T>::AssetNotSupported
+            );
+            // A strategy whose status cannot be read is treated as halted
+            ensure!(
+                matches!(T::Strategies::is_halted(strategy), Ok(false)),
+                Error::<T>::Halted
+            );
+            ensure!(
+                Self::has_capacity(strategy, &metadata),
+                Error::<T>::StrategyAtCapacity
+            );
+            ensure!(
+                metadata.risk_score <= Self::allocation_limits().max_risk_score,
+                Error::<T>::StrategyTooRisky
+            );
+            Ok(())
+        }
+
+        fn abort_migration(vault_id: &T::VaultId) {
+            if let Some(Migration { to, .. }) = Migrations::<T>::take(vault_id) {
+                Self::deposit_event(Event::MigrationAborted {
+                    vault_id: *vault_id,
+                    to,
+                });
+            }
+        }
+
+        /// Moves a chunk of every running migration, and returns the weight consumed. A chunk
+        /// failing to move leaves the vault as it was and interrupts its migration.
+        fn progress_migrations() -> Weight {
+            // A strategy whose halted flag is not initialized is left alone, as if halted
+            if Self::is_halted().unwrap_or(true) {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let running: Vec<_> = Migrations::<T>::iter()
+                .filter(|(_, migration)| migration.state == State::Transferring)
+                .collect();
+            let weight = T::WeightInfo::migrate_chunks(running.len() as u32);
+
+            for (vault_id, mut migration) in running {
+                let result =
+                    with_transaction(|| match Self::migrate_chunk(&vault_id, &migration) {
+                        Ok(completed) => TransactionOutcome::Commit(Ok(completed)),
+                        Err(error) => TransactionOutcome::Rollback(Err(error)),
+                    });
+                match result {
+                    Ok(true) => {
+                        Migrations::<T>::remove(vault_id);
+                        Self::deposit_event(Event::MigrationCompleted {
+                            vault_id,
+                            to: migration.to,
+                        });
+                    }
+                    Ok(false) => (),
+                    Err(_) => {
+                        migration.state = State::Normal;
+                        Self::deposit_event(Event::MigrationInterrupted {
+                            vault_id,
+                            to: migration.to.clone(),
+                        });
+                        Migrations::<T>::insert(vault_id, migration);
+                    }
+                }
+            }
+
+            weight
+        }
+
+        /// Moves up to [`Migration::chunk`] of `vault_id` from its other strategies to the new
+        /// one, and returns whether the vault is now entirely routed to the new strategy.
+        fn migrate_chunk(
+            vault_id: &T::VaultId,
+            migration: &Migration<T::AccountId, T::BlockNumber>,
+        ) -> Result<bool, DispatchError> {
+            let to = &migration.to;
+            let target = BTreeMap::from([(to.clone(), Self::routed_share_of(vault_id)?)]);
+            let (moved, completed) = Self::stage_transfer(vault_id, &target, migration.chunk)?;
+
+            if !moved.is_zero() {
+                Self::deposit_event(Event::MigrationProgressed {
+                    vault_id: *vault_id,
+                    to: to.clone(),
+                    moved,
+                });
+            }
+
+            Ok(completed)
+        }
+
+        fn do_set_rebalance_schedule(
+            strategy: &T::AccountId,
+            interval: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            ensure!(
+                T::Strategies::contains(strategy),
+                Error::<T>::StrategyNotRegistered
+            );
+
+            match interval {
+                Some(interval) => {
+                    ensure!(!interval.is_zero(), Error::<T>::InvalidRebalanceInterval);
+                    let now = frame_system::Pallet::<T>::block_number();
+                    RebalanceSchedules::<T>::mutate(strategy, |schedule| {
+                        *schedule = Some(match schedule.take() {
+                            Some(schedule) => RebalanceSchedule {
+                                interval,
+                                next_run: now.saturating_add(interval),
+                                ..schedule
+                            },
+                            None => RebalanceSchedule {
+                                interval,
+                                next_run: now.saturating_add(interval),
+                                last_run: None,
+                                consecutive_failures: 0,
+                            },
+                        });
+                    });
+                }
+                None => RebalanceSchedules::<T>::remove(strategy),
+            }
+
+            Self::deposit_event(Event::RebalanceScheduleUpdated {
+                strategy: strategy.clone(),
+                interval,
+            });
+
+            Ok(())
+        }
+
+        /// Rebalances the strategies whose rebalance is due, the most overdue first, as long as
+        /// `remaining_weight` allows it, and returns the weight consumed. A rebalance that does
+        /// not fit is left for a later block.
+        fn run_scheduled_rebalances(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let db_weight = T::DbWeight::get();
+            let mut consumed = db_weight.reads(1);
+            if remaining_weight < consumed || Self::is_halted().unwrap_or(true) {
+                return consumed.min(remaining_weight);
+            }
+
+            let mut due: Vec<_> = RebalanceSchedules::<T>::iter()
+                .filter(|(_, schedule)| schedule.next_run <= now)
+                .collect();
+            due.sort_by_key(|(_, schedule)| schedule.next_run);
+            consumed = consumed.saturating_add(db_weight.reads(due.len() as u64));
+
+            for (strategy, schedule) in due {
+                // The strategy rebalances each of its vaults
+                let vaults = Self::vaults_of(&strategy).len() as u32;
+                consumed = consumed.saturating_add(db_weight.reads(1));
+                let rebalance_weight = T::WeightInfo::keeper_rebalance(vaults);
+                if consumed.saturating_add(rebalance_weight) > remaining_weight {
+                    continue;
+                }
+                consumed = consumed.saturating_add(rebalance_weight);
+
+                // A failed rebalance must not leave its partial changes behind
+                let result = with_transaction(|| match T::Strategies::rebalance(&strategy) {
+                    Ok(()) => TransactionOutcome::Commit(Ok(())),
+                    Err(error) => TransactionOutcome::Rollback(Err(error)),
+                });
+                let schedule = Self::next_schedule(schedule, now, result.is_ok());
+                RebalanceSchedules::<T>::insert(&strategy, schedule);
+
+                if result.is_ok() {
+                    Self::deposit_event(Event::ScheduledRebalanceSucceeded { strategy });
+                } else {
+                    Self::deposit_event(Event::ScheduledRebalanceFailed {
+                        strategy,
+                        consecutive_failures: schedule.consecutive_failures,
+                        next_run: schedule.next_run,
+                    });
+                }
+            }
+
+            consumed
+        }
+
+        /// The schedule following a rebalance run at `now`: the interval is doubled for every
+        /// consecutive failure, up to [`Config::MaxRebalanceBackoff`] times.
+        fn next_schedule(
+            schedule: RebalanceSchedule<T::BlockNumber>,
+            now: T::BlockNumber,
+            succeeded: bool,
+        ) -> RebalanceSchedule<T::BlockNumber> {
+            let consecutive_failures = if succeeded {
+                0
+            } else {
+                schedule.consecutive_failures.saturating_add(1)
+            };
+            let backoff = consecutive_failures.min(T::MaxRebalanceBackoff::get());
+            let multiplier: T::BlockNumber = 1_u32.checked_shl(backoff).unwrap_or(u32::MAX).into();
+
+            RebalanceSchedule {
+                interval: schedule.interval,
+                next_run: now.saturating_add(schedule.interval.saturating_mul(multiplier)),
+                last_run: Some(now),
+                consecutive_failures,
+            }
+        }
+
+        /// Moves `vault_id` towards the best strategy for its asset once it has outperformed the
+        /// current strategy of the vault by [`Config::MinApyImprovement`] for
+        /// [`Config::ReallocationDelay`] blocks, by at most [`Config::MaxReallocationPerBlock`]
+        /// of the vault per block, the funds moving along with the shares.
+        fn do_reallocate(vault_id: &T::VaultId, now: T::BlockNumber) -> DispatchResult {
+            // A vault whose asset is unknown is left as is
+            let asset = match T::Vault::asset_id(vault_id) {
+                Ok(asset) => asset,
+                Err(_) => return Ok(()),
+            };
+            let pending = Self::reallocations(vault_id);
+            let from = match pending
+                .as_ref()
+                .map(|reallocation| reallocation.from.clone())
+            {
+                Some(from) => from,
+                None => match Self::main_strategy_of(vault_id) {
+                    Some(from) => from,
+                    None => return Ok(()),
+                },
+            };
+
+            let to = match Self::get_optimum_strategy_for(asset) {
+                Ok(to) if to != from && Self::outperforms(&to, &from, asset) => to,
+                _ => {
+                    Self::cancel_reallocation(vault_id);
+                    return Ok(());
+                }
+            };
+
+            match pending {
+                Some(reallocation) if reallocation.to == to => {
+                    let sustained =
+                        now.saturating_sub(reallocation.since) >= T::ReallocationDelay::get();
+                    if sustained && reallocation.last_moved != Some(now) {
+                        Self::move_allocation(vault_id, reallocation, now)?;
+                    }
+                }
+                _ => {
+                    Self::cancel_reallocation(vault_id);
+                    Reallocations::<T>::insert(
+                        vault_id,
+                        Reallocation {
+                            from: from.clone(),
+                            to: to.clone(),
+                            since: now,
+                            last_moved: None,
+                        },
+                    );
+                    Self::deposit_event(Event::ReallocationProposed {
+                        vault_id: *vault_id,
+                        from,
+                        to,
+                    });
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Moves at most [`Config::MaxReallocationPerBlock`] of `vault_id` from `reallocation.from`
+        /// to `reallocation.to`.
+        fn move_allocation(
+            vault_id: &T::VaultId,
+            mut reallocation: Reallocation<T::AccountId, T::BlockNumber>,
+            now: T::BlockNumber,
+        ) -> DispatchResult {
+            let Reallocation { from, to, .. } = reallocation.clone();
+
+            let mut target = Self::vault_strategy(vault_id)
+                .ok_or(Error::<T>::VaultNotAssociated)?
+                .into_inner();
+            let from_weight = target.remove(&from).unwrap_or_default();
+            let to_weight = target.get(&to).copied().unwrap_or_default();
+            target.insert(to.clone(), to_weight.saturating_add(from_weight));
+            let (moved, completed) =
+                Self::stage_transfer(vault_id, &target, T::MaxReallocationPerBlock::get())?;
+
+            Self::deposit_event(Event::ReallocationProgressed {
+                vault_id: *vault_id,
+                from: from.clone(),
+                to: to.clone(),
+                moved,
+            });
+
+            if completed {
+                Reallocations::<T>::remove(vault_id);
+                Self::deposit_event(Event::ReallocationCompleted {
+                    vault_id: *vault_id,
+                    from,
+                    to,
+                });
+            } else {
+                reallocation.last_moved = Some(now);
+                Reallocations::<T>::insert(vault_id, reallocation);
+            }
+
+            Ok(())
+        }
+
+        /// Computes the target allocation of `vault_id`: the share of the vault routed to
+        /// strategies is greedily given to the best ranked eligible strategies, each up to its cap,
+        /// the best ranked strategy also receiving the rounding dust of the caps. As the expected
+        /// APY of a vault is linear in its weights, the greedy allocation is the one maximizing it
+        /// under the caps.
+        pub fn compute_target_allocation(
+            vault_id: &T::VaultId,
+        ) -> Result<BTreeMap<T::AccountId, Perquintill>, DispatchError> {
+            let total = Self::routed_share_of(vault_id)?;
+            let asset = T::Vault::asset_id(vault_id)?;
+
+            let limits = Self::allocation_limits();
+            let current = Self::strategies_of(vault_id);
+            let strategies: Vec<_> = Self::ranked_strategies(asset, &current)?
+                .into_iter()
+                .filter(|(_, risk_score, _)| *risk_score <= limits.max_risk_score)
+                .collect();
+            ensure!(
+                strategies.len() >= limits.min_strategies as usize,
+                Error::<T>::InsufficientDiversification
+            );
+
+            let mut remaining = total;
+            let mut target = BTreeMap::new();
+            let mut top_ranked = None;
+            for (strategy, _, _) in strategies {
+                // What is left by rounding the caps down is not worth another strategy
+                if remaining <= Self::rounding_dust(target.len()) {
+                    break;
+                }
+                let weight = (Self::cap_of(&strategy, &limits) * total).min(remaining);
+                if !weight.is_zero() {
+                    remaining = remaining.saturating_sub(weight);
+                    top_ranked.get_or_insert_with(|| strategy.clone());
+                    target.insert(strategy, weight);
+                }
+            }
+            // It is given to the best ranked strategy instead
+            if let Some(strategy) = top_ranked {
+                if remaining <= Self::rounding_dust(target.len()) {
+                    if let Some(weight) = target.get_mut(&strategy) {
+                        *weight = weight.saturating_add(remaining);
+                        remaining = Perquintill::zero();
+                    }
+                }
+            }
+            ensure!(remaining.is_zero(), Error::<T>::AllocationCapsTooLow);
+
+            Ok(target)
+        }
+
+        /// Ensures `allocation` can be the target allocation of `vault_id`: it covers the share of
+        /// the vault routed to strategies and only gives it to running whitelisted strategies
+        /// supporting the asset of the vault, within the allocation limits and caps.
+        ///
+        /// The allocation is not compared with [`compute_target_allocation`]: the ranking drifts
+        /// between the block it was computed at and the one it is checked at, and recomputing it
+        /// would make every unsigned proposal as heavy as the optimization itself.
+        ///
+        /// [`compute_target_allocation`]: Pallet::compute_target_allocation
+        pub fn ensure_valid_allocation(
+            vault_id: &T::VaultId,
+            allocation: &BTreeMap<T::AccountId, Perquintill>,
+        ) -> DispatchResult {
+            let total = Self::routed_share_of(vault_id)?;
+            let asset = T::Vault::asset_id(vault_id)?;
+            let limits = Self::allocation_limits();
+            let whitelisted_strategies = Self::whitelisted_strategies();
+
+            ensure!(
+                allocation.len() >= limits.min_strategies as usize,
+                Error::<T>::InsufficientDiversification
+            );
+            let allocated = allocation
+                .values()
+                .try_fold(0_u64, |sum, weight| sum.checked_add(weight.deconstruct()))
+                .ok_or(ArithmeticError::Overflow)?;
+            ensure!(
+                allocated == total.deconstruct(),
+                Error::<T>::InvalidProposedAllocation
+            );
+
+            for (strategy, weight) in allocation {
+                ensure!(!weight.is_zero(), Error::<T>::InvalidProposedAllocation);
+                ensure!(
+                    whitelisted_strategies.contains(strategy),
+                    Error::<T>::StrategyNotWhitelisted
+                );
+                // A strategy whose status cannot be read is treated as halted
+                ensure!(
+                    matches!(T::Strategies::is_halted(strategy), Ok(false)),
+                    Error::<T>::Halted
+                );
+                let metadata = T::Strategies::metadata(strategy).map_err(Self::registry_error)?;
+                ensure!(
+                    metadata.supported_assets.contains(&asset),
+                    Error::<T>::InvalidProposedAllocation
+                );
+                ensure!(
+                    metadata.risk_score <= limits.max_risk_score,
+                    Error::<T>::StrategyTooRisky
+                );
+                ensure!(
+                    *weight
+                        <= (Self::cap_of(strategy, &limits) * total)
+                            .saturating_add(Self::rounding_dust(allocation.len())),
+                    Error::<T>::AllocationCapExceeded
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Stores `target` as the target allocation of `vault_id`.
+        fn set_target_allocation(
+            vault_id: &T::VaultId,
+            target: BTreeMap<T::AccountId, Perquintill>,
+        ) -> DispatchResult {
+            let target = BoundedBTreeMap::try_from(target)
+                .map_err(|_| Error::<T>::TooManyWhitelistedStrategies)?;
+            TargetAllocations::<T>::insert(vault_id, target);
+            // The target allocation supersedes the single strategy reallocation
+            Self::cancel_reallocation(vault_id);
+            Self::deposit_event(Event::TargetAllocationUpdated {
+                vault_id: *vault_id,
+            });
+
+            Ok(())
+        }
+
+        /// The share of `vault_id` routed to strategies, see [`VaultStrategy`].
+        fn routed_share_of(vault_id: &T::VaultId) -> Result<Perquintill, DispatchError> {
+            let allocation =
+                Self::vault_strategy(vault_id).ok_or(Error::<T>::VaultNotAssociated)?;
+            Ok(allocation
+                .values()
+                .fold(Perquintill::zero(), |total, weight| {
+                    total.saturating_add(*weight)
+                }))
+        }
+
+        /// The maximum share of a vault `strategy` may manage: its cap, but no more than
+        /// `1 / min_strategies`.
+        fn cap_of(strategy: &T::AccountId, limits: &AllocationLimits) -> Perquintill {
+            let diversification_cap =
+                Perquintill::from_rational(1_u64, u64::from(limits.min_strategies.max(1)));
+            Self::allocation_caps(strategy)
+                .unwrap_or_else(Perquintill::one)
+                .min(diversification_cap)
+        }
+
+        /// The share of a vault that rounding down the caps of `strategies` strategies may leave
+        /// unallocated, each capped weight being rounded down twice.
+        fn rounding_dust(strategies: usize) -> Perquintill {
+            Perquintill::from_parts((strategies as u64).saturating_mul(2))
+        }
+
+        /// Proposes, through unsigned transactions, the target allocation of every associated
+        /// vault whose target allocation is outdated.
+        fn propose_allocations(now: T::BlockNumber) {
+            for vault_id in Self::associated_vaults() {
+                let target = match Self::compute_target_allocation(&vault_id) {
+                    Ok(target) => target,
+                    Err(_) => continue,
+                };
+                let current =
+                    Self::target_allocations(vault_id).map(|current| current.into_inner());
+                if current.as_ref() == Some(&target) {
+                    continue;
+                }
+                let allocation = match BoundedBTreeMap::try_from(target) {
+                    Ok(allocation) => allocation,
+                    Err(_) => continue,
+                };
+
+                let call = Call::propose_allocation {
+                    vault_id,
+                    allocation,
+                    block_number: now,
+                };
+                if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+                    .is_err()
+                {
+                    log::error!(
+                        target: "runtime::instrumental-strategy",
+                        "failed to submit the target allocation of vault {:?}",
+                        vault_id
+                    );
+                }
+            }
+        }
+
+        /// Moves at most [`Config::MaxReallocationPerBlock`] of `vault_id` from the strategies
+        /// above their target weight to the ones below it.
+        fn converge_allocation(
+            vault_id: &T::VaultId,
+            target: BoundedBTreeMap<T::AccountId, Perquintill, T::MaxWhitelistedStrategies>,
+            now: T::BlockNumber,
+        ) -> DispatchResult {
+            if LastConverged::<T>::get(vault_id) == Some(now) {
+                return Ok(());
+            }
+            let (moved, _) = Self::stage_transfer(
+                vault_id,
+                &target.into_inner(),
+                T::MaxReallocationPerBlock::get(),
+            )?;
+            if moved.is_zero() {
+                return Ok(());
+            }
+
+            LastConverged::<T>::insert(vault_id, now);
+            Self::deposit_event(Event::TargetAllocationProgressed {
+                vault_id: *vault_id,
+                moved,
+            });
+
+            Ok(())
+        }
+
+        /// Moves at most `step` of `vault_id` from the strategies above their weight in `target` to
+        /// the ones below it, and returns the share of the vault moved along with whether nothing
+        /// is left to move.
+        ///
+        /// The funds follow the weights: every strategy whose weight changed is allotted its new
+        /// weight of the value of the vault. Strategies are associated before receiving funds and
+        /// dissociated once they returned all of them.
+        fn stage_transfer(
+            vault_id: &T::VaultId,
+            target: &BTreeMap<T::AccountId, Perquintill>,
+            step: Perquintill,
+        ) -> Result<(Perquintill, bool), DispatchError> {
+            let current = Self::vault_strategy(vault_id)
+                .ok_or(Error::<T>::VaultNotAssociated)?
+                .into_inner();
+            let weight_of = |weights: &BTreeMap<T::AccountId, Perquintill>, strategy| {
+                weights.get(strategy).copied().unwrap_or_default()
+            };
+            let excess_of =
+                |weights: &BTreeMap<T::AccountId, Perquintill>,
+                 reference: &BTreeMap<T::AccountId, Perquintill>| {
+                    weights
+                        .iter()
+                        .fold(Perquintill::zero(), |sum, (strategy, weight)| {
+                            sum.saturating_add(
+                                weight.saturating_sub(weight_of(reference, strategy)),
+                            )
+                        })
+                };
+
+            let surplus = excess_of(&current, target);
+            let deficit = excess_of(target, &current);
+            let moved = surplus.min(deficit).min(step);
+            if moved.is_zero() {
+                return Ok((moved, true));
+            }
+
+            let mut allocation = current.clone();
+            let mut to_take = moved;
+            for (strategy, weight) in current.iter() {
+                let taken = weight
+                    .saturating_sub(weight_of(target, strategy))
+                    .min(to_take);
+                if !taken.is_zero() {
+                    to_take = to_take.saturating_sub(taken);
+                    allocation.insert(strategy.clone(), weight.saturating_sub(taken));
+                }
+            }
+            let mut to_give = moved;
+            for (strategy, target_weight) in target.iter() {
+                let weight = weight_of(&allocation, strategy);
+                let given = target_weight.saturating_sub(weight).min(to_give);
+                if !given.is_zero() {
+                    to_give = to_give.saturating_sub(given);
+                    allocation.insert(strategy.clone(), weight.saturating_add(given));
+                }
+            }
+
+            let value = Self::value_of(vault_id)?;
+            for strategy in allocation.keys() {
+                if !current.contains_key(strategy) {
+                    T::Strategies::associate_vault(strategy, vault_id)
+                        .map_err(Self::registry_error)?;
+                }
+            }
+            let amounts = allocation
+                .iter()
+                .filter(|(strategy, weight)| weight_of(&current, *strategy) != **weight)
+                .map(|(strategy, weight)| (strategy.clone(), Self::share_of(*weight, value)))
+                .collect();
+            Self::allot_amounts(vault_id, amounts)?;
+            for (strategy, weight) in allocation.iter() {
+                if weight.is_zero() {
+                    T::Strategies::dissociate_vault(strategy, vault_id)
+                        .map_err(Self::registry_error)?;
+                }
+            }
+
+            allocation.retain(|_, weight| !weight.is_zero());
+            let completed = excess_of(&allocation, target).is_zero();
+            let allocation = BoundedBTreeMap::try_from(allocation)
+                .map_err(|_| Error::<T>::TooManyWhitelistedStrategies)?;
+            Self::set_vault_strategy(vault_id, Some(allocation))?;
+
+            Ok((moved, completed))
+        }
+
+        /// Allots each strategy its amount of `vault_id`, the strategies managing more than their
+        /// amount first, so that the other ones find the funds they take in the vault.
+        fn allot_amounts(
+            vault_id: &T::VaultId,
+            amounts: Vec<(T::AccountId, T::Balance)>,
+        ) -> DispatchResult {
+            let (shrinking, growing): (Vec<_>, Vec<_>) =
+                amounts.into_iter().partition(|(strategy, amount)| {
+                    T::Strategies::assets_under_management(strategy, vault_id)
+                        .map_or(true, |managed| managed > *amount)
+                });
+            for (strategy, amount) in shrinking.into_iter().chain(growing) {
+                T::Strategies::allot(&strategy, vault_id, amount).map_err(Self::registry_error)?;
+            }
+            Ok(())
+        }
+
+        /// The value of `vault_id`: the funds left in the vault and the ones its strategies
+        /// manage.
+        fn value_of(vault_id: &T::VaultId) -> Result<T::Balance, DispatchError> {
+            let asset = T::Vault::asset_id(vault_id)?;
+            let idle = T::Currency::balance(asset, &T::Vault::account_id(vault_id));
+            let managed =
+                <Self as InstrumentalProtocolStrategy>::assets_under_management(vault_id)?;
+            idle.checked_add(&managed)
+                .ok_or_else(|| ArithmeticError::Overflow.into())
+        }
+
+        /// The part of `value` a strategy of weight `weight` is allotted.
+        fn share_of(weight: Perquintill, value: T::Balance) -> T::Balance {
+            (weight * value.saturated_into::<u128>()).saturated_into()
+        }
+
+        /// Drops the pending or ongoing reallocation of `vault_id`, if any.
+        fn cancel_reallocation(vault_id: &T::VaultId) {
+            if let Some(Reallocation { from, to, .. }) = Reallocations::<T>::take(vault_id) {
+                Self::deposit_event(Event::ReallocationCancelled {
+                    vault_id: *vault_id,
+                    from,
+                    to,
+                });
+            }
+        }
+
+        /// Whether the score of `to` exceeds the one of `from`, where the funds are deployed, by
+        /// more than [`Config::MinApyImprovement`]. A strategy failing to report its APY scores
+        /// nothing, one failing to report its metadata is considered as risky as can be.
+        fn outperforms(to: &T::AccountId, from: &T::AccountId, asset: T::AssetId) -> bool {
+            let total_value_locked = Self::total_value_locked();
+            let score_of = |strategy: &T::AccountId, is_current: bool| {
+                let risk_score = T::Strategies::metadata(strategy)
+                    .map(|metadata| metadata.risk_score)
+                    .unwrap_or_else(|_| Percent::one());
+                Self::selection_apy(strategy, asset)
+                    .map(|apy| {
+                        Self::score_of(strategy, risk_score, &apy, total_value_locked, is_current)
+                    })
+                    .unwrap_or_default()
+            };
+            score_of(to, false).saturating_sub(score_of(from, true)) > T::MinApyImprovement::get()
+        }
+
+        /// The APY strategies are compared with: the averaged APY of `strategy` once sampled, its
+        /// instantaneous APY until then.
+        pub fn selection_apy(
+            strategy: &T::AccountId,
+            asset: T::AssetId,
+        ) -> Result<Apy<T::BlockNumber>, DispatchError> {
+            match Self::averaged_apy(strategy, asset) {
+                Some(apy) => Ok(apy),
+                None => T::Strategies::get_apy(strategy, asset).map_err(Self::registry_error),
+            }
+        }
+
+        /// Samples the APY of every whitelisted strategy for the asset of every associated vault,
+        /// and returns the number of samples taken.
+        fn sample_apys(now: T::BlockNumber) -> u32 {
+            let mut assets = Vec::new();
+            for vault_id in Self::associated_vaults() {
+                if let Ok(asset) = T::Vault::asset_id(&vault_id) {
+                    if !assets.contains(&asset) {
+                        assets.push(asset);
+                    }
+                }
+            }
+
+            let mut samples = 0_u32;
+            for strategy in Self::whitelisted_strategies() {
+                for asset in assets.iter() {
+                    // A strategy failing to report its APY keeps its previous samples
+                    let sample = match T::Strategies::get_apy(&strategy, *asset) {
+                        Ok(apy) => Apy::new(apy.rate, now),
+                        Err(_) => continue,
+                    };
+
+                    if !Self::record_apy_sample(&strategy, asset, sample) {
+                        log::error!(
+                            target: "runtime::instrumental-strategy",
+                            "no APY sample of strategy {:?} can be kept",
+                            strategy
+                        );
+                    }
+                    AveragedApy::<T>::mutate(&strategy, asset, |average| {
+                        *average = Some(Self::smooth(*average, sample));
+                    });
+                    samples = samples.saturating_add(1);
+                }
+            }
+            samples
+        }
+
+        /// Adds `sample` to the history of `strategy` for `asset`, in place of the oldest sample
+        /// once the history is full, and returns whether the sample was kept.
+        fn record_apy_sample(
+            strategy: &T::AccountId,
+            asset: &T::AssetId,
+            sample: Apy<T::BlockNumber>,
+        ) -> bool {
+            let mut history = ApyHistory::<T>::get(strategy, asset).unwrap_or_default();
+            if let Err(sample) = history.try_push(sample) {
+                let head = ApyHistoryHead::<T>::get(strategy, asset).unwrap_or_default();
+                match history.get_mut(head as usize) {
+                    Some(oldest) => *oldest = sample,
+                    // Only happens when no sample can be kept at all
+                    None => return false,
+                }
+                let next = head.saturating_add(1) % (history.len() as u32);
+                ApyHistoryHead::<T>::insert(strategy, asset, next);
+            }
+            ApyHistory::<T>::insert(strategy, asset, history);
+            true
+        }
+
+        /// Adds `sample` to the exponential moving average `average`.
+        fn smooth(
+            average: Option<Apy<T::BlockNumber>>,
+            sample: Apy<T::BlockNumber>,
+        ) -> Apy<T::BlockNumber> {
+            let average = match average {
+                Some(average) => average,
+                None => return sample,
+            };
+            let factor = T::ApySmoothingFactor::get();
+            let rate = (factor * sample.rate.into_inner()).saturating_add(
+                Perquintill::one().saturating_sub(factor) * average.rate.into_inner(),
+            );
+
+            Apy {
+                rate: FixedU128::from_inner(rate),
+                last_updated: sample.last_updated,
+            }
+        }
+
+        /// The strategy managing the largest share of `vault_id`, the lowest account ID first on
+        /// ties.
+        fn main_strategy_of(vault_id: &T::VaultId) -> Option<T::AccountId> {
+            Self::vault_strategy(vault_id)?
+                .into_iter()
+                .fold(
+                    None,
+                    |main: Option<(T::AccountId, Perquintill)>, (strategy, weight)| match main {
+                        Some(main) if main.1 >= weight => Some(main),
+                        _ => Some((strategy, weight)),
+                    },
+                )
+                .map(|(strategy, _)| strategy)
+        }
+
+        /// Returns the strategies `vault_id` is routed to, see [`VaultStrategy`].
+        pub fn strategies_of(vault_id: &T::VaultId) -> Vec<T::AccountId> {
+            Self::vault_strategy(vault_id)
+                .map(|allocation| {
+                    allocation
+                        .into_iter()
+                        .map(|(strategy, _)| strategy)
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// The registered strategies eligible for the funds of the associated vaults, see
+        /// [`WhitelistedStrategies`].
+        pub fn whitelisted_strategies() -> BTreeSet<T::AccountId> {
+            WhitelistedStrategies::<T>::get()
+                .map(BoundedBTreeSet::into_inner)
+                .unwrap_or_default()
+        }
+
+        /// The limits the target allocations are computed under, see
+        /// [`CurrentAllocationLimits`].
+        pub fn allocation_limits() -> AllocationLimits {
+            CurrentAllocationLimits::<T>::get().unwrap_or_default()
+        }
+
+        /// The APY samples of `strategy` for `asset`, the oldest first. See [`ApyHistory`].
+        pub fn apy_history(strategy: &T::AccountId, asset: T::AssetId) -> Vec<Apy<T::BlockNumber>> {
+            let mut history = ApyHistory::<T>::get(strategy, asset)
+                .map(BoundedVec::into_inner)
+                .unwrap_or_default();
+            let head = ApyHistoryHead::<T>::get(strategy, asset).unwrap_or_default() as usize;
+            history.rotate_left(head.min(history.len()));
+            history
+        }
+
+        /// Returns the vaults routed to `strategy`, see [`StrategyVaults`].
+        pub fn vaults_of(strategy: &T::AccountId) -> Vec<T::VaultId> {
+            Self::strategy_vaults(strategy)
+                .map(|vaults| vaults.into_iter().collect())
+                .unwrap_or_default()
+        }
+
+        /// Routes `vault_id` to `allocation`, or stops routing it when `None`, keeping
+        /// [`StrategyVaults`] in sync with [`VaultStrategy`].
+        fn set_vault_strategy(
+            vault_id: &T::VaultId,
+            allocation: Option<
+                BoundedBTreeMap<T::AccountId, Perquintill, T::MaxWhitelistedStrategies>,
+            >,
+        ) -> DispatchResult {
+            for strategy in Self::strategies_of(vault_id) {
+                StrategyVaults::<T>::mutate_exists(strategy, |vaults| {
+                    if let Some(set) = vaults {
+                        set.remove(vault_id);
+                        if set.is_empty() {
+                            *vaults = None;
+                        }
+                    }
+                });
+            }
+
+            let allocation = match allocation {
+                Some(allocation) => allocation,
+                None => {
+                    VaultStrategy::<T>::remove(vault_id);
+                    return Ok(());
+                }
+            };
+            for strategy in allocation.keys() {
+                StrategyVaults::<T>::try_mutate(strategy, |vaults| -> DispatchResult {
+                    vaults
+                        .get_or_insert_with(BoundedBTreeSet::new)
+                        .try_insert(*vault_id)
+                        .map_err(|_| Error::<T>::TooManyAssociatedStrategies)?;
+                    Ok(())
+                })?;
+            }
+            VaultStrategy::<T>::insert(vault_id, allocation);
+
+            Ok(())
+        }
+
+        /// The whitelisted strategies `vault_id` allocates its funds to. A vault that does not
+        /// allocate to any of them is split evenly across all the whitelisted strategies.
+        fn allocation_of(
+            vault_id: &T::VaultId,
+        ) -> Result<
+            BoundedBTreeMap<T::AccountId, Perquintill, T::MaxWhitelistedStrategies>,
+            DispatchError,
+        > {
+            let whitelisted_strategies = Self::whitelisted_strategies();
+            let allocation: BTreeMap<T::AccountId, Perquintill> =
+                match T::Vault::get_strategies(vault_id) {
+                    Ok(strategies) => strategies
+                        .into_iter()
+                        .filter(|(strategy, _)| whitelisted_strategies.contains(strategy))
+                        .collect(),
+                    Err(_) => BTreeMap::new(),
+                };
+            let allocation = if allocation.is_empty() {
+                Self::split_evenly(
+                    whitelisted_strategies.into_iter().collect(),
+                    Perquintill::one(),
+                )
+            } else {
+                allocation
+            };
+
+            BoundedBTreeMap::try_from(allocation)
+                .map_err(|_| Error::<T>::TooManyWhitelistedStrategies.into())
+        }
+
+        /// Splits `total` evenly across `strategies` and gives the rounding remainder to the first
+        /// one, so the weights always sum to `total`.
+        fn split_evenly(
+            strategies: Vec<T::AccountId>,
+            total: Perquintill,
+        ) -> BTreeMap<T::AccountId, Perquintill> {
+            let count = (strategies.len() as u64).max(1);
+            let share = total.deconstruct() / count;
+            let remainder = total.deconstruct() % count;
+
+            strategies
+                .into_iter()
+                .enumerate()
+                .map(|(index, strategy)| {
+                    let weight = if index == 0 { share + remainder } else { share };
+                    (strategy, Perquintill::from_parts(weight))
+                })
+                .collect()
+        }
+
+        fn ensure_associated(vault_id: &T::VaultId) -> DispatchResult {
+            ensure!(
+                AssociatedVaults::<T>::get().contains(vault_id),
+                Error::<T>::VaultNotAssociated
+            );
+            Ok(())
+        }
+
+        /// Maps an error of [`Config::Strategies`] to the error of this pallet.
+        fn registry_error(error: RegistryError) -> DispatchError {
+            match error {
+                RegistryError::StrategyNotRegistered => Error::<T>::StrategyNotRegistered.into(),
+                RegistryError::Strategy(error) => error,
+            }
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    //                                            Runtime API
+    // ---------------------------------------------------------------------------------------------
+
+    impl<T: Config> Pallet<T> {
+        /// Returns the registered strategies, in registration order.
+        pub fn strategy_infos(
+        ) -> Vec<StrategyInfo<T::AccountId, T::AssetId, T::BlockNumber, T::VaultId>> {
+            let whitelisted_strategies = Self::whitelisted_strategies();
+            T::Strategies::accounts()
+                .into_iter()
+                .map(|strategy| {
+                    let vaults = Self::vaults_of(&strategy);
+                    let mut apys: Vec<(T::AssetId, Apy<T::BlockNumber>)> = Vec::new();
+                    for vault_id in vaults.iter() {
+                        // A strategy failing to report its APY is listed without it
+                        if let Ok(asset) = T::Vault::asset_id(vault_id) {
+                            if !apys.iter().any(|(known, _)| known == &asset) {
+                                if let Ok(apy) = Self::selection_apy(&strategy, asset) {
+                                    apys.push((asset, apy));
+                                }
+                            }
+                        }
+                    }
+
+                    StrategyInfo {
+                        name: T::Strategies::metadata(&strategy)
+                            .map(|metadata| metadata.name)
+                            .unwrap_or_default(),
+                        whitelisted: whitelisted_strategies.contains(&strategy),
+                        is_halted: T::Strategies::is_halted(&strategy).ok(),
+                        apys,
+                        vaults,
+                        account: strategy,
+                    }
+                })
+                .collect()
+        }
+
+        /// Returns the associated vaults.
+        pub fn vault_infos() -> Vec<VaultInfo<T::AccountId, T::Balance, T::BlockNumber, T::VaultId>>
+        {
+            Self::associated_vaults()
+                .into_iter()
+                .map(|vault_id| {
+                    let allocation: Vec<_> = Self::vault_strategy(vault_id)
+                        .map(|allocation| allocation.into_iter().collect())
+                        .unwrap_or_default();
+
+                    VaultInfo {
+                        vault_id,
+                        assets_under_management: allocation
+                            .iter()
+                            .map(|(strategy, _)| {
+                                (
+                                    strategy.clone(),
+                                    T::Strategies::assets_under_management(strategy, &vault_id)
+                                        .ok(),
+                                )
+                            })
+                            .collect(),
+                        allocation,
+                        target_allocation: Self::target_allocations(vault_id)
+                            .map(|target| target.into_iter().collect()),
+                        migration: Self::migrations(vault_id),
+                    }
+                })
+                .collect()
         }
     }
 }
@@ -1,16 +1,22 @@
-use frame_support::{parameter_types, traits::Everything, PalletId};
-use frame_system::{EnsureRoot, EnsureSigned};
+use frame_support::{
+    parameter_types,
+    traits::{Everything, GenesisBuild},
+    PalletId,
+};
+use frame_system::{offchain::SendTransactionTypes, EnsureRoot, EnsureSigned};
 use orml_traits::parameter_type_with_key;
 use pallet_collective::EnsureProportionAtLeast;
 use primitives::currency::{CurrencyId, ValidateCurrencyId};
 use sp_core::H256;
 use sp_runtime::{
-    testing::Header,
+    testing::{Header, TestXt},
     traits::{ConvertInto, IdentityLookup},
-    Permill,
+    transaction_validity::TransactionPriority,
+    FixedU128, Percent, Permill, Perquintill,
 };
+use traits::strategy::InstrumentalProtocolStrategy;
 
-use super::fnft;
+use super::{fnft, strategies::IdleStrategy};
 use crate as instrumental_strategy;
 
 pub type AccountId = u128;
@@ -309,6 +315,11 @@ impl pallet_pablo::Config for MockRuntime {
 parameter_types! {
     pub const MaxAssociatedVaults: u32 = MAX_ASSOCIATED_VAULTS;
     pub const InstrumentalPabloStrategyPalletId: PalletId = PalletId(*b"strmxpab");
+    pub const PabloRiskScore: Percent = Percent::from_percent(50);
+    pub const PabloRebalanceInterval: BlockNumber = 10;
+    pub const PabloRebalanceThreshold: Balance = 1_000;
+    pub const PabloKeeperBountyShare: Percent = Percent::from_percent(10);
+    pub const PabloMaxKeeperBounty: Balance = 1_000;
 }
 
 impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
@@ -318,10 +329,36 @@ impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
     type Currency = Tokens;
     type Event = Event;
     type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type KeeperBountyShare = PabloKeeperBountyShare;
     type MaxAssociatedVaults = MaxAssociatedVaults;
+    type MaxKeeperBounty = PabloMaxKeeperBounty;
     type Pablo = Pablo;
     type PalletId = InstrumentalPabloStrategyPalletId;
     type PoolId = PoolId;
+    type RebalanceInterval = PabloRebalanceInterval;
+    type RebalanceThreshold = PabloRebalanceThreshold;
+    type RiskScore = PabloRiskScore;
+    type Vault = Vault;
+    type VaultId = VaultId;
+    type WeightInfo = ();
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                   Instrumental Default Strategy
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const InstrumentalDefaultStrategyPalletId: PalletId = PalletId(*b"strmxdef");
+}
+
+impl pallet_instrumental_strategy_default::Config for MockRuntime {
+    type AssetId = CurrencyId;
+    type Balance = Balance;
+    type Event = Event;
+    type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type MaxAssociatedVaults = MaxAssociatedVaults;
+    type PalletId = InstrumentalDefaultStrategyPalletId;
+    type PoolId = PoolId;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
@@ -333,21 +370,53 @@ impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
 
 parameter_types! {
     pub const InstrumentalStrategyPalletId: PalletId = PalletId(*b"dynamic_");
+    pub const MaxWhitelistedStrategies: u32 = 8;
+    pub const MaxReallocationPerBlock: Perquintill = Perquintill::from_percent(25);
+    // 1% of yearly yield
+    pub const MinApyImprovement: FixedU128 = FixedU128::from_inner(10_000_000_000_000_000);
+    pub const ReallocationDelay: BlockNumber = 10;
+    pub const ApySamplingPeriod: BlockNumber = 10;
+    pub const ApySmoothingFactor: Perquintill = Perquintill::from_percent(20);
+    pub const MaxApySamples: u32 = 24;
+    pub const InstrumentalStrategyUnsignedPriority: TransactionPriority = TransactionPriority::MAX;
+    pub const MaxRebalanceBackoff: u32 = 4;
 }
 
 impl instrumental_strategy::Config for MockRuntime {
+    type ApySamplingPeriod = ApySamplingPeriod;
+    type ApySmoothingFactor = ApySmoothingFactor;
     type AssetId = CurrencyId;
     type Balance = Balance;
+    type Currency = Tokens;
     type Event = Event;
+    type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type MaxApySamples = MaxApySamples;
     type MaxAssociatedVaults = MaxAssociatedVaults;
-    type PabloStrategy = PabloStrategy;
+    type MaxReallocationPerBlock = MaxReallocationPerBlock;
+    type MaxRebalanceBackoff = MaxRebalanceBackoff;
+    type MaxWhitelistedStrategies = MaxWhitelistedStrategies;
+    type MinApyImprovement = MinApyImprovement;
     type PalletId = InstrumentalStrategyPalletId;
     type PoolId = PoolId;
+    type ReallocationDelay = ReallocationDelay;
+    type Scoring = instrumental_strategy::scoring::RawApy;
+    type Strategies = (PabloStrategy, DefaultStrategy, IdleStrategy);
+    type UnsignedPriority = InstrumentalStrategyUnsignedPriority;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
 }
 
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for MockRuntime
+where
+    Call: From<LocalCall>,
+{
+    type Extrinsic = Extrinsic;
+    type OverarchingCall = Call;
+}
+
 // -------------------------------------------------------------------------------------------------
 //                                         Construct Runtime
 // -------------------------------------------------------------------------------------------------
@@ -376,7 +445,9 @@ frame_support::construct_runtime!(
         Pablo: pallet_pablo::{Pallet, Call, Storage, Event<T>},
 
         PabloStrategy: pallet_instrumental_strategy_pablo::{Pallet, Call, Storage, Event<T>},
-        InstrumentalStrategy: instrumental_strategy::{Pallet, Call, Storage, Event<T>},
+        DefaultStrategy: pallet_instrumental_strategy_default::{Pallet, Call, Storage, Event<T>},
+        InstrumentalStrategy:
+        instrumental_strategy::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
     }
 );
 
@@ -385,14 +456,49 @@ frame_support::construct_runtime!(
 // -------------------------------------------------------------------------------------------------
 
 #[derive(Default)]
-pub struct ExtBuilder {}
+pub struct ExtBuilder {
+    associated_vaults: Vec<VaultId>,
+    is_halted: bool,
+}
 
 impl ExtBuilder {
     pub fn build(self) -> sp_io::TestExternalities {
-        let t = frame_system::GenesisConfig::default()
+        let mut storage = frame_system::GenesisConfig::default()
             .build_storage::<MockRuntime>()
             .unwrap();
 
-        t.into()
+        GenesisBuild::<MockRuntime>::assimilate_storage(
+            &pallet_instrumental_strategy_pablo::GenesisConfig::default(),
+            &mut storage,
+        )
+        .unwrap();
+        GenesisBuild::<MockRuntime>::assimilate_storage(
+            &pallet_instrumental_strategy_default::GenesisConfig::default(),
+            &mut storage,
+        )
+        .unwrap();
+
+        // Whitelist the strategies deploying funds, as governance would do on a live chain
+        instrumental_strategy::GenesisConfig::<MockRuntime> {
+            whitelisted_strategies: vec![
+                <PabloStrategy as InstrumentalProtocolStrategy>::account_id(),
+            ],
+            is_halted: self.is_halted,
+            associated_vaults: self.associated_vaults,
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        storage.into()
+    }
+
+    pub fn associated_vaults(mut self, associated_vaults: Vec<VaultId>) -> Self {
+        self.associated_vaults = associated_vaults;
+        self
+    }
+
+    pub fn halted(mut self, is_halted: bool) -> Self {
+        self.is_halted = is_halted;
+        self
     }
 }
@@ -1,7 +1,15 @@
-use frame_support::PalletId;
-use sp_runtime::traits::AccountIdConversion;
+use frame_support::{storage::unhashed, PalletId};
+use primitives::currency::CurrencyId;
+use sp_runtime::{
+    traits::{AccountIdConversion, Zero},
+    DispatchError, DispatchResult, Percent,
+};
+use traits::strategy::{
+    Apy, InstrumentalProtocolStrategy, InstrumentalStrategyMetadata, StrategyMetadata,
+    SupportedAssets,
+};
 
-use super::runtime::AccountId;
+use super::runtime::{AccountId, Balance, BlockNumber, PoolId, VaultId};
 
 #[derive(Clone, Copy)]
 pub struct Strategy {
@@ -31,6 +39,113 @@ pub mod defined_strategies {
     use super::{super::runtime::AccountId, Strategy};
 
     pub const PABLO_STRATEGY: Strategy = Strategy::new(PalletId(*b"stratpab"));
+    pub const IDLE_STRATEGY: Strategy = Strategy::new(PalletId(*b"stratidl"));
 }
 
 pub use defined_strategies::*;
+
+// -------------------------------------------------------------------------------------------------
+//                                           Idle Strategy
+// -------------------------------------------------------------------------------------------------
+
+/// A strategy holding no funds and earning nothing, registered so that allocations can span more
+/// strategies than the pallets of the mock runtime provide.
+pub struct IdleStrategy;
+
+impl IdleStrategy {
+    const FAILING_KEY: &'static [u8] = b":mock:idle_strategy:failing";
+    const HALTED_KEY: &'static [u8] = b":mock:idle_strategy:halted";
+    /// The error rebalances fail with once [`fail_rebalances`](IdleStrategy::fail_rebalances) was
+    /// called.
+    pub const REBALANCE_ERROR: DispatchError = DispatchError::Other("idle strategy failing");
+
+    /// Makes every later rebalance of the strategy fail.
+    pub fn fail_rebalances() {
+        unhashed::put(Self::FAILING_KEY, &true);
+    }
+
+    fn ensure_rebalancing() -> DispatchResult {
+        if unhashed::get_or_default(Self::FAILING_KEY) {
+            return Err(Self::REBALANCE_ERROR);
+        }
+        Ok(())
+    }
+}
+
+impl InstrumentalProtocolStrategy for IdleStrategy {
+    type AccountId = AccountId;
+    type AssetId = CurrencyId;
+    type Balance = Balance;
+    type BlockNumber = BlockNumber;
+    type PoolId = PoolId;
+    type VaultId = VaultId;
+
+    fn account_id() -> Self::AccountId {
+        IDLE_STRATEGY.pallet_id.into_account_truncating()
+    }
+
+    fn associate_vault(_vault_id: &Self::VaultId) -> DispatchResult {
+        Ok(())
+    }
+
+    fn dissociate_vault(_vault_id: &Self::VaultId) -> DispatchResult {
+        Ok(())
+    }
+
+    fn rebalance() -> DispatchResult {
+        Self::ensure_rebalancing()
+    }
+
+    fn rebalance_vault(_vault_id: &Self::VaultId) -> DispatchResult {
+        Self::ensure_rebalancing()
+    }
+
+    fn assets_under_management(_vault_id: &Self::VaultId) -> Result<Self::Balance, DispatchError> {
+        Ok(Balance::zero())
+    }
+
+    fn allot(_vault_id: &Self::VaultId, _amount: Self::Balance) -> DispatchResult {
+        Ok(())
+    }
+
+    fn get_apy(_asset: Self::AssetId) -> Result<Apy<Self::BlockNumber>, DispatchError> {
+        Ok(Apy::zero())
+    }
+
+    fn halt() -> DispatchResult {
+        unhashed::put(Self::HALTED_KEY, &true);
+        Ok(())
+    }
+
+    fn start() -> DispatchResult {
+        unhashed::put(Self::HALTED_KEY, &false);
+        Ok(())
+    }
+
+    fn halt_vault(_vault_id: &Self::VaultId) -> DispatchResult {
+        Ok(())
+    }
+
+    fn start_vault(_vault_id: &Self::VaultId) -> DispatchResult {
+        Ok(())
+    }
+
+    fn is_halted() -> Result<bool, DispatchError> {
+        Ok(unhashed::get_or_default(Self::HALTED_KEY))
+    }
+}
+
+impl InstrumentalStrategyMetadata for IdleStrategy {
+    type AssetId = CurrencyId;
+    type Balance = Balance;
+
+    fn metadata() -> StrategyMetadata<Self::AssetId, Self::Balance> {
+        StrategyMetadata {
+            name: b"idle".to_vec(),
+            supported_assets: SupportedAssets::All,
+            risk_score: Percent::zero(),
+            synchronous_unwind: true,
+            max_capacity: None,
+        }
+    }
+}
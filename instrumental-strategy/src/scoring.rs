@@ -0,0 +1,77 @@
+//! # Strategy Scoring
+//!
+//! Policies the aggregator ranks strategies with. A policy turns what is known about a strategy
+//! into a single score, the strategy with the highest score being the preferred one. Scores are
+//! expressed in the same unit as the [`Apy`](traits::strategy::Apy) rate, so that they can be
+//! compared with [`Config::MinApyImprovement`](crate::Config::MinApyImprovement).
+use frame_support::traits::Get;
+use sp_runtime::{traits::Saturating, FixedU128, Percent, Perquintill};
+use sp_std::marker::PhantomData;
+
+/// What a [`StrategyScoring`] policy knows about a strategy when scoring it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoreInputs {
+    /// The annualized rate the strategy is selected on.
+    pub apy: FixedU128,
+    /// The risk score reported in the metadata of the strategy.
+    pub risk_score: Percent,
+    /// The share of the funds managed by the whitelisted strategies that is managed by this
+    /// strategy.
+    pub tvl_share: Perquintill,
+    /// Whether the funds being placed are already deployed into the strategy, i.e. choosing it
+    /// does not require migrating them.
+    pub is_current: bool,
+}
+
+/// Scores a strategy from its [`ScoreInputs`].
+pub trait StrategyScoring {
+    /// Returns the score of a strategy, higher is better.
+    fn score(inputs: &ScoreInputs) -> FixedU128;
+}
+
+/// Scores strategies by their APY alone.
+pub struct RawApy;
+
+impl StrategyScoring for RawApy {
+    fn score(inputs: &ScoreInputs) -> FixedU128 {
+        inputs.apy
+    }
+}
+
+/// Subtracts `Penalty` times the risk score from the APY: with a 10% penalty, a strategy with a
+/// 50% risk score loses 5% of APY.
+pub struct RiskPenalizedApy<Penalty>(PhantomData<Penalty>);
+
+impl<Penalty: Get<FixedU128>> StrategyScoring for RiskPenalizedApy<Penalty> {
+    fn score(inputs: &ScoreInputs) -> FixedU128 {
+        let penalty = Penalty::get().saturating_mul(FixedU128::from(inputs.risk_score));
+        inputs.apy.saturating_sub(penalty)
+    }
+}
+
+/// Discounts the APY of a strategy by `Penalty` times its share of the total value locked, so that
+/// funds are not concentrated into a single strategy for a marginal gain.
+pub struct ConcentrationAdjustedApy<Penalty>(PhantomData<Penalty>);
+
+impl<Penalty: Get<Perquintill>> StrategyScoring for ConcentrationAdjustedApy<Penalty> {
+    fn score(inputs: &ScoreInputs) -> FixedU128 {
+        let discount = Penalty::get() * inputs.tvl_share;
+        inputs
+            .apy
+            .saturating_mul(FixedU128::from(Perquintill::one().saturating_sub(discount)))
+    }
+}
+
+/// Subtracts `Cost`, the yield lost migrating funds, from the APY of every strategy the funds are
+/// not deployed into yet.
+pub struct MigrationCostAdjustedApy<Cost>(PhantomData<Cost>);
+
+impl<Cost: Get<FixedU128>> StrategyScoring for MigrationCostAdjustedApy<Cost> {
+    fn score(inputs: &ScoreInputs) -> FixedU128 {
+        if inputs.is_current {
+            inputs.apy
+        } else {
+            inputs.apy.saturating_sub(Cost::get())
+        }
+    }
+}
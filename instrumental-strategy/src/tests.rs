@@ -1,12 +1,88 @@
-use frame_support::{assert_noop, assert_ok};
+use codec::Decode;
+use composable_traits::{
+    defi::CurrencyPair,
+    dex::Amm,
+    vault::{Deposit, Vault as VaultTrait, VaultConfig},
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned},
+    traits::{
+        fungibles::{Inspect, Mutate},
+        Get, Hooks,
+    },
+    BoundedBTreeMap,
+};
+use pallet_instrumental_strategy_pablo::PoolState;
+use pallet_pablo::PoolInitConfiguration;
 use primitives::currency::CurrencyId;
-use traits::strategy::InstrumentalProtocolStrategy;
+use sp_core::offchain::{
+    testing::{TestOffchainExt, TestTransactionPoolExt},
+    OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+};
+use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128, Percent, Permill, Perquintill};
+use sp_std::collections::btree_map::BTreeMap;
+use traits::{
+    instrumental::{InstrumentalDynamicStrategy, State},
+    strategy::{
+        Apy, InstrumentalProtocolStrategy, InstrumentalStrategyMetadata, RegistryError,
+        StrategyRegistry,
+    },
+};
 
-use crate::mock::runtime::{
-    ExtBuilder, InstrumentalStrategy, MockRuntime, PabloStrategy, VaultId, MAX_ASSOCIATED_VAULTS,
+use crate::{
+    mock::{
+        runtime::{
+            AccountId, Balance, BlockNumber, Call, DefaultStrategy, Event, ExtBuilder, Extrinsic,
+            InstrumentalStrategy, MaxApySamples, MockRuntime, Origin, Pablo, PabloStrategy, PoolId,
+            System, Tokens, Vault, VaultId, MAX_ASSOCIATED_VAULTS,
+        },
+        strategies::IdleStrategy,
+    },
+    pallet::{
+        AllocationLimits, Error, Halted, Migration, Migrations, TargetAllocations, VaultStrategy,
+        INVALID_ALLOCATION,
+    },
+    scoring::{
+        ConcentrationAdjustedApy, MigrationCostAdjustedApy, RawApy, RiskPenalizedApy, ScoreInputs,
+        StrategyScoring,
+    },
+    weights::WeightInfo,
 };
-#[allow(unused_imports)]
-use crate::pallet::Error;
+
+// -------------------------------------------------------------------------------------------------
+//                                              Genesis
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_genesis_sets_up_whitelist_vaults_and_halted_flag() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1, 2])
+        .halted(true)
+        .build()
+        .execute_with(|| {
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+            assert!(InstrumentalStrategy::whitelisted_strategies().contains(&pablo_strategy));
+            for vault_id in [1, 2] {
+                assert!(InstrumentalStrategy::associated_vaults().contains(&vault_id));
+                assert_eq!(
+                    InstrumentalStrategy::strategies_of(&vault_id),
+                    vec![pablo_strategy]
+                );
+                assert!(PabloStrategy::associated_vaults().contains(&vault_id));
+            }
+            assert_eq!(
+                <InstrumentalStrategy as InstrumentalProtocolStrategy>::is_halted(),
+                Ok(true)
+            );
+            // The registered strategies are halted through their own genesis config
+            assert_eq!(
+                <PabloStrategy as InstrumentalProtocolStrategy>::is_halted(),
+                Ok(false)
+            );
+        });
+}
 
 // -------------------------------------------------------------------------------------------------
 //                                              Get Apy
@@ -24,6 +100,256 @@ fn test_get_apy() {
     });
 }
 
+// -------------------------------------------------------------------------------------------------
+//                                         Strategy Registry
+// -------------------------------------------------------------------------------------------------
+
+type Strategies = <MockRuntime as crate::Config>::Strategies;
+
+#[test]
+fn test_registry_lists_registered_strategies() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let idle_strategy = <IdleStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_eq!(
+            Strategies::accounts(),
+            vec![pablo_strategy, default_strategy, idle_strategy]
+        );
+        assert!(Strategies::contains(&pablo_strategy));
+        assert_eq!(
+            Strategies::metadata(&pablo_strategy),
+            Ok(PabloStrategy::metadata())
+        );
+    });
+}
+
+#[test]
+fn test_registry_rejects_unknown_strategy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let unknown_strategy = InstrumentalStrategy::account_id();
+        let vault_id: VaultId = 1;
+
+        assert!(!Strategies::contains(&unknown_strategy));
+        assert_noop!(
+            Strategies::associate_vault(&unknown_strategy, &vault_id),
+            RegistryError::StrategyNotRegistered
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                        Strategy Whitelist
+// -------------------------------------------------------------------------------------------------
+
+fn governance_origin() -> Origin {
+    Origin::from(pallet_collective::RawOrigin::Members(2, 3))
+}
+
+#[test]
+fn test_whitelist_requires_external_origin() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::delist_strategy(Origin::signed(pablo_strategy), pablo_strategy),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            InstrumentalStrategy::delist_strategy(
+                Origin::from(pallet_collective::RawOrigin::Members(1, 3)),
+                pablo_strategy
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn test_delist_and_whitelist_a_strategy() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        assert!(InstrumentalStrategy::whitelisted_strategies().contains(&pablo_strategy));
+
+        assert_ok!(InstrumentalStrategy::delist_strategy(
+            governance_origin(),
+            pablo_strategy
+        ));
+        assert!(!InstrumentalStrategy::whitelisted_strategies().contains(&pablo_strategy));
+        System::assert_last_event(Event::InstrumentalStrategy(
+            crate::Event::StrategyDelisted {
+                strategy: pablo_strategy,
+            },
+        ));
+
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            pablo_strategy
+        ));
+        assert!(InstrumentalStrategy::whitelisted_strategies().contains(&pablo_strategy));
+        System::assert_last_event(Event::InstrumentalStrategy(
+            crate::Event::StrategyWhitelisted {
+                strategy: pablo_strategy,
+            },
+        ));
+    });
+}
+
+#[test]
+fn test_whitelisting_a_strategy_twice_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::whitelist_strategy(governance_origin(), pablo_strategy),
+            Error::<MockRuntime>::StrategyAlreadyWhitelisted
+        );
+    });
+}
+
+#[test]
+fn test_whitelisting_an_unregistered_strategy_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let unknown_strategy = InstrumentalStrategy::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::whitelist_strategy(governance_origin(), unknown_strategy),
+            Error::<MockRuntime>::StrategyNotRegistered
+        );
+    });
+}
+
+#[test]
+fn test_delisting_a_strategy_not_whitelisted_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let unknown_strategy = InstrumentalStrategy::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::delist_strategy(governance_origin(), unknown_strategy),
+            Error::<MockRuntime>::StrategyNotWhitelisted
+        );
+    });
+}
+
+#[test]
+fn test_delisting_a_strategy_migrates_its_vaults_away() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let asset_id = CurrencyId::LAYR;
+        let (vault_id, pool_id) = create_vault_deployed_into_pablo(asset_id);
+        let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+        let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+        let idle = Tokens::balance(asset_id, &vault_account);
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            default_strategy
+        ));
+
+        assert_ok!(InstrumentalStrategy::delist_strategy(
+            governance_origin(),
+            pablo_strategy
+        ));
+        assert!(!PabloStrategy::associated_vaults().contains(&vault_id));
+        assert!(InstrumentalStrategy::associated_vaults().contains(&vault_id));
+        assert!(Tokens::balance(lp_token, &pablo_strategy).is_zero());
+        assert!(Tokens::balance(asset_id, &vault_account) > idle);
+        System::assert_has_event(Event::InstrumentalStrategy(
+            crate::Event::VaultMigratedAway {
+                vault_id,
+                strategy: pablo_strategy,
+            },
+        ));
+
+        // The weight of the delisted strategy moved along with its funds
+        assert_eq!(
+            InstrumentalStrategy::vault_strategy(&vault_id)
+                .unwrap()
+                .into_inner(),
+            BTreeMap::from([(default_strategy, Perquintill::from_percent(50))])
+        );
+    });
+}
+
+#[test]
+fn test_delisting_the_last_strategy_returns_the_funds_to_the_vaults() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let asset_id = CurrencyId::LAYR;
+        let (vault_id, pool_id) = create_vault_deployed_into_pablo(asset_id);
+        let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+        let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+        let idle = Tokens::balance(asset_id, &vault_account);
+
+        assert_ok!(InstrumentalStrategy::delist_strategy(
+            governance_origin(),
+            pablo_strategy
+        ));
+        assert!(!PabloStrategy::associated_vaults().contains(&vault_id));
+        assert!(Tokens::balance(lp_token, &pablo_strategy).is_zero());
+        assert!(Tokens::balance(asset_id, &vault_account) > idle);
+        assert!(InstrumentalStrategy::vault_strategy(&vault_id)
+            .unwrap()
+            .is_empty());
+    });
+}
+
+#[test]
+fn test_no_optimum_strategy_without_whitelisted_strategies() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_ok!(InstrumentalStrategy::delist_strategy(
+            governance_origin(),
+            pablo_strategy
+        ));
+        assert_noop!(
+            InstrumentalStrategy::get_optimum_strategy_for(CurrencyId::PICA),
+            Error::<MockRuntime>::NoStrategySupportsAsset
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                         Optimum Strategy
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_get_optimum_strategy_for_picks_a_strategy_supporting_the_asset() {
+    ExtBuilder::default().build().execute_with(|| {
+        let asset_id = CurrencyId::PICA;
+
+        assert!(PabloStrategy::supports_asset(&asset_id));
+        assert_eq!(
+            InstrumentalStrategy::get_optimum_strategy_for(asset_id),
+            Ok(<PabloStrategy as InstrumentalProtocolStrategy>::account_id())
+        );
+    });
+}
+
+#[test]
+fn test_get_optimum_strategy_for_skips_halted_strategies() {
+    ExtBuilder::default().build().execute_with(|| {
+        let asset_id = CurrencyId::PICA;
+
+        assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::halt());
+        assert_noop!(
+            InstrumentalStrategy::get_optimum_strategy_for(asset_id),
+            Error::<MockRuntime>::AllStrategiesHalted
+        );
+
+        assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::start());
+        assert_eq!(
+            InstrumentalStrategy::get_optimum_strategy_for(asset_id),
+            Ok(<PabloStrategy as InstrumentalProtocolStrategy>::account_id())
+        );
+    });
+}
+
 // -------------------------------------------------------------------------------------------------
 //                                          Associate Vault
 // -------------------------------------------------------------------------------------------------
@@ -33,7 +359,33 @@ fn test_add_an_associated_vault() {
     ExtBuilder::default().build().execute_with(|| {
         let vault_id: VaultId = 1;
 
-        assert_ok!(InstrumentalStrategy::associate_vault(&vault_id));
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+    });
+}
+
+#[test]
+fn test_associating_a_vault_records_its_strategies() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id: VaultId = 1;
+
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+
+        let allocation = InstrumentalStrategy::vault_strategy(&vault_id).unwrap();
+        assert_eq!(allocation.get(&pablo_strategy), Some(&Perquintill::one()));
+        assert_eq!(
+            InstrumentalStrategy::strategies_of(&vault_id),
+            vec![pablo_strategy]
+        );
+        assert_eq!(
+            InstrumentalStrategy::vaults_of(&pablo_strategy),
+            vec![vault_id]
+        );
+        assert!(PabloStrategy::associated_vaults().contains(&vault_id));
     });
 }
 
@@ -42,9 +394,11 @@ fn test_adding_an_associated_vault_twice_throws_an_error() {
     ExtBuilder::default().build().execute_with(|| {
         let vault_id: VaultId = 1;
 
-        assert_ok!(InstrumentalStrategy::associate_vault(&vault_id));
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
         assert_noop!(
-            InstrumentalStrategy::associate_vault(&vault_id),
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id),
             Error::<MockRuntime>::VaultAlreadyAssociated
         );
     });
@@ -54,15 +408,1368 @@ fn test_adding_an_associated_vault_twice_throws_an_error() {
 fn test_associating_too_many_vaults_throws_an_error() {
     ExtBuilder::default().build().execute_with(|| {
         for vault_id in 0..MAX_ASSOCIATED_VAULTS {
-            assert_ok!(InstrumentalStrategy::associate_vault(
-                &(vault_id as VaultId)
-            ));
+            assert_ok!(
+                <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(
+                    &(vault_id as VaultId)
+                )
+            );
         }
 
         let vault_id = MAX_ASSOCIATED_VAULTS as VaultId;
         assert_noop!(
-            InstrumentalStrategy::associate_vault(&vault_id),
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id),
             Error::<MockRuntime>::TooManyAssociatedStrategies
         );
     });
 }
+
+// -------------------------------------------------------------------------------------------------
+//                                         Dissociate Vault
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_dissociate_an_associated_vault() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id: VaultId = 1;
+
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        assert_ok!(InstrumentalStrategy::dissociate_vault(&vault_id));
+
+        assert!(!InstrumentalStrategy::associated_vaults().contains(&vault_id));
+        assert!(!PabloStrategy::associated_vaults().contains(&vault_id));
+        assert_eq!(InstrumentalStrategy::vault_strategy(&vault_id), None);
+        assert_eq!(InstrumentalStrategy::strategy_vaults(&pablo_strategy), None);
+    });
+}
+
+#[test]
+fn test_dissociating_a_not_associated_vault_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let vault_id: VaultId = 1;
+
+        assert_noop!(
+            InstrumentalStrategy::dissociate_vault(&vault_id),
+            Error::<MockRuntime>::VaultNotAssociated
+        );
+    });
+}
+
+#[test]
+fn test_per_vault_operations_require_an_associated_vault() {
+    ExtBuilder::default().build().execute_with(|| {
+        let vault_id: VaultId = 1;
+
+        assert_noop!(
+            InstrumentalStrategy::rebalance_vault(&vault_id),
+            Error::<MockRuntime>::VaultNotAssociated
+        );
+        assert_noop!(
+            InstrumentalStrategy::assets_under_management(&vault_id),
+            Error::<MockRuntime>::VaultNotAssociated
+        );
+        assert_noop!(
+            InstrumentalStrategy::halt_vault(&vault_id),
+            Error::<MockRuntime>::VaultNotAssociated
+        );
+        assert_noop!(
+            InstrumentalStrategy::start_vault(&vault_id),
+            Error::<MockRuntime>::VaultNotAssociated
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                            Reallocation
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_rebalance_keeps_vaults_already_on_the_optimum_strategy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id: VaultId = 1;
+
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        assert_ok!(<InstrumentalStrategy as InstrumentalProtocolStrategy>::rebalance());
+
+        assert_eq!(InstrumentalStrategy::reallocations(&vault_id), None);
+        assert_eq!(
+            InstrumentalStrategy::strategies_of(&vault_id),
+            vec![pablo_strategy]
+        );
+    });
+}
+
+#[test]
+fn test_a_vault_failing_to_rebalance_does_not_block_the_others() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let unregistered_strategy: AccountId = 9_999;
+        let (broken_vault_id, _) = create_vault_deployed_into_pablo(CurrencyId::LAYR);
+        let (vault_id, _) = create_vault_deployed_into_pablo(CurrencyId::USDT);
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            default_strategy
+        ));
+        let target_of = |strategy| {
+            BoundedBTreeMap::try_from(BTreeMap::from([(strategy, Perquintill::from_percent(50))]))
+                .unwrap()
+        };
+        TargetAllocations::<MockRuntime>::insert(broken_vault_id, target_of(unregistered_strategy));
+        TargetAllocations::<MockRuntime>::insert(vault_id, target_of(default_strategy));
+
+        assert_ok!(InstrumentalStrategy::liquidity_rebalance(
+            governance_origin()
+        ));
+
+        System::assert_has_event(Event::InstrumentalStrategy(
+            crate::Event::UnableToRebalanceVault {
+                vault_id: broken_vault_id,
+                error: Error::<MockRuntime>::StrategyNotRegistered.into(),
+            },
+        ));
+        System::assert_has_event(Event::InstrumentalStrategy(
+            crate::Event::TargetAllocationProgressed {
+                vault_id,
+                moved: Perquintill::from_percent(25),
+            },
+        ));
+        assert_eq!(InstrumentalStrategy::last_converged(vault_id), Some(1));
+        assert_eq!(InstrumentalStrategy::last_converged(broken_vault_id), None);
+    });
+}
+
+#[test]
+fn test_a_strategy_failing_to_rebalance_a_vault_does_not_block_the_others() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let idle_strategy = <IdleStrategy as InstrumentalProtocolStrategy>::account_id();
+        let (vault_id, _) = create_vault_deployed_into_pablo(CurrencyId::LAYR);
+        let allocation = BTreeMap::from([
+            (pablo_strategy, Perquintill::from_percent(50)),
+            (idle_strategy, Perquintill::from_percent(50)),
+        ]);
+        VaultStrategy::<MockRuntime>::insert(
+            vault_id,
+            BoundedBTreeMap::try_from(allocation).unwrap(),
+        );
+        IdleStrategy::fail_rebalances();
+
+        System::set_block_number(2);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::rebalance_vault(&vault_id)
+        );
+
+        System::assert_has_event(Event::InstrumentalStrategy(
+            crate::Event::StrategyNotRebalanced {
+                vault_id,
+                strategy: idle_strategy,
+                error: IdleStrategy::REBALANCE_ERROR,
+            },
+        ));
+        assert_eq!(PabloStrategy::last_rebalanced(vault_id), Some(2));
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                       Allocation Optimizer
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_set_allocation_limits() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let limits = AllocationLimits {
+            min_strategies: 2,
+            max_risk_score: Percent::from_percent(60),
+        };
+
+        assert_eq!(
+            InstrumentalStrategy::allocation_limits(),
+            AllocationLimits::default()
+        );
+        assert_ok!(InstrumentalStrategy::set_allocation_limits(
+            governance_origin(),
+            limits
+        ));
+        assert_eq!(InstrumentalStrategy::allocation_limits(), limits);
+        System::assert_last_event(Event::InstrumentalStrategy(
+            crate::Event::AllocationLimitsUpdated { limits },
+        ));
+    });
+}
+
+#[test]
+fn test_allocation_limits_require_a_strategy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let limits = AllocationLimits {
+            min_strategies: 0,
+            max_risk_score: Percent::one(),
+        };
+
+        assert_noop!(
+            InstrumentalStrategy::set_allocation_limits(governance_origin(), limits),
+            Error::<MockRuntime>::InvalidAllocationLimits
+        );
+    });
+}
+
+#[test]
+fn test_set_allocation_cap() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let cap = Some(Perquintill::from_percent(40));
+
+        assert_ok!(InstrumentalStrategy::set_allocation_cap(
+            governance_origin(),
+            pablo_strategy,
+            cap
+        ));
+        assert_eq!(InstrumentalStrategy::allocation_caps(pablo_strategy), cap);
+
+        assert_ok!(InstrumentalStrategy::set_allocation_cap(
+            governance_origin(),
+            pablo_strategy,
+            None
+        ));
+        assert_eq!(InstrumentalStrategy::allocation_caps(pablo_strategy), None);
+    });
+}
+
+#[test]
+fn test_capping_an_unregistered_strategy_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let unknown_strategy = InstrumentalStrategy::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::set_allocation_cap(
+                governance_origin(),
+                unknown_strategy,
+                Some(Perquintill::from_percent(40))
+            ),
+            Error::<MockRuntime>::StrategyNotRegistered
+        );
+    });
+}
+
+#[test]
+fn test_target_allocation_gives_the_rounding_dust_to_the_best_ranked_strategy() {
+    ExtBuilder::default().build().execute_with(|| {
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let idle_strategy = <IdleStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        for strategy in [default_strategy, idle_strategy] {
+            assert_ok!(InstrumentalStrategy::whitelist_strategy(
+                governance_origin(),
+                strategy
+            ));
+        }
+        // Each strategy is capped at a third of the vault, which rounds down
+        assert_ok!(InstrumentalStrategy::set_allocation_limits(
+            governance_origin(),
+            AllocationLimits {
+                min_strategies: 3,
+                max_risk_score: Percent::one(),
+            }
+        ));
+        let cap = Perquintill::from_rational(1_u64, 3);
+        assert!(cap.deconstruct() * 3 < Perquintill::one().deconstruct());
+
+        let target = InstrumentalStrategy::compute_target_allocation(&vault_id).unwrap();
+        assert_eq!(target.len(), 3);
+        assert_eq!(
+            target
+                .values()
+                .map(|weight| weight.deconstruct())
+                .sum::<u64>(),
+            Perquintill::one().deconstruct()
+        );
+        assert_eq!(target.values().filter(|weight| **weight > cap).count(), 1);
+        assert_ok!(InstrumentalStrategy::ensure_valid_allocation(
+            &vault_id, &target
+        ));
+    });
+}
+
+#[test]
+fn test_optimizing_a_not_associated_vault_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let vault_id: VaultId = 1;
+
+        assert_noop!(
+            InstrumentalStrategy::optimize_allocation(governance_origin(), vault_id),
+            Error::<MockRuntime>::VaultNotAssociated
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                            APY History
+// -------------------------------------------------------------------------------------------------
+
+const VAULT_MANAGER: AccountId = 1;
+
+fn create_vault(asset_id: CurrencyId) -> VaultId {
+    let config = VaultConfig {
+        asset_id,
+        manager: VAULT_MANAGER,
+        reserved: Perquintill::one(),
+        strategies: BTreeMap::new(),
+    };
+    let vault_id = <Vault as VaultTrait>::create(Deposit::Existential, config);
+    assert_ok!(vault_id);
+    vault_id.unwrap()
+}
+
+#[test]
+fn test_apys_are_sampled_every_sampling_period() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let asset_id = CurrencyId::PICA;
+        let vault_id = create_vault(asset_id);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+
+        InstrumentalStrategy::on_initialize(5);
+        assert!(InstrumentalStrategy::apy_history(&pablo_strategy, asset_id).is_empty());
+        assert_eq!(
+            InstrumentalStrategy::averaged_apy(pablo_strategy, asset_id),
+            None
+        );
+
+        InstrumentalStrategy::on_initialize(10);
+        InstrumentalStrategy::on_initialize(20);
+        assert_eq!(
+            InstrumentalStrategy::apy_history(&pablo_strategy, asset_id),
+            vec![
+                Apy::new(FixedU128::zero(), 10),
+                Apy::new(FixedU128::zero(), 20)
+            ]
+        );
+        assert_eq!(
+            InstrumentalStrategy::averaged_apy(pablo_strategy, asset_id),
+            Some(Apy::new(FixedU128::zero(), 20))
+        );
+        assert_eq!(
+            InstrumentalStrategy::selection_apy(&pablo_strategy, asset_id),
+            Ok(Apy::new(FixedU128::zero(), 20))
+        );
+    });
+}
+
+#[test]
+fn test_apy_history_is_bounded() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let asset_id = CurrencyId::PICA;
+        let vault_id = create_vault(asset_id);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+
+        let max_samples = MaxApySamples::get() as u64;
+        for period in 1..=(max_samples + 5) {
+            InstrumentalStrategy::on_initialize(period * 10);
+        }
+
+        let history = InstrumentalStrategy::apy_history(&pablo_strategy, asset_id);
+        assert_eq!(history.len() as u64, max_samples);
+        assert_eq!(history.first().and_then(|apy| apy.last_updated), Some(60));
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                        Off-chain Proposals
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_offchain_worker_proposes_the_target_allocation() {
+    let (offchain, _offchain_state) = TestOffchainExt::new();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    let mut ext = ExtBuilder::default().build();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+
+    ext.execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        System::set_block_number(10);
+
+        InstrumentalStrategy::offchain_worker(10);
+
+        let transaction = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        let transaction = Extrinsic::decode(&mut &*transaction).unwrap();
+        assert_eq!(transaction.signature, None);
+
+        let allocation = BTreeMap::from([(pablo_strategy, Perquintill::one())]);
+        let call = crate::Call::propose_allocation {
+            vault_id,
+            allocation: allocation.clone().try_into().unwrap(),
+            block_number: 10,
+        };
+        assert_eq!(transaction.call, Call::InstrumentalStrategy(call.clone()));
+        assert_ok!(InstrumentalStrategy::validate_unsigned(
+            TransactionSource::Local,
+            &call
+        ));
+
+        assert_ok!(InstrumentalStrategy::propose_allocation(
+            Origin::none(),
+            vault_id,
+            allocation.clone().try_into().unwrap(),
+            10
+        ));
+        assert_eq!(
+            InstrumentalStrategy::target_allocations(vault_id).map(|target| target.into_inner()),
+            Some(allocation)
+        );
+
+        // An up-to-date target allocation is not proposed again
+        InstrumentalStrategy::offchain_worker(20);
+        assert!(pool_state.read().transactions.is_empty());
+    });
+}
+
+#[test]
+fn test_out_of_bounds_proposals_are_rejected() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        System::set_block_number(10);
+
+        let allocation = BTreeMap::from([(pablo_strategy, Perquintill::from_percent(50))]);
+        let call = crate::Call::propose_allocation {
+            vault_id,
+            allocation: allocation.clone().try_into().unwrap(),
+            block_number: 10,
+        };
+
+        assert_eq!(
+            InstrumentalStrategy::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Custom(INVALID_ALLOCATION).into()
+        );
+        assert_noop!(
+            InstrumentalStrategy::propose_allocation(
+                Origin::none(),
+                vault_id,
+                allocation.try_into().unwrap(),
+                10
+            ),
+            Error::<MockRuntime>::InvalidProposedAllocation
+        );
+    });
+}
+
+#[test]
+fn test_proposals_within_bounds_are_accepted_even_if_not_computed_on_chain() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            default_strategy
+        ));
+        System::set_block_number(10);
+
+        // The whole vault goes to a single strategy on chain
+        let allocation = BTreeMap::from([
+            (pablo_strategy, Perquintill::from_percent(50)),
+            (default_strategy, Perquintill::from_percent(50)),
+        ]);
+        assert_ne!(
+            InstrumentalStrategy::compute_target_allocation(&vault_id),
+            Ok(allocation.clone())
+        );
+        let call = crate::Call::propose_allocation {
+            vault_id,
+            allocation: allocation.clone().try_into().unwrap(),
+            block_number: 10,
+        };
+
+        assert_ok!(InstrumentalStrategy::validate_unsigned(
+            TransactionSource::External,
+            &call
+        ));
+        assert_ok!(InstrumentalStrategy::propose_allocation(
+            Origin::none(),
+            vault_id,
+            allocation.clone().try_into().unwrap(),
+            10
+        ));
+        assert_eq!(
+            InstrumentalStrategy::target_allocations(vault_id).map(|target| target.into_inner()),
+            Some(allocation)
+        );
+    });
+}
+
+#[test]
+fn test_proposals_allocating_to_a_halted_strategy_are_rejected() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            default_strategy
+        ));
+        assert_ok!(<DefaultStrategy as InstrumentalProtocolStrategy>::halt());
+        System::set_block_number(10);
+
+        let allocation = BTreeMap::from([
+            (pablo_strategy, Perquintill::from_percent(50)),
+            (default_strategy, Perquintill::from_percent(50)),
+        ]);
+        let call = crate::Call::propose_allocation {
+            vault_id,
+            allocation: allocation.clone().try_into().unwrap(),
+            block_number: 10,
+        };
+
+        assert_eq!(
+            InstrumentalStrategy::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Custom(INVALID_ALLOCATION).into()
+        );
+        assert_noop!(
+            InstrumentalStrategy::propose_allocation(
+                Origin::none(),
+                vault_id,
+                allocation.try_into().unwrap(),
+                10
+            ),
+            Error::<MockRuntime>::Halted
+        );
+    });
+}
+
+#[test]
+fn test_stale_proposals_are_rejected() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        System::set_block_number(30);
+
+        let call = crate::Call::propose_allocation {
+            vault_id,
+            allocation: BTreeMap::from([(pablo_strategy, Perquintill::one())])
+                .try_into()
+                .unwrap(),
+            block_number: 10,
+        };
+
+        assert_eq!(
+            InstrumentalStrategy::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Stale.into()
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                            Halt & Start
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_halt_and_start_propagate_to_registered_strategies() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+
+        assert_ok!(InstrumentalStrategy::halt(governance_origin()));
+        assert_eq!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(true)
+        );
+        assert_eq!(
+            <PabloStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(true)
+        );
+        System::assert_last_event(Event::InstrumentalStrategy(crate::Event::Halted));
+
+        assert_ok!(InstrumentalStrategy::start(governance_origin()));
+        assert_eq!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(false)
+        );
+        assert_eq!(
+            <PabloStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(false)
+        );
+        System::assert_last_event(Event::InstrumentalStrategy(crate::Event::Unhalted));
+    });
+}
+
+#[test]
+fn test_start_leaves_strategies_halted_on_their_own_halted() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        assert_ok!(<DefaultStrategy as InstrumentalProtocolStrategy>::halt());
+
+        assert_ok!(InstrumentalStrategy::halt(governance_origin()));
+        assert_ok!(InstrumentalStrategy::start(governance_origin()));
+
+        assert_eq!(
+            <PabloStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(false)
+        );
+        assert_eq!(
+            <DefaultStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(true)
+        );
+        System::assert_has_event(Event::InstrumentalStrategy(
+            crate::Event::StrategyLeftHalted {
+                strategy: default_strategy,
+            },
+        ));
+    });
+}
+
+#[test]
+fn test_halt_goes_on_when_a_strategy_fails_to_halt() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        // Pablo cannot stop a vault that does not exist
+        assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::associate_vault(&99));
+        // The flag of a strategy never initialized reads as running
+        pallet_instrumental_strategy_default::Halted::<MockRuntime>::kill();
+        assert_eq!(
+            <DefaultStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(false)
+        );
+
+        assert_ok!(InstrumentalStrategy::halt(governance_origin()));
+
+        assert_eq!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(true)
+        );
+        assert_eq!(
+            <DefaultStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(true)
+        );
+        assert_eq!(
+            <PabloStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Ok(false)
+        );
+        assert!(System::events().iter().any(|record| matches!(
+            &record.event,
+            Event::InstrumentalStrategy(crate::Event::StrategyNotHalted { strategy, .. })
+                if *strategy == pablo_strategy
+        )));
+    });
+}
+
+#[test]
+fn test_halt_requires_external_origin() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::halt(Origin::signed(pablo_strategy)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn test_halted_strategy_rejects_associating_and_rebalancing() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1])
+        .build()
+        .execute_with(|| {
+            let vault_id: VaultId = 2;
+            let associated_vault_id: VaultId = 1;
+
+            assert_ok!(<InstrumentalStrategy as InstrumentalProtocolStrategy>::halt());
+            assert_noop!(
+                <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id),
+                Error::<MockRuntime>::Halted
+            );
+            assert_noop!(
+                InstrumentalStrategy::liquidity_rebalance(governance_origin()),
+                Error::<MockRuntime>::Halted
+            );
+            assert_noop!(
+                <InstrumentalStrategy as InstrumentalProtocolStrategy>::rebalance_vault(
+                    &associated_vault_id
+                ),
+                Error::<MockRuntime>::Halted
+            );
+            assert_noop!(
+                InstrumentalStrategy::halt_vault(&associated_vault_id),
+                Error::<MockRuntime>::Halted
+            );
+            assert_noop!(
+                InstrumentalStrategy::start_vault(&associated_vault_id),
+                Error::<MockRuntime>::Halted
+            );
+        });
+}
+
+#[test]
+fn test_uninitialized_halted_flag_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        Halted::<MockRuntime>::kill();
+
+        assert_noop!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::is_halted(),
+            Error::<MockRuntime>::StorageIsNotInitialized
+        );
+        assert_noop!(
+            InstrumentalStrategy::liquidity_rebalance(governance_origin()),
+            Error::<MockRuntime>::StorageIsNotInitialized
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                             Migration
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_invalid_migrations_throw_an_error() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1])
+        .build()
+        .execute_with(|| {
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+            let chunk = Perquintill::from_percent(25);
+
+            assert_noop!(
+                InstrumentalStrategy::migrate_vault_strategy(governance_origin(), 1, 42, chunk),
+                Error::<MockRuntime>::StrategyNotWhitelisted
+            );
+            assert_noop!(
+                InstrumentalStrategy::migrate_vault_strategy(
+                    governance_origin(),
+                    2,
+                    pablo_strategy,
+                    chunk
+                ),
+                Error::<MockRuntime>::VaultNotAssociated
+            );
+            assert_noop!(
+                InstrumentalStrategy::migrate_vault_strategy(
+                    governance_origin(),
+                    1,
+                    pablo_strategy,
+                    Perquintill::zero()
+                ),
+                Error::<MockRuntime>::InvalidMigrationChunk
+            );
+            assert_noop!(
+                InstrumentalStrategy::migrate_vault_strategy(
+                    governance_origin(),
+                    1,
+                    pablo_strategy,
+                    chunk
+                ),
+                Error::<MockRuntime>::VaultAlreadyOnStrategy
+            );
+            assert_noop!(
+                InstrumentalStrategy::resume_vault_migration(governance_origin(), 1),
+                Error::<MockRuntime>::NoMigration
+            );
+            assert_noop!(
+                InstrumentalStrategy::abort_vault_migration(governance_origin(), 1),
+                Error::<MockRuntime>::NoMigration
+            );
+        });
+}
+
+#[test]
+fn test_migrating_to_a_halted_strategy_throws_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let vault_id = create_vault(CurrencyId::PICA);
+        assert_ok!(
+            <InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id)
+        );
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            default_strategy
+        ));
+        assert_ok!(<DefaultStrategy as InstrumentalProtocolStrategy>::halt());
+
+        assert_noop!(
+            InstrumentalStrategy::migrate_vault_strategy(
+                governance_origin(),
+                vault_id,
+                default_strategy,
+                Perquintill::from_percent(25)
+            ),
+            Error::<MockRuntime>::Halted
+        );
+    });
+}
+
+#[test]
+fn test_failed_migration_chunks_interrupt_the_migration_until_resumed() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+            let allocation = InstrumentalStrategy::vault_strategy(1);
+            // The new strategy is not registered, so no chunk can be moved to it
+            Migrations::<MockRuntime>::insert(
+                1,
+                Migration {
+                    to: 42,
+                    chunk: Perquintill::from_percent(25),
+                    state: State::Transferring,
+                    since: 1,
+                },
+            );
+
+            InstrumentalStrategy::on_initialize(2);
+            assert_eq!(
+                InstrumentalStrategy::migrations(1).unwrap().state,
+                State::Normal
+            );
+            assert_eq!(InstrumentalStrategy::vault_strategy(1), allocation);
+            System::assert_last_event(Event::InstrumentalStrategy(
+                crate::Event::MigrationInterrupted {
+                    vault_id: 1,
+                    to: 42,
+                },
+            ));
+            assert_noop!(
+                InstrumentalStrategy::resume_vault_migration(Origin::signed(pablo_strategy), 1),
+                sp_runtime::DispatchError::BadOrigin
+            );
+
+            assert_ok!(InstrumentalStrategy::resume_vault_migration(
+                governance_origin(),
+                1
+            ));
+            assert_eq!(
+                InstrumentalStrategy::migrations(1).unwrap().state,
+                State::Transferring
+            );
+            assert_noop!(
+                InstrumentalStrategy::resume_vault_migration(governance_origin(), 1),
+                Error::<MockRuntime>::MigrationNotInterrupted
+            );
+
+            assert_ok!(InstrumentalStrategy::abort_vault_migration(
+                governance_origin(),
+                1
+            ));
+            assert_eq!(InstrumentalStrategy::migrations(1), None);
+            assert_eq!(InstrumentalStrategy::vault_strategy(1), allocation);
+            System::assert_last_event(Event::InstrumentalStrategy(
+                crate::Event::MigrationAborted {
+                    vault_id: 1,
+                    to: 42,
+                },
+            ));
+        });
+}
+
+#[test]
+fn test_migrations_complete_once_the_vault_is_on_the_new_strategy() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+            Migrations::<MockRuntime>::insert(
+                1,
+                Migration {
+                    to: pablo_strategy,
+                    chunk: Perquintill::from_percent(25),
+                    state: State::Transferring,
+                    since: 1,
+                },
+            );
+
+            InstrumentalStrategy::on_initialize(2);
+            assert_eq!(InstrumentalStrategy::migrations(1), None);
+            System::assert_last_event(Event::InstrumentalStrategy(
+                crate::Event::MigrationCompleted {
+                    vault_id: 1,
+                    to: pablo_strategy,
+                },
+            ));
+        });
+}
+
+const LIQUIDITY_PROVIDER: AccountId = 2;
+const DEPOSITOR: AccountId = 3;
+
+/// Creates a vault of `asset_id` deploying half of its funds into Pablo, along with the Pablo pool
+/// the strategy provides liquidity to.
+fn create_vault_deployed_into_pablo(asset_id: CurrencyId) -> (VaultId, PoolId) {
+    let quote_asset = CurrencyId::CROWD_LOAN;
+    let liquidity = 1_000_000_000 * CurrencyId::unit::<Balance>();
+    assert_ok!(Tokens::mint_into(asset_id, &LIQUIDITY_PROVIDER, liquidity));
+    assert_ok!(Tokens::mint_into(
+        quote_asset,
+        &LIQUIDITY_PROVIDER,
+        liquidity
+    ));
+    let pool_id =
+        Pablo::do_create_pool(
+            PoolInitConfiguration::<AccountId, CurrencyId, BlockNumber>::ConstantProduct {
+                owner: LIQUIDITY_PROVIDER,
+                pair: CurrencyPair {
+                    base: asset_id,
+                    quote: quote_asset,
+                },
+                fee: Permill::zero(),
+                base_weight: Permill::from_percent(50),
+            },
+        );
+    assert_ok!(pool_id);
+    let pool_id = pool_id.unwrap();
+    assert_ok!(<Pablo as Amm>::add_liquidity(
+        &LIQUIDITY_PROVIDER,
+        pool_id,
+        liquidity,
+        liquidity,
+        0_u128,
+        true
+    ));
+    pallet_instrumental_strategy_pablo::Pools::<MockRuntime>::insert(
+        asset_id,
+        PoolState {
+            pool_id,
+            state: State::Normal,
+        },
+    );
+
+    let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+    let config = VaultConfig {
+        asset_id,
+        manager: VAULT_MANAGER,
+        reserved: Perquintill::from_percent(50),
+        strategies: BTreeMap::from([(pablo_strategy, Perquintill::from_percent(50))]),
+    };
+    let vault_id = <Vault as VaultTrait>::create(Deposit::Existential, config);
+    assert_ok!(vault_id);
+    let vault_id = vault_id.unwrap();
+
+    let amount = 1_000 * CurrencyId::unit::<Balance>();
+    assert_ok!(Tokens::mint_into(asset_id, &DEPOSITOR, amount));
+    assert_ok!(<Vault as VaultTrait>::deposit(
+        &vault_id, &DEPOSITOR, amount
+    ));
+    assert_ok!(<InstrumentalStrategy as InstrumentalProtocolStrategy>::associate_vault(&vault_id));
+    assert_ok!(<InstrumentalStrategy as InstrumentalProtocolStrategy>::rebalance_vault(&vault_id));
+
+    (vault_id, pool_id)
+}
+
+#[test]
+fn test_migrations_move_the_funds_to_the_new_strategy() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+        let asset_id = CurrencyId::LAYR;
+        let (vault_id, pool_id) = create_vault_deployed_into_pablo(asset_id);
+        let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+        let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+
+        let idle = Tokens::balance(asset_id, &vault_account);
+        assert!(!Tokens::balance(lp_token, &pablo_strategy).is_zero());
+        assert!(!PabloStrategy::assets_under_management(&vault_id)
+            .unwrap()
+            .is_zero());
+
+        assert_ok!(InstrumentalStrategy::whitelist_strategy(
+            governance_origin(),
+            default_strategy
+        ));
+        assert_ok!(InstrumentalStrategy::migrate_vault_strategy(
+            governance_origin(),
+            vault_id,
+            default_strategy,
+            Perquintill::one()
+        ));
+        InstrumentalStrategy::on_initialize(2);
+
+        // Pablo returned everything it deployed to the vault, the default strategy keeping the
+        // funds there
+        assert!(Tokens::balance(lp_token, &pablo_strategy).is_zero());
+        assert!(Tokens::balance(asset_id, &pablo_strategy).is_zero());
+        assert!(Tokens::balance(asset_id, &vault_account) > idle);
+        assert!(!PabloStrategy::associated_vaults().contains(&vault_id));
+        assert_eq!(
+            InstrumentalStrategy::strategies_of(&vault_id),
+            vec![default_strategy]
+        );
+        assert_eq!(InstrumentalStrategy::migrations(vault_id), None);
+        System::assert_last_event(Event::InstrumentalStrategy(
+            crate::Event::MigrationCompleted {
+                vault_id,
+                to: default_strategy,
+            },
+        ));
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                        Rebalance Schedules
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_scheduled_rebalances_run_in_on_idle() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+            governance_origin(),
+            pablo_strategy,
+            Some(5)
+        ));
+        let schedule = InstrumentalStrategy::rebalance_schedules(pablo_strategy).unwrap();
+        assert_eq!(schedule.next_run, 6);
+
+        // Not due yet
+        InstrumentalStrategy::on_idle(5, u64::MAX);
+        assert_eq!(
+            InstrumentalStrategy::rebalance_schedules(pablo_strategy)
+                .unwrap()
+                .last_run,
+            None
+        );
+
+        InstrumentalStrategy::on_idle(6, u64::MAX);
+        let schedule = InstrumentalStrategy::rebalance_schedules(pablo_strategy).unwrap();
+        assert_eq!(schedule.last_run, Some(6));
+        assert_eq!(schedule.next_run, 11);
+        assert_eq!(schedule.consecutive_failures, 0);
+        System::assert_last_event(Event::InstrumentalStrategy(
+            crate::Event::ScheduledRebalanceSucceeded {
+                strategy: pablo_strategy,
+            },
+        ));
+    });
+}
+
+#[test]
+fn test_failed_scheduled_rebalances_back_off() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+            governance_origin(),
+            pablo_strategy,
+            Some(5)
+        ));
+        assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::halt());
+
+        InstrumentalStrategy::on_idle(6, u64::MAX);
+        let schedule = InstrumentalStrategy::rebalance_schedules(pablo_strategy).unwrap();
+        assert_eq!(schedule.consecutive_failures, 1);
+        assert_eq!(schedule.next_run, 16);
+
+        InstrumentalStrategy::on_idle(16, u64::MAX);
+        let schedule = InstrumentalStrategy::rebalance_schedules(pablo_strategy).unwrap();
+        assert_eq!(schedule.consecutive_failures, 2);
+        assert_eq!(schedule.next_run, 36);
+
+        assert_ok!(<PabloStrategy as InstrumentalProtocolStrategy>::start());
+        InstrumentalStrategy::on_idle(36, u64::MAX);
+        let schedule = InstrumentalStrategy::rebalance_schedules(pablo_strategy).unwrap();
+        assert_eq!(schedule.consecutive_failures, 0);
+        assert_eq!(schedule.next_run, 41);
+    });
+}
+
+#[test]
+fn test_scheduled_rebalances_respect_the_remaining_weight() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+            governance_origin(),
+            pablo_strategy,
+            Some(5)
+        ));
+
+        InstrumentalStrategy::on_idle(6, 0);
+        assert_eq!(
+            InstrumentalStrategy::rebalance_schedules(pablo_strategy)
+                .unwrap()
+                .last_run,
+            None
+        );
+    });
+}
+
+#[test]
+fn test_scheduled_rebalances_are_weighed_by_the_vaults_of_the_strategy() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1, 2])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+            assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+                governance_origin(),
+                pablo_strategy,
+                Some(5)
+            ));
+
+            // Enough for a strategy without vaults, not for one rebalancing two of them
+            InstrumentalStrategy::on_idle(6, <() as WeightInfo>::keeper_rebalance(0));
+            assert_eq!(
+                InstrumentalStrategy::rebalance_schedules(pablo_strategy)
+                    .unwrap()
+                    .last_run,
+                None
+            );
+
+            InstrumentalStrategy::on_idle(6, <() as WeightInfo>::keeper_rebalance(2));
+            assert_eq!(
+                InstrumentalStrategy::rebalance_schedules(pablo_strategy)
+                    .unwrap()
+                    .last_run,
+                Some(6)
+            );
+        });
+}
+
+#[test]
+fn test_scheduled_rebalances_run_the_most_overdue_first() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+        let default_strategy = <DefaultStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+            governance_origin(),
+            pablo_strategy,
+            Some(5)
+        ));
+        assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+            governance_origin(),
+            default_strategy,
+            Some(3)
+        ));
+
+        // Only one rebalance fits, the one due since block 4 runs first
+        InstrumentalStrategy::on_idle(6, <() as WeightInfo>::keeper_rebalance(0));
+        assert_eq!(
+            InstrumentalStrategy::rebalance_schedules(default_strategy)
+                .unwrap()
+                .last_run,
+            Some(6)
+        );
+        assert_eq!(
+            InstrumentalStrategy::rebalance_schedules(pablo_strategy)
+                .unwrap()
+                .last_run,
+            None
+        );
+
+        InstrumentalStrategy::on_idle(7, <() as WeightInfo>::keeper_rebalance(0));
+        assert_eq!(
+            InstrumentalStrategy::rebalance_schedules(pablo_strategy)
+                .unwrap()
+                .last_run,
+            Some(7)
+        );
+    });
+}
+
+#[test]
+fn test_invalid_rebalance_schedules_throw_an_error() {
+    ExtBuilder::default().build().execute_with(|| {
+        let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+        assert_noop!(
+            InstrumentalStrategy::set_rebalance_schedule(governance_origin(), 42, Some(5)),
+            Error::<MockRuntime>::StrategyNotRegistered
+        );
+        assert_noop!(
+            InstrumentalStrategy::set_rebalance_schedule(
+                governance_origin(),
+                pablo_strategy,
+                Some(0)
+            ),
+            Error::<MockRuntime>::InvalidRebalanceInterval
+        );
+
+        assert_ok!(InstrumentalStrategy::set_rebalance_schedule(
+            governance_origin(),
+            pablo_strategy,
+            None
+        ));
+        assert_eq!(
+            InstrumentalStrategy::rebalance_schedules(pablo_strategy),
+            None
+        );
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                            Runtime API
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_strategy_infos_list_the_registered_strategies() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1])
+        .build()
+        .execute_with(|| {
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+            let infos = InstrumentalStrategy::strategy_infos();
+            assert_eq!(infos.len(), 3);
+            let info = &infos[0];
+            assert_eq!(info.account, pablo_strategy);
+            assert_eq!(info.name, b"pablo".to_vec());
+            assert!(info.whitelisted);
+            assert_eq!(info.is_halted, Some(false));
+            assert_eq!(info.vaults, vec![1]);
+            let info = &infos[1];
+            assert_eq!(info.name, b"default".to_vec());
+            assert!(!info.whitelisted);
+            assert!(info.vaults.is_empty());
+        });
+}
+
+#[test]
+fn test_vault_infos_report_the_allocation_of_the_associated_vaults() {
+    ExtBuilder::default()
+        .associated_vaults(vec![1])
+        .build()
+        .execute_with(|| {
+            let pablo_strategy = <PabloStrategy as InstrumentalProtocolStrategy>::account_id();
+
+            let infos = InstrumentalStrategy::vault_infos();
+            assert_eq!(infos.len(), 1);
+            let info = &infos[0];
+            assert_eq!(info.vault_id, 1);
+            assert_eq!(info.allocation, vec![(pablo_strategy, Perquintill::one())]);
+            assert_eq!(info.assets_under_management.len(), 1);
+            assert_eq!(info.assets_under_management[0].0, pablo_strategy);
+            assert_eq!(info.target_allocation, None);
+            assert_eq!(info.migration, None);
+        });
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                              Scoring
+// -------------------------------------------------------------------------------------------------
+
+frame_support::parameter_types! {
+    pub const RiskPenalty: FixedU128 = FixedU128::from_inner(100_000_000_000_000_000);
+    pub const ConcentrationPenalty: Perquintill = Perquintill::from_percent(50);
+    pub const HighMigrationCost: FixedU128 = FixedU128::from_inner(30_000_000_000_000_000);
+    pub const LowMigrationCost: FixedU128 = FixedU128::from_inner(10_000_000_000_000_000);
+}
+
+/// A high yield but risky and crowded strategy the funds are not deployed into yet.
+fn risky_inputs() -> ScoreInputs {
+    ScoreInputs {
+        apy: FixedU128::saturating_from_rational(10, 100),
+        risk_score: Percent::from_percent(80),
+        tvl_share: Perquintill::from_percent(90),
+        is_current: false,
+    }
+}
+
+/// A lower yield but safe and lightly used strategy the funds are already deployed into.
+fn safe_inputs() -> ScoreInputs {
+    ScoreInputs {
+        apy: FixedU128::saturating_from_rational(8, 100),
+        risk_score: Percent::from_percent(10),
+        tvl_share: Perquintill::from_percent(10),
+        is_current: true,
+    }
+}
+
+#[test]
+fn test_raw_apy_prefers_the_highest_apy() {
+    assert_eq!(RawApy::score(&risky_inputs()), risky_inputs().apy);
+    assert!(RawApy::score(&risky_inputs()) > RawApy::score(&safe_inputs()));
+}
+
+#[test]
+fn test_risk_penalized_apy_prefers_the_safer_strategy() {
+    type Policy = RiskPenalizedApy<RiskPenalty>;
+
+    assert_eq!(
+        Policy::score(&risky_inputs()),
+        FixedU128::saturating_from_rational(2, 100)
+    );
+    assert_eq!(
+        Policy::score(&safe_inputs()),
+        FixedU128::saturating_from_rational(7, 100)
+    );
+
+    let hopeless = ScoreInputs {
+        apy: FixedU128::saturating_from_rational(1, 100),
+        ..risky_inputs()
+    };
+    assert_eq!(Policy::score(&hopeless), FixedU128::zero());
+}
+
+#[test]
+fn test_concentration_adjusted_apy_prefers_the_less_crowded_strategy() {
+    type Policy = ConcentrationAdjustedApy<ConcentrationPenalty>;
+
+    assert_eq!(
+        Policy::score(&risky_inputs()),
+        FixedU128::saturating_from_rational(55, 1000)
+    );
+    assert_eq!(
+        Policy::score(&safe_inputs()),
+        FixedU128::saturating_from_rational(76, 1000)
+    );
+}
+
+#[test]
+fn test_migration_cost_adjusted_apy_only_moves_for_a_large_enough_gain() {
+    assert_eq!(
+        MigrationCostAdjustedApy::<HighMigrationCost>::score(&safe_inputs()),
+        safe_inputs().apy
+    );
+    assert!(
+        MigrationCostAdjustedApy::<HighMigrationCost>::score(&risky_inputs())
+            < MigrationCostAdjustedApy::<HighMigrationCost>::score(&safe_inputs())
+    );
+    assert!(
+        MigrationCostAdjustedApy::<LowMigrationCost>::score(&risky_inputs())
+            > MigrationCostAdjustedApy::<LowMigrationCost>::score(&safe_inputs())
+    );
+}
@@ -5,6 +5,23 @@ use sp_std::marker::PhantomData;
 
 pub trait WeightInfo {
     fn test() -> Weight;
+    fn whitelist_strategy() -> Weight;
+    fn delist_strategy(v: u32) -> Weight;
+    fn associate_vault() -> Weight;
+    fn liquidity_rebalance() -> Weight;
+    fn set_allocation_limits() -> Weight;
+    fn set_allocation_cap() -> Weight;
+    fn optimize_allocation(s: u32) -> Weight;
+    fn propose_allocation(s: u32) -> Weight;
+    fn sample_apys(s: u32) -> Weight;
+    fn set_rebalance_schedule() -> Weight;
+    fn migrate_vault_strategy() -> Weight;
+    fn resume_vault_migration() -> Weight;
+    fn abort_vault_migration() -> Weight;
+    fn migrate_chunks(m: u32) -> Weight;
+    fn keeper_rebalance(v: u32) -> Weight;
+    fn halt() -> Weight;
+    fn start() -> Weight;
 }
 
 /// Weights for pallet_instrumental_strategy using the Substrate node and recommended hardware.
@@ -13,6 +30,74 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn test() -> Weight {
         10_000 as Weight
     }
+
+    fn whitelist_strategy() -> Weight {
+        10_000 as Weight
+    }
+
+    fn delist_strategy(v: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(v as Weight))
+    }
+
+    fn associate_vault() -> Weight {
+        10_000 as Weight
+    }
+
+    fn liquidity_rebalance() -> Weight {
+        10_000 as Weight
+    }
+
+    fn set_allocation_limits() -> Weight {
+        10_000 as Weight
+    }
+
+    fn set_allocation_cap() -> Weight {
+        10_000 as Weight
+    }
+
+    fn optimize_allocation(s: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+    }
+
+    fn propose_allocation(s: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+    }
+
+    fn sample_apys(s: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+    }
+
+    fn set_rebalance_schedule() -> Weight {
+        10_000 as Weight
+    }
+
+    fn migrate_vault_strategy() -> Weight {
+        10_000 as Weight
+    }
+
+    fn resume_vault_migration() -> Weight {
+        10_000 as Weight
+    }
+
+    fn abort_vault_migration() -> Weight {
+        10_000 as Weight
+    }
+
+    fn migrate_chunks(m: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(m as Weight))
+    }
+
+    fn keeper_rebalance(v: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(v as Weight))
+    }
+
+    fn halt() -> Weight {
+        10_000 as Weight
+    }
+
+    fn start() -> Weight {
+        10_000 as Weight
+    }
 }
 
 // For backwards compatibility and tests
@@ -20,4 +105,72 @@ impl WeightInfo for () {
     fn test() -> Weight {
         10_000 as Weight
     }
+
+    fn whitelist_strategy() -> Weight {
+        10_000 as Weight
+    }
+
+    fn delist_strategy(v: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(v as Weight))
+    }
+
+    fn associate_vault() -> Weight {
+        10_000 as Weight
+    }
+
+    fn liquidity_rebalance() -> Weight {
+        10_000 as Weight
+    }
+
+    fn set_allocation_limits() -> Weight {
+        10_000 as Weight
+    }
+
+    fn set_allocation_cap() -> Weight {
+        10_000 as Weight
+    }
+
+    fn optimize_allocation(s: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+    }
+
+    fn propose_allocation(s: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+    }
+
+    fn sample_apys(s: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+    }
+
+    fn set_rebalance_schedule() -> Weight {
+        10_000 as Weight
+    }
+
+    fn migrate_vault_strategy() -> Weight {
+        10_000 as Weight
+    }
+
+    fn resume_vault_migration() -> Weight {
+        10_000 as Weight
+    }
+
+    fn abort_vault_migration() -> Weight {
+        10_000 as Weight
+    }
+
+    fn migrate_chunks(m: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(m as Weight))
+    }
+
+    fn keeper_rebalance(v: u32) -> Weight {
+        (10_000 as Weight).saturating_add((10_000 as Weight).saturating_mul(v as Weight))
+    }
+
+    fn halt() -> Weight {
+        10_000 as Weight
+    }
+
+    fn start() -> Weight {
+        10_000 as Weight
+    }
 }
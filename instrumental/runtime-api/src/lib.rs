@@ -0,0 +1,20 @@
+//! # Instrumental Runtime API
+//!
+//! Vault-level queries for frontends: the realized APY of the vault of each asset.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use pallet_instrumental::RealizedApy;
+
+sp_api::decl_runtime_apis! {
+    pub trait InstrumentalRuntimeApi<AssetId>
+    where
+        AssetId: Codec,
+    {
+        /// The realized APY of the vault associated with `asset` over every window, `None` if no
+        /// vault is associated with `asset`. See
+        /// [`realized_apys`](pallet_instrumental::Pallet::realized_apys).
+        fn realized_apy(asset: AssetId) -> Option<RealizedApy>;
+    }
+}
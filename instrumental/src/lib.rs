@@ -12,9 +12,22 @@
 //! - [`create`](Pallet::create)
 //! - [`add_liquidity`](Pallet::add_liquidity)
 //! - [`remove_liquidity`](Pallet::remove_liquidity)
+//! - [`xcm_add_liquidity`](Pallet::xcm_add_liquidity)
+//! - [`xcm_remove_liquidity`](Pallet::xcm_remove_liquidity)
+//! - [`mint_position`](Pallet::mint_position)
+//! - [`transfer_position`](Pallet::transfer_position)
+//! - [`redeem_position`](Pallet::redeem_position)
+//! - [`create_deposit_schedule`](Pallet::create_deposit_schedule)
+//! - [`cancel_deposit_schedule`](Pallet::cancel_deposit_schedule)
 //!
 //! ### Terminology
 //!
+//! - Position: a deposit represented by a financial NFT (fNFT). The vault shares of the deposit are
+//!   held by the asset account of the fNFT, so whoever owns the fNFT owns the position.
+//!
+//! - Deposit schedule: a recurring deposit of a fixed amount of an asset every `period` blocks,
+//!   until a `total` amount has been deposited.
+//!
 //! ### Goals
 //!
 //! ### Actors
@@ -28,9 +41,15 @@
 //! - Mosaic Pallet: Instrumental speaks to the Mosaic pallet which then redirects calls to the
 //!   Instrumental pallet.
 //!
+//! - Sibling parachains: deposit and withdraw on behalf of their (remote) accounts through XCM
+//!   `Transact` calls to [`xcm_add_liquidity`](Pallet::xcm_add_liquidity) and
+//!   [`xcm_remove_liquidity`](Pallet::xcm_remove_liquidity).
+//!
 //! - [`Vault Pallet`](../composable_traits/vault/index.html): Each asset supported by this pallet
-//!   will have an underlying vault. Each vault will have an associated strategy that will dictate
-//!   where those assets will go in order to earn yield.
+//!   will have an underlying vault. Each vault will have one or more associated strategies that
+//!   will dictate where those assets will go in order to earn yield. The deployable part of a vault
+//!   can be given to the optimum strategy, spread by the dynamic strategy, or split between
+//!   explicitly weighted strategies.
 //!
 //! ### Implementations
 //!
@@ -45,11 +64,42 @@
 //!
 //! - [`remove_liquidity`](Pallet::remove_liquidity): Removes assets from its associated vault.
 //!
+//! - [`xcm_add_liquidity`](Pallet::xcm_add_liquidity): Adds assets of a remote account to its
+//!   associated vault.
+//!
+//! - [`xcm_remove_liquidity`](Pallet::xcm_remove_liquidity): Removes assets from its associated
+//!   vault and sends them back to the remote account via reserve transfer.
+//!
+//! - [`mint_position`](Pallet::mint_position): Adds assets to its associated vault and mints an
+//!   fNFT holding the issued vault shares.
+//!
+//! - [`transfer_position`](Pallet::transfer_position): Transfers the ownership of a position.
+//!
+//! - [`redeem_position`](Pallet::redeem_position): Burns a position and redeems its underlying
+//!   assets.
+//!
+//! - [`create_deposit_schedule`](Pallet::create_deposit_schedule): Creates a recurring deposit.
+//!
+//! - [`cancel_deposit_schedule`](Pallet::cancel_deposit_schedule): Cancels a recurring deposit.
+//!
 //! ### Runtime Storage Objects
 //!
 //! - [`AssetVault`]: Mapping of an [`AssetId`](Config::AssetId) to the underlying Cubic Vault's
 //!   [`VaultId`](Config::VaultId) that is responsible for enforcing the asset's strategy.
 //!
+//! - [`SharePriceSnapshots`]: Ring buffer of the share price of each vault, sampled every
+//!   [`SnapshotInterval`](Config::SnapshotInterval) blocks. Used to compute the realized APY of a
+//!   vault (see [`realized_apy`](Pallet::realized_apy)).
+//!
+//! - [`DepositSchedules`]: The recurring deposits of each account.
+//!
+//! - [`DepositScheduleQueue`]: The deposit schedules due at a given block. They are executed in
+//!   `on_initialize`, within the [`MaxScheduledDepositsWeight`](Config::MaxScheduledDepositsWeight)
+//!   budget; the ones that do not fit are postponed to the next block.
+//!
+//! - [`PendingDepositSchedules`]: The deposit schedules that found no room in
+//!   [`DepositScheduleQueue`]. They are queued again as soon as there is room.
+//!
 //! ## Usage
 //!
 //! ### Example
@@ -93,22 +143,37 @@ pub mod pallet {
 
     use codec::{Codec, FullCodec};
     use composable_support::validation::Validated;
-    use composable_traits::vault::{
-        Deposit as Duration, FundsAvailability, StrategicVault, Vault, VaultConfig,
+    use composable_traits::{
+        fnft::FinancialNft,
+        vault::{Deposit as Duration, FundsAvailability, StrategicVault, Vault, VaultConfig},
+    };
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{
+            fungibles::{Inspect, Transfer},
+            tokens::nonfungibles::{
+                Create, Inspect as NftInspect, Mutate as NftMutate, Transfer as NftTransfer,
+            },
+        },
+        transactional, PalletId,
     };
-    use frame_support::{pallet_prelude::*, transactional, PalletId};
     use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+    use orml_traits::xcm_transfer::XcmTransfer;
     use sp_runtime::{
         traits::{
-            AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Zero,
+            AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Convert,
+            One, Saturating, UniqueSaturatedInto, Zero,
         },
-        ArithmeticError, Perquintill,
+        ArithmeticError, FixedI128, FixedPointNumber, Perquintill,
     };
-    use sp_std::{collections::btree_map::BTreeMap, fmt::Debug};
+    use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, vec::Vec};
     use traits::{
-        instrumental::{Instrumental, InstrumentalDynamicStrategy, InstrumentalVaultConfig},
+        instrumental::{
+            Instrumental, InstrumentalDynamicStrategy, InstrumentalVaultConfig, StrategyAllocation,
+        },
         strategy::InstrumentalProtocolStrategy,
     };
+    use xcm::latest::MultiLocation;
 
     use crate::{
         validation::{ValidateVaultDoesNotExist, ValidateVaultExists},
@@ -186,14 +251,147 @@ pub mod pallet {
         /// vaults.
         #[pallet::constant]
         type PalletId: Get<PalletId>;
+
+        /// The number of blocks between two consecutive share price snapshots of a vault.
+        #[pallet::constant]
+        type SnapshotInterval: Get<Self::BlockNumber>;
+
+        /// The maximum number of share price snapshots stored for each vault. Together with
+        /// [`SnapshotInterval`](Config::SnapshotInterval) it should cover at least the longest
+        /// [`ApyWindow`].
+        #[pallet::constant]
+        type MaxSnapshots: Get<u32>;
+
+        /// The number of blocks produced in a day, used to measure [`ApyWindow`]s and to annualize
+        /// the realized returns.
+        #[pallet::constant]
+        type BlocksPerDay: Get<Self::BlockNumber>;
+
+        /// The amount of vault shares (LP tokens) whose value is recorded as the share price.
+        #[pallet::constant]
+        type ShareUnit: Get<Self::Balance>;
+
+        /// Origin of the XCM `Transact` calls sent by sibling parachains on behalf of remote
+        /// accounts. Resolves to the location of the remote account.
+        type XcmOrigin: EnsureOrigin<Self::Origin, Success = MultiLocation>;
+
+        /// Converts the location of a remote account into the local account holding its assets.
+        type LocationToAccountId: Convert<MultiLocation, Self::AccountId>;
+
+        /// Used to send withdrawn assets back to remote accounts via reserve transfer.
+        type XcmTransfer: XcmTransfer<Self::AccountId, Self::Balance, Self::AssetId>;
+
+        /// The weight limit of the execution of a reserve transfer on the destination chain.
+        #[pallet::constant]
+        type XcmDestWeight: Get<Weight>;
+
+        /// Used to move vault shares and redeemed assets in and out of positions.
+        type Currency: Inspect<Self::AccountId, AssetId = Self::AssetId, Balance = Self::Balance>
+            + Transfer<Self::AccountId, AssetId = Self::AssetId, Balance = Self::Balance>;
+
+        /// The ID of the fNFT representing a position.
+        type FinancialNftInstanceId: Parameter + Member + Copy + MaxEncodedLen;
+
+        /// Financial NFTs representing positions. There is one collection per asset, created
+        /// alongside the vault of the asset, or with the first position of vaults created before
+        /// positions existed. `collection_owner` must report the collections that exist.
+        type FinancialNft: FinancialNft<
+                Self::AccountId,
+                CollectionId = Self::AssetId,
+                ItemId = Self::FinancialNftInstanceId,
+            > + NftTransfer<Self::AccountId>;
+
+        /// The maximum number of deposit schedules due at the same block.
+        #[pallet::constant]
+        type MaxSchedulesPerBlock: Get<u32>;
+
+        /// The maximum number of deposit schedules a single account may hold.
+        #[pallet::constant]
+        type MaxSchedulesPerAccount: Get<u32>;
+
+        /// The maximum weight spent executing deposit schedules in a single block.
+        #[pallet::constant]
+        type MaxScheduledDepositsWeight: Get<Weight>;
     }
 
     // ---------------------------------------------------------------------------------------------
     //                                           Pallet Types
     // ---------------------------------------------------------------------------------------------
 
-    pub type InstrumentalVaultConfigFor<T> =
-        InstrumentalVaultConfig<<T as Config>::AssetId, Perquintill>;
+    pub type InstrumentalVaultConfigFor<T> = InstrumentalVaultConfig<
+        <T as frame_system::Config>::AccountId,
+        <T as Config>::AssetId,
+        Perquintill,
+    >;
+
+    /// The value of [`ShareUnit`](Config::ShareUnit) vault shares at a given block.
+    #[derive(
+        Encode, Decode, MaxEncodedLen, Clone, Copy, Default, RuntimeDebug, PartialEq, Eq, TypeInfo,
+    )]
+    pub struct SharePriceSnapshot<BlockNumber, Balance> {
+        pub block_number: BlockNumber,
+        pub share_price: Balance,
+    }
+
+    pub type SharePriceSnapshotOf<T> =
+        SharePriceSnapshot<<T as frame_system::Config>::BlockNumber, <T as Config>::Balance>;
+
+    /// The period over which the realized APY of a vault is measured.
+    #[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+    pub enum ApyWindow {
+        OneDay,
+        SevenDays,
+        ThirtyDays,
+    }
+
+    impl ApyWindow {
+        /// The length of the window in days.
+        pub const fn days(self) -> u32 {
+            match self {
+                ApyWindow::OneDay => 1,
+                ApyWindow::SevenDays => 7,
+                ApyWindow::ThirtyDays => 30,
+            }
+        }
+    }
+
+    /// Realized APY of a vault over every [`ApyWindow`]. `None` if there is not enough history for
+    /// the window yet.
+    #[derive(
+        Encode, Decode, MaxEncodedLen, Clone, Copy, Default, RuntimeDebug, PartialEq, Eq, TypeInfo,
+    )]
+    pub struct RealizedApy {
+        pub one_day: Option<FixedI128>,
+        pub seven_days: Option<FixedI128>,
+        pub thirty_days: Option<FixedI128>,
+    }
+
+    pub type DepositScheduleId = u64;
+
+    /// A recurring deposit of `amount` of `asset` every `period` blocks, until `total` has been
+    /// deposited.
+    #[derive(
+        Encode, Decode, MaxEncodedLen, Clone, Copy, Default, RuntimeDebug, PartialEq, Eq, TypeInfo,
+    )]
+    pub struct DepositSchedule<AssetId, Balance, BlockNumber> {
+        pub asset: AssetId,
+        pub amount: Balance,
+        pub period: BlockNumber,
+        pub total: Balance,
+        /// The amount deposited so far.
+        pub deposited: Balance,
+        /// The block at which the next deposit is due.
+        pub next_execution: BlockNumber,
+        /// Set when the last deposit was skipped because the balance of the owner was
+        /// insufficient.
+        pub insufficient_balance: bool,
+    }
+
+    pub type DepositScheduleOf<T> = DepositSchedule<
+        <T as Config>::AssetId,
+        <T as Config>::Balance,
+        <T as frame_system::Config>::BlockNumber,
+    >;
 
     // ---------------------------------------------------------------------------------------------
     //                                          Runtime Storage
@@ -205,6 +403,60 @@ pub mod pallet {
     #[pallet::getter(fn asset_vault)]
     pub type AssetVault<T: Config> = StorageMap<_, Blake2_128Concat, T::AssetId, T::VaultId>;
 
+    /// Ring buffer of the share price snapshots of a vault. Once the buffer is full, the oldest
+    /// snapshot (pointed to by [`SnapshotCursor`]) is overwritten.
+    #[pallet::storage]
+    #[pallet::getter(fn share_price_snapshots)]
+    pub type SharePriceSnapshots<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::VaultId,
+        BoundedVec<SharePriceSnapshotOf<T>, T::MaxSnapshots>,
+    >;
+
+    /// Position of the oldest snapshot in a full [`SharePriceSnapshots`] ring buffer.
+    #[pallet::storage]
+    pub type SnapshotCursor<T: Config> = StorageMap<_, Blake2_128Concat, T::VaultId, u32>;
+
+    /// The ID of the next deposit schedule.
+    #[pallet::storage]
+    pub type NextDepositScheduleId<T: Config> = StorageValue<_, DepositScheduleId>;
+
+    /// The deposit schedules of each account.
+    #[pallet::storage]
+    #[pallet::getter(fn deposit_schedules)]
+    pub type DepositSchedules<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        DepositScheduleId,
+        DepositScheduleOf<T>,
+    >;
+
+    /// The deposit schedules due at a given block.
+    #[pallet::storage]
+    #[pallet::getter(fn deposit_schedule_queue)]
+    pub type DepositScheduleQueue<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::BlockNumber,
+        BoundedVec<(T::AccountId, DepositScheduleId), T::MaxSchedulesPerBlock>,
+    >;
+
+    /// The deposit schedules that could not be queued, with the first block they may be queued at.
+    /// They are kept until there is room for them in [`DepositScheduleQueue`].
+    #[pallet::storage]
+    #[pallet::getter(fn pending_deposit_schedules)]
+    pub type PendingDepositSchedules<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        DepositScheduleId,
+        T::BlockNumber,
+    >;
+
     // ---------------------------------------------------------------------------------------------
     //                                          Runtime Events
     // ---------------------------------------------------------------------------------------------
@@ -233,6 +485,105 @@ pub mod pallet {
             asset: T::AssetId,
             amount: T::Balance,
         },
+
+        /// Emitted after a successful call to the [`xcm_add_liquidity`](Pallet::xcm_add_liquidity)
+        /// extrinsic.
+        AddedLiquidityFromRemote {
+            /// Location of the remote account.
+            location: MultiLocation,
+            asset: T::AssetId,
+            amount: T::Balance,
+        },
+
+        /// Emitted after a successful call to the
+        /// [`xcm_remove_liquidity`](Pallet::xcm_remove_liquidity) extrinsic.
+        RemovedLiquidityToRemote {
+            /// Location of the remote account.
+            location: MultiLocation,
+            asset: T::AssetId,
+            amount: T::Balance,
+        },
+
+        /// Emitted after a successful call to the [`mint_position`](Pallet::mint_position)
+        /// extrinsic.
+        PositionMinted {
+            asset: T::AssetId,
+            position: T::FinancialNftInstanceId,
+            owner: T::AccountId,
+            amount: T::Balance,
+            /// The amount of vault shares held by the position.
+            shares: T::Balance,
+        },
+
+        /// Emitted after a successful call to the [`transfer_position`](Pallet::transfer_position)
+        /// extrinsic.
+        PositionTransferred {
+            asset: T::AssetId,
+            position: T::FinancialNftInstanceId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+
+        /// Emitted after a successful call to the [`redeem_position`](Pallet::redeem_position)
+        /// extrinsic.
+        PositionRedeemed {
+            asset: T::AssetId,
+            position: T::FinancialNftInstanceId,
+            owner: T::AccountId,
+            /// The amount of `asset` redeemed.
+            amount: T::Balance,
+        },
+
+        /// Emitted after a successful call to the
+        /// [`create_deposit_schedule`](Pallet::create_deposit_schedule) extrinsic.
+        DepositScheduleCreated {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+            schedule: DepositScheduleOf<T>,
+        },
+
+        /// Emitted after a successful call to the
+        /// [`cancel_deposit_schedule`](Pallet::cancel_deposit_schedule) extrinsic.
+        DepositScheduleCancelled {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+        },
+
+        /// Emitted when a scheduled deposit has been executed.
+        ScheduledDepositExecuted {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+            amount: T::Balance,
+        },
+
+        /// Emitted when a scheduled deposit has been skipped because the balance of the owner was
+        /// insufficient. The schedule is flagged and the deposit is retried after a period.
+        ScheduledDepositSkipped {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+        },
+
+        /// Emitted when a scheduled deposit failed. The schedule is kept and retried after a
+        /// period.
+        ScheduledDepositFailed {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+            error: DispatchError,
+        },
+
+        /// Emitted when there was no room to queue a deposit schedule again. The schedule is kept
+        /// in [`PendingDepositSchedules`] until there is room for it.
+        DepositSchedulePending {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+        },
+
+        /// Emitted when the `total` amount of a deposit schedule has been deposited. The schedule
+        /// is removed.
+        DepositScheduleCompleted {
+            owner: T::AccountId,
+            schedule_id: DepositScheduleId,
+        },
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -258,6 +609,44 @@ pub mod pallet {
         /// This error is thrown if a user tries to withdraw an amount of assets that is currently
         /// not held in the specified vault.
         NotEnoughLiquidity,
+
+        /// This error is thrown when the realized APY is requested for a window that is not yet
+        /// covered by the share price snapshots of the vault.
+        NotEnoughSnapshots,
+
+        /// This error is thrown when a position (fNFT) does not exist.
+        PositionDoesNotExist,
+
+        /// This error is thrown when a user tries to transfer or redeem a position they do not
+        /// own.
+        NotPositionOwner,
+
+        /// This error is thrown when a deposit schedule is created with a zero `amount` or
+        /// `period`, or with a `total` lower than its `amount`.
+        InvalidDepositSchedule,
+
+        /// This error is thrown when a user tries to cancel a deposit schedule that does not
+        /// exist.
+        DepositScheduleDoesNotExist,
+
+        /// This error is thrown when there is no room left to queue a deposit schedule.
+        DepositScheduleQueueFull,
+
+        /// This error is thrown when a user tries to create a deposit schedule while already
+        /// holding [`MaxSchedulesPerAccount`](Config::MaxSchedulesPerAccount) of them.
+        TooManyDepositSchedules,
+
+        /// This error is thrown when the weights of the strategies of a new vault do not sum to
+        /// its `percent_deployable`.
+        InvalidStrategyAllocation,
+
+        /// This error is thrown when a new vault is split between more strategies than the dynamic
+        /// strategy allows.
+        TooManyStrategies,
+
+        /// This error is thrown when a new vault allocates funds to an account that is not an
+        /// eligible strategy of the dynamic strategy.
+        StrategyNotEligible,
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -265,7 +654,18 @@ pub mod pallet {
     // ---------------------------------------------------------------------------------------------
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(block_number: T::BlockNumber) -> Weight {
+            let mut weight = Self::execute_deposit_schedules(block_number);
+
+            let interval = T::SnapshotInterval::get();
+            if !interval.is_zero() && (block_number % interval).is_zero() {
+                weight = weight.saturating_add(Self::snapshot_share_prices(block_number));
+            }
+
+            weight
+        }
+    }
 
     // ---------------------------------------------------------------------------------------------
     //                                            Extrinsics
@@ -287,6 +687,8 @@ pub mod pallet {
         /// 1. the call must have been signed by the issuer.
         /// 2. [`config.asset_id`](InstrumentalVaultConfig) must not correspond to a
         /// preexisting Instrumental vault.
+        /// 3. the weights of [`config.strategies`](InstrumentalVaultConfig) must sum to
+        /// [`config.percent_deployable`](InstrumentalVaultConfig).
         ///
         /// ## Emits
         ///
@@ -301,6 +703,12 @@ pub mod pallet {
         ///
         /// - [`VaultAlreadyExists`](Error::VaultAlreadyExists): there already exists an underlying
         ///   vault for `asset`.
+        /// - [`InvalidStrategyAllocation`](Error::InvalidStrategyAllocation): the strategy weights
+        ///   do not sum to `percent_deployable`.
+        /// - [`TooManyStrategies`](Error::TooManyStrategies): the vault is split between more
+        ///   strategies than the dynamic strategy allows.
+        /// - [`StrategyNotEligible`](Error::StrategyNotEligible): an explicit strategy is not
+        ///   registered and whitelisted by the dynamic strategy.
         ///
         /// # Examples
         ///
@@ -316,7 +724,7 @@ pub mod pallet {
             // Requirement 1) This extrinsic must be signed
             let _from = ensure_signed(origin)?;
 
-            let vault_id = <Self as Instrumental>::create(config)?;
+            let vault_id = <Self as Instrumental>::create(config.clone())?;
             Self::deposit_event(Event::Created { vault_id, config });
 
             Ok(().into())
@@ -409,6 +817,415 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Add assets of a remote account into its underlying vault.
+        ///
+        /// # Overview
+        ///
+        /// Meant to be dispatched through an XCM `Transact` sent by a sibling parachain. The assets
+        /// are taken from the local account of the remote account (see
+        /// [`LocationToAccountId`](Config::LocationToAccountId)), so they have to be deposited
+        /// there beforehand, usually by the preceding instructions of the same message.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `asset`: the [`AssetId`](Config::AssetId) of the asset to deposit.
+        /// - `amount`: the amount of `asset` to deposit.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been sent by a remote account (see
+        ///    [`XcmOrigin`](Config::XcmOrigin)).
+        /// 2. There must be a vault associated with `asset`.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::AddedLiquidityFromRemote`]
+        ///
+        /// ## Errors
+        ///
+        /// - [`AssetDoesNotHaveAnAssociatedVault`](Error::AssetDoesNotHaveAnAssociatedVault): no
+        ///   vault has been created for `asset`.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::xcm_add_liquidity())]
+        pub fn xcm_add_liquidity(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            amount: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be sent by a remote account
+            let location = T::XcmOrigin::ensure_origin(origin)?;
+            let issuer = T::LocationToAccountId::convert(location.clone());
+
+            <Self as Instrumental>::add_liquidity(&issuer, &asset, amount)?;
+
+            Self::deposit_event(Event::AddedLiquidityFromRemote {
+                location,
+                asset,
+                amount,
+            });
+
+            Ok(().into())
+        }
+
+        /// Remove assets of a remote account from its underlying vault and send them back to the
+        /// remote account via reserve transfer.
+        ///
+        /// # Overview
+        ///
+        /// Meant to be dispatched through an XCM `Transact` sent by a sibling parachain.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `asset`: the [`AssetId`](Config::AssetId) of the asset to withdraw.
+        /// - `amount`: the amount of `asset` to withdraw.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been sent by a remote account (see
+        ///    [`XcmOrigin`](Config::XcmOrigin)).
+        /// 2. There must be a vault associated with `asset`.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::RemovedLiquidityToRemote`]
+        ///
+        /// ## Errors
+        ///
+        /// - [`AssetDoesNotHaveAnAssociatedVault`](Error::AssetDoesNotHaveAnAssociatedVault): no
+        ///   vault has been created for `asset`.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::xcm_remove_liquidity())]
+        pub fn xcm_remove_liquidity(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            amount: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be sent by a remote account
+            let location = T::XcmOrigin::ensure_origin(origin)?;
+            let issuer = T::LocationToAccountId::convert(location.clone());
+
+            Self::do_xcm_remove_liquidity(&issuer, &asset, amount, location.clone())?;
+
+            Self::deposit_event(Event::RemovedLiquidityToRemote {
+                location,
+                asset,
+                amount,
+            });
+
+            Ok(().into())
+        }
+
+        /// Add assets into its underlying vault and mint an fNFT representing the position.
+        ///
+        /// # Overview
+        ///
+        /// The vault shares issued for the deposit are held by the asset account of the fNFT,
+        /// which is minted to the issuer of this call.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `asset`: the [`AssetId`](Config::AssetId) of the asset to deposit.
+        /// - `amount`: the amount of `asset` to deposit.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been signed by the issuer.
+        /// 2. There must be a vault associated with `asset`.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::PositionMinted`]
+        ///
+        /// ## Errors
+        ///
+        /// - [`AssetDoesNotHaveAnAssociatedVault`](Error::AssetDoesNotHaveAnAssociatedVault): no
+        ///   vault has been created for `asset`.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::mint_position())]
+        pub fn mint_position(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            amount: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be signed
+            let issuer = ensure_signed(origin)?;
+
+            let (position, shares) = Self::do_mint_position(&issuer, &asset, amount)?;
+
+            Self::deposit_event(Event::PositionMinted {
+                asset,
+                position,
+                owner: issuer,
+                amount,
+                shares,
+            });
+
+            Ok(().into())
+        }
+
+        /// Transfer the ownership of a position, along with the vault shares it holds.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `asset`: the [`AssetId`](Config::AssetId) of the position (its fNFT collection).
+        /// - `position`: the ID of the fNFT representing the position.
+        /// - `to`: the new owner of the position.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been signed by the owner of the position.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::PositionTransferred`]
+        ///
+        /// ## Errors
+        ///
+        /// - [`PositionDoesNotExist`](Error::PositionDoesNotExist): no such position.
+        /// - [`NotPositionOwner`](Error::NotPositionOwner): the issuer does not own the position.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::transfer_position())]
+        pub fn transfer_position(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            position: T::FinancialNftInstanceId,
+            to: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be signed by the owner of the position
+            let issuer = ensure_signed(origin)?;
+            Self::ensure_position_owner(&issuer, &asset, &position)?;
+
+            T::FinancialNft::transfer(&asset, &position, &to)?;
+
+            Self::deposit_event(Event::PositionTransferred {
+                asset,
+                position,
+                from: issuer,
+                to,
+            });
+
+            Ok(().into())
+        }
+
+        /// Burn a position and redeem the assets underlying its vault shares.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `asset`: the [`AssetId`](Config::AssetId) of the position (its fNFT collection).
+        /// - `position`: the ID of the fNFT representing the position.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been signed by the owner of the position.
+        /// 2. The vault must hold enough `asset` to redeem the shares of the position.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::PositionRedeemed`]
+        ///
+        /// ## Errors
+        ///
+        /// - [`PositionDoesNotExist`](Error::PositionDoesNotExist): no such position.
+        /// - [`NotPositionOwner`](Error::NotPositionOwner): the issuer does not own the position.
+        /// - [`AssetDoesNotHaveAnAssociatedVault`](Error::AssetDoesNotHaveAnAssociatedVault): no
+        ///   vault has been created for `asset`.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::redeem_position())]
+        pub fn redeem_position(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            position: T::FinancialNftInstanceId,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be signed by the owner of the position
+            let issuer = ensure_signed(origin)?;
+            Self::ensure_position_owner(&issuer, &asset, &position)?;
+
+            let amount = Self::do_redeem_position(&issuer, &asset, &position)?;
+
+            Self::deposit_event(Event::PositionRedeemed {
+                asset,
+                position,
+                owner: issuer,
+                amount,
+            });
+
+            Ok(().into())
+        }
+
+        /// Create a recurring deposit of `amount` of `asset` every `period` blocks, until `total`
+        /// has been deposited.
+        ///
+        /// # Overview
+        ///
+        /// The first deposit is due `period` blocks after the creation of the schedule. Deposits
+        /// are skipped (and the schedule flagged) while the balance of the issuer is insufficient.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `asset`: the [`AssetId`](Config::AssetId) of the asset to deposit.
+        /// - `amount`: the amount of `asset` to deposit every period.
+        /// - `period`: the number of blocks between two deposits.
+        /// - `total`: the total amount of `asset` to deposit.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been signed by the issuer.
+        /// 2. There must be a vault associated with `asset`.
+        /// 3. `amount` and `period` must be non-zero and `total` must be at least `amount`.
+        /// 4. The issuer must hold fewer than
+        ///    [`MaxSchedulesPerAccount`](Config::MaxSchedulesPerAccount) deposit schedules.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::DepositScheduleCreated`]
+        ///
+        /// ## State Changes
+        ///
+        /// - [`DepositSchedules`]: the schedule is stored under the issuer.
+        /// - [`DepositScheduleQueue`]: the schedule is queued for its first deposit.
+        ///
+        /// ## Errors
+        ///
+        /// - [`AssetDoesNotHaveAnAssociatedVault`](Error::AssetDoesNotHaveAnAssociatedVault): no
+        ///   vault has been created for `asset`.
+        /// - [`InvalidDepositSchedule`](Error::InvalidDepositSchedule): requirement 3 is not met.
+        /// - [`TooManyDepositSchedules`](Error::TooManyDepositSchedules): requirement 4 is not met.
+        /// - [`DepositScheduleQueueFull`](Error::DepositScheduleQueueFull): too many schedules are
+        ///   due around the first deposit.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::create_deposit_schedule())]
+        pub fn create_deposit_schedule(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            amount: T::Balance,
+            period: T::BlockNumber,
+            total: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be signed
+            let issuer = ensure_signed(origin)?;
+
+            // Requirement 2) The asset must have an associated vault
+            ensure!(
+                AssetVault::<T>::contains_key(asset),
+                Error::<T>::AssetDoesNotHaveAnAssociatedVault
+            );
+
+            // Requirement 3) The schedule must be able to deposit something
+            ensure!(
+                !amount.is_zero() && !period.is_zero() && total >= amount,
+                Error::<T>::InvalidDepositSchedule
+            );
+
+            // Requirement 4) The issuer must not hold too many schedules
+            let max_schedules = T::MaxSchedulesPerAccount::get() as usize;
+            ensure!(
+                DepositSchedules::<T>::iter_prefix(&issuer)
+                    .take(max_schedules)
+                    .count()
+                    < max_schedules,
+                Error::<T>::TooManyDepositSchedules
+            );
+
+            let (schedule_id, schedule) =
+                Self::do_create_deposit_schedule(&issuer, asset, amount, period, total)?;
+
+            Self::deposit_event(Event::DepositScheduleCreated {
+                owner: issuer,
+                schedule_id,
+                schedule,
+            });
+
+            Ok(().into())
+        }
+
+        /// Cancel a deposit schedule of the issuer.
+        ///
+        /// ## Parameters
+        ///
+        /// - `origin`: [`Origin`](frame_system::pallet::Config::Origin) type representing the
+        ///   origin of this dispatch.
+        /// - `schedule_id`: the ID of the schedule to cancel.
+        ///
+        /// ## Requirements
+        ///
+        /// 1. The call must have been signed by the owner of the schedule.
+        ///
+        /// ## Emits
+        ///
+        /// - [`Event::DepositScheduleCancelled`]
+        ///
+        /// ## State Changes
+        ///
+        /// - [`DepositSchedules`]: the schedule is removed.
+        /// - [`DepositScheduleQueue`]: the schedule is removed from the queue.
+        /// - [`PendingDepositSchedules`]: the schedule is removed if it was pending.
+        ///
+        /// ## Errors
+        ///
+        /// - [`DepositScheduleDoesNotExist`](Error::DepositScheduleDoesNotExist): the issuer has no
+        ///   schedule with this ID.
+        ///
+        /// # Examples
+        ///
+        /// # Weight: O(TBD)
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_deposit_schedule())]
+        pub fn cancel_deposit_schedule(
+            origin: OriginFor<T>,
+            schedule_id: DepositScheduleId,
+        ) -> DispatchResultWithPostInfo {
+            // Requirement 1) This extrinsic must be signed by the owner of the schedule
+            let issuer = ensure_signed(origin)?;
+
+            let schedule = DepositSchedules::<T>::take(&issuer, schedule_id)
+                .ok_or(Error::<T>::DepositScheduleDoesNotExist)?;
+            DepositScheduleQueue::<T>::mutate_exists(schedule.next_execution, |queue| {
+                if let Some(entries) = queue {
+                    entries.retain(|(owner, id)| !(owner == &issuer && *id == schedule_id));
+                    if entries.is_empty() {
+                        *queue = None;
+                    }
+                }
+            });
+            PendingDepositSchedules::<T>::remove(&issuer, schedule_id);
+
+            Self::deposit_event(Event::DepositScheduleCancelled {
+                owner: issuer,
+                schedule_id,
+            });
+
+            Ok(().into())
+        }
     }
 
     // ---------------------------------------------------------------------------------------------
@@ -439,6 +1256,8 @@ pub mod pallet {
         ///
         /// 1. [`config.asset_id`](InstrumentalVaultConfig) must not correspond to a preexisting
         /// Instrumental vault.
+        /// 2. the weights of [`config.strategies`](InstrumentalVaultConfig) must sum to
+        /// [`config.percent_deployable`](InstrumentalVaultConfig).
         ///
         /// ## State Changes
         ///
@@ -449,6 +1268,12 @@ pub mod pallet {
         ///
         /// - [`VaultAlreadyExists`](Error::VaultAlreadyExists): their already exists an underlying
         ///   vault for `asset`.
+        /// - [`InvalidStrategyAllocation`](Error::InvalidStrategyAllocation): the strategy weights
+        ///   do not sum to `percent_deployable`.
+        /// - [`TooManyStrategies`](Error::TooManyStrategies): the vault is split between more
+        ///   strategies than the dynamic strategy allows.
+        /// - [`StrategyNotEligible`](Error::StrategyNotEligible): an explicit strategy is not
+        ///   registered and whitelisted by the dynamic strategy.
         ///
         /// # Runtime: O(TBD)
         fn create(config: InstrumentalVaultConfigFor<T>) -> Result<Self::VaultId, DispatchError> {
@@ -541,23 +1366,39 @@ pub mod pallet {
                 .checked_sub(&config.percent_deployable)
                 .ok_or(ArithmeticError::Overflow)?;
 
-            // TODO: (Nevin)
-            //  - obtain the optimum strategies account_id
-            let strategy_account_id = T::InstrumentalStrategy::get_optimum_strategy_for(asset_id)?;
-            let strategies: BTreeMap<T::AccountId, Perquintill> =
-                BTreeMap::from([(strategy_account_id, config.percent_deployable)]);
+            let strategies: BTreeMap<T::AccountId, Perquintill> = match config.strategies.clone() {
+                StrategyAllocation::Optimum => {
+                    let strategy_account_id =
+                        T::InstrumentalStrategy::get_optimum_strategy_for(asset_id)?;
+                    BTreeMap::from([(strategy_account_id, config.percent_deployable)])
+                }
+                StrategyAllocation::Diversified => {
+                    T::InstrumentalStrategy::get_diversified_allocation_for(
+                        asset_id,
+                        config.percent_deployable,
+                    )?
+                }
+                StrategyAllocation::Explicit(strategies) => strategies,
+            };
 
-            // Requirement 2) Create the underlying vault
+            // Requirement 2) The strategies must be eligible and, together with `reserved`,
+            // account for the whole vault
+            Self::ensure_strategy_allocation(&strategies, config.percent_deployable)?;
+
+            // Requirement 3) Create the underlying vault
             let vault_id: T::VaultId = T::Vault::create(
                 Duration::Existential,
                 VaultConfig {
                     asset_id,
-                    manager,
+                    manager: manager.clone(),
                     reserved,
                     strategies,
                 },
             )?;
 
+            // Requirement 4) Create the collection of the fNFTs representing positions of the asset
+            Self::ensure_position_collection(&asset_id, &manager)?;
+
             AssetVault::<T>::insert(asset_id, &vault_id);
 
             Ok(vault_id)
@@ -602,6 +1443,437 @@ pub mod pallet {
 
             Ok(())
         }
+
+        #[transactional]
+        fn do_xcm_remove_liquidity(
+            issuer: &T::AccountId,
+            asset: &T::AssetId,
+            amount: T::Balance,
+            location: MultiLocation,
+        ) -> Result<(), DispatchError> {
+            <Self as Instrumental>::remove_liquidity(issuer, asset, amount)?;
+
+            T::XcmTransfer::transfer(
+                issuer.clone(),
+                *asset,
+                amount,
+                location,
+                T::XcmDestWeight::get(),
+            )?;
+
+            Ok(())
+        }
+
+        fn ensure_strategy_allocation(
+            strategies: &BTreeMap<T::AccountId, Perquintill>,
+            percent_deployable: Perquintill,
+        ) -> Result<(), DispatchError> {
+            ensure!(
+                strategies.len() <= T::InstrumentalStrategy::MaxStrategies::get() as usize,
+                Error::<T>::TooManyStrategies
+            );
+            ensure!(
+                strategies.keys().all(T::InstrumentalStrategy::is_eligible),
+                Error::<T>::StrategyNotEligible
+            );
+
+            let allocated = strategies
+                .values()
+                .try_fold(0_u64, |sum, weight| sum.checked_add(weight.deconstruct()))
+                .ok_or(Error::<T>::InvalidStrategyAllocation)?;
+            ensure!(
+                allocated == percent_deployable.deconstruct(),
+                Error::<T>::InvalidStrategyAllocation
+            );
+
+            Ok(())
+        }
+
+        /// Creates the collection of the fNFTs representing positions of `asset`, owned by
+        /// `manager`, unless it already exists.
+        fn ensure_position_collection(
+            asset: &T::AssetId,
+            manager: &T::AccountId,
+        ) -> DispatchResult {
+            if T::FinancialNft::collection_owner(asset).is_none() {
+                T::FinancialNft::create_collection(asset, manager, manager)?;
+            }
+
+            Ok(())
+        }
+
+        fn ensure_position_owner(
+            issuer: &T::AccountId,
+            asset: &T::AssetId,
+            position: &T::FinancialNftInstanceId,
+        ) -> Result<(), DispatchError> {
+            let owner =
+                T::FinancialNft::owner(asset, position).ok_or(Error::<T>::PositionDoesNotExist)?;
+            ensure!(&owner == issuer, Error::<T>::NotPositionOwner);
+
+            Ok(())
+        }
+
+        #[transactional]
+        fn do_mint_position(
+            issuer: &T::AccountId,
+            asset: &T::AssetId,
+            amount: T::Balance,
+        ) -> Result<(T::FinancialNftInstanceId, T::Balance), DispatchError> {
+            let vault_id: T::VaultId =
+                Self::asset_vault(asset).ok_or(Error::<T>::AssetDoesNotHaveAnAssociatedVault)?;
+
+            // Vaults created before positions were introduced have no collection yet
+            Self::ensure_position_collection(asset, &Self::account_id())?;
+
+            let position = T::FinancialNft::get_next_nft_id(asset)?;
+            T::FinancialNft::mint_into(asset, &position, issuer)?;
+
+            // The shares are issued to the issuer first and then locked in the position
+            let shares = <T::Vault as Vault>::deposit(&vault_id, issuer, amount)?;
+            let lp_token = <T::Vault as Vault>::lp_asset_id(&vault_id)?;
+            let position_account = T::FinancialNft::asset_account(asset, &position);
+            T::Currency::transfer(lp_token, issuer, &position_account, shares, false)?;
+
+            Ok((position, shares))
+        }
+
+        #[transactional]
+        fn do_redeem_position(
+            owner: &T::AccountId,
+            asset: &T::AssetId,
+            position: &T::FinancialNftInstanceId,
+        ) -> Result<T::Balance, DispatchError> {
+            let vault_id: T::VaultId =
+                Self::asset_vault(asset).ok_or(Error::<T>::AssetDoesNotHaveAnAssociatedVault)?;
+
+            let lp_token = <T::Vault as Vault>::lp_asset_id(&vault_id)?;
+            let position_account = T::FinancialNft::asset_account(asset, position);
+            let shares = T::Currency::balance(lp_token, &position_account);
+
+            let amount = <T::Vault as Vault>::withdraw(&vault_id, &position_account, shares)?;
+            T::Currency::transfer(*asset, &position_account, owner, amount, false)?;
+
+            T::FinancialNft::burn(asset, position, Some(owner))?;
+
+            Ok(amount)
+        }
+
+        #[transactional]
+        fn do_create_deposit_schedule(
+            owner: &T::AccountId,
+            asset: T::AssetId,
+            amount: T::Balance,
+            period: T::BlockNumber,
+            total: T::Balance,
+        ) -> Result<(DepositScheduleId, DepositScheduleOf<T>), DispatchError> {
+            let schedule_id = NextDepositScheduleId::<T>::get().unwrap_or_default();
+            NextDepositScheduleId::<T>::put(
+                schedule_id
+                    .checked_add(1)
+                    .ok_or(ArithmeticError::Overflow)?,
+            );
+
+            let first_execution = frame_system::Pallet::<T>::block_number().saturating_add(period);
+            let next_execution =
+                Self::enqueue_deposit_schedule(first_execution, owner, schedule_id)?;
+
+            let schedule = DepositSchedule {
+                asset,
+                amount,
+                period,
+                total,
+                deposited: T::Balance::zero(),
+                next_execution,
+                insufficient_balance: false,
+            };
+            DepositSchedules::<T>::insert(owner, schedule_id, schedule);
+
+            Ok((schedule_id, schedule))
+        }
+
+        /// Queue a deposit schedule at the first block, starting from `from`, with room left.
+        fn enqueue_deposit_schedule(
+            from: T::BlockNumber,
+            owner: &T::AccountId,
+            schedule_id: DepositScheduleId,
+        ) -> Result<T::BlockNumber, DispatchError> {
+            let mut block_number = from;
+            for _ in 0..T::MaxSchedulesPerBlock::get() {
+                let queued = DepositScheduleQueue::<T>::mutate(block_number, |queue| {
+                    queue
+                        .get_or_insert_with(BoundedVec::default)
+                        .try_push((owner.clone(), schedule_id))
+                        .is_ok()
+                });
+                if queued {
+                    return Ok(block_number);
+                }
+                block_number = block_number.saturating_add(One::one());
+            }
+
+            Err(Error::<T>::DepositScheduleQueueFull.into())
+        }
+
+        /// Execute the deposit schedules due at `block_number`. The ones exceeding the
+        /// [`MaxScheduledDepositsWeight`](Config::MaxScheduledDepositsWeight) budget are postponed
+        /// to the next block.
+        fn execute_deposit_schedules(block_number: T::BlockNumber) -> Weight {
+            let db_weight = T::DbWeight::get();
+            // Taking the queue reads and clears it
+            let mut weight = db_weight.reads_writes(1, 1);
+            let due = match DepositScheduleQueue::<T>::take(block_number) {
+                Some(due) => due,
+                None => return weight,
+            };
+
+            // Queueing a schedule again probes up to `MaxSchedulesPerBlock` queues before storing
+            // the schedule
+            let max_probes: Weight = T::MaxSchedulesPerBlock::get().into();
+            let reschedule_weight =
+                db_weight.reads_writes(max_probes, max_probes.saturating_add(1));
+            let schedule_weight =
+                T::WeightInfo::execute_deposit_schedule().saturating_add(reschedule_weight);
+            let weight_limit = T::MaxScheduledDepositsWeight::get();
+            let mut executed_weight: Weight = 0;
+            let next_block = block_number.saturating_add(One::one());
+
+            for (owner, schedule_id) in due {
+                weight = weight.saturating_add(db_weight.reads(1));
+                let schedule = match DepositSchedules::<T>::get(&owner, schedule_id) {
+                    Some(schedule) => schedule,
+                    None => continue,
+                };
+
+                if executed_weight.saturating_add(schedule_weight) > weight_limit {
+                    weight = weight.saturating_add(reschedule_weight);
+                    Self::reschedule_deposit(&owner, schedule_id, schedule, next_block);
+                    continue;
+                }
+
+                executed_weight = executed_weight.saturating_add(schedule_weight);
+                Self::execute_deposit_schedule(block_number, &owner, schedule_id, schedule);
+            }
+
+            // Pending schedules are queued again with what is left of the budget
+            let pending: Vec<_> = PendingDepositSchedules::<T>::iter()
+                .take(T::MaxSchedulesPerBlock::get() as usize)
+                .collect();
+            for (owner, schedule_id, from) in pending {
+                if executed_weight.saturating_add(reschedule_weight) > weight_limit {
+                    break;
+                }
+                weight = weight.saturating_add(db_weight.reads_writes(2, 1));
+                executed_weight = executed_weight.saturating_add(reschedule_weight);
+                match DepositSchedules::<T>::get(&owner, schedule_id) {
+                    Some(schedule) => Self::reschedule_deposit(
+                        &owner,
+                        schedule_id,
+                        schedule,
+                        from.max(next_block),
+                    ),
+                    None => PendingDepositSchedules::<T>::remove(&owner, schedule_id),
+                }
+            }
+
+            weight.saturating_add(executed_weight)
+        }
+
+        fn execute_deposit_schedule(
+            block_number: T::BlockNumber,
+            owner: &T::AccountId,
+            schedule_id: DepositScheduleId,
+            mut schedule: DepositScheduleOf<T>,
+        ) {
+            let amount = schedule
+                .amount
+                .min(schedule.total.saturating_sub(schedule.deposited));
+
+            if T::Currency::reducible_balance(schedule.asset, owner, false) < amount {
+                schedule.insufficient_balance = true;
+                Self::deposit_event(Event::ScheduledDepositSkipped {
+                    owner: owner.clone(),
+                    schedule_id,
+                });
+            } else {
+                match <Self as Instrumental>::add_liquidity(owner, &schedule.asset, amount) {
+                    Ok(()) => {
+                        schedule.deposited = schedule.deposited.saturating_add(amount);
+                        schedule.insufficient_balance = false;
+                        Self::deposit_event(Event::ScheduledDepositExecuted {
+                            owner: owner.clone(),
+                            schedule_id,
+                            amount,
+                        });
+                    }
+                    Err(error) => Self::deposit_event(Event::ScheduledDepositFailed {
+                        owner: owner.clone(),
+                        schedule_id,
+                        error,
+                    }),
+                }
+            }
+
+            if schedule.deposited >= schedule.total {
+                DepositSchedules::<T>::remove(owner, schedule_id);
+                Self::deposit_event(Event::DepositScheduleCompleted {
+                    owner: owner.clone(),
+                    schedule_id,
+                });
+                return;
+            }
+
+            let next_execution = block_number.saturating_add(schedule.period);
+            Self::reschedule_deposit(owner, schedule_id, schedule, next_execution);
+        }
+
+        fn reschedule_deposit(
+            owner: &T::AccountId,
+            schedule_id: DepositScheduleId,
+            mut schedule: DepositScheduleOf<T>,
+            from: T::BlockNumber,
+        ) {
+            match Self::enqueue_deposit_schedule(from, owner, schedule_id) {
+                Ok(next_execution) => {
+                    schedule.next_execution = next_execution;
+                    DepositSchedules::<T>::insert(owner, schedule_id, schedule);
+                    PendingDepositSchedules::<T>::remove(owner, schedule_id);
+                }
+                // The schedule is never dropped, it waits for room in the queue instead
+                Err(_) => {
+                    DepositSchedules::<T>::insert(owner, schedule_id, schedule);
+                    if !PendingDepositSchedules::<T>::contains_key(owner, schedule_id) {
+                        Self::deposit_event(Event::DepositSchedulePending {
+                            owner: owner.clone(),
+                            schedule_id,
+                        });
+                    }
+                    PendingDepositSchedules::<T>::insert(owner, schedule_id, from);
+                }
+            }
+        }
+
+        /// Record the current share price of every Instrumental vault.
+        fn snapshot_share_prices(block_number: T::BlockNumber) -> Weight {
+            let mut snapshots: u32 = 0;
+
+            AssetVault::<T>::iter_values().for_each(|vault_id| {
+                // Vaults without issued shares have no share price yet
+                if let Ok(share_price) =
+                    <T::Vault as Vault>::lp_share_value(&vault_id, T::ShareUnit::get())
+                {
+                    Self::push_snapshot(
+                        &vault_id,
+                        SharePriceSnapshot {
+                            block_number,
+                            share_price,
+                        },
+                    );
+                    snapshots = snapshots.saturating_add(1);
+                }
+            });
+
+            T::WeightInfo::snapshot_share_prices(snapshots)
+        }
+
+        fn push_snapshot(vault_id: &T::VaultId, snapshot: SharePriceSnapshotOf<T>) {
+            SharePriceSnapshots::<T>::mutate(vault_id, |snapshots| {
+                let snapshots = snapshots.get_or_insert_with(BoundedVec::default);
+                if snapshots.try_push(snapshot).is_ok() {
+                    return;
+                }
+
+                // The buffer is full: overwrite the oldest snapshot and move the cursor forward
+                let cursor = SnapshotCursor::<T>::get(vault_id).unwrap_or_default();
+                if let Some(oldest) = snapshots.get_mut(cursor as usize) {
+                    *oldest = snapshot;
+                }
+                let next_cursor = cursor
+                    .saturating_add(1)
+                    .checked_rem(T::MaxSnapshots::get())
+                    .unwrap_or_default();
+                SnapshotCursor::<T>::insert(vault_id, next_cursor);
+            });
+        }
+
+        /// Returns the realized APY of the vault associated with `asset` over `window`, computed
+        /// from the latest share price snapshot and the latest snapshot taken at least `window`
+        /// before it.
+        ///
+        /// The APY is annualized linearly, without compounding, like the
+        /// [`Apy`](traits::strategy::Apy) reported by strategies; `1` corresponds to 100%.
+        ///
+        /// ## Errors
+        ///
+        /// - [`AssetDoesNotHaveAnAssociatedVault`](Error::AssetDoesNotHaveAnAssociatedVault): no
+        ///   vault has been created for `asset`.
+        /// - [`NotEnoughSnapshots`](Error::NotEnoughSnapshots): the snapshots of the vault do not
+        ///   cover `window` yet.
+        pub fn realized_apy(
+            asset: &T::AssetId,
+            window: ApyWindow,
+        ) -> Result<FixedI128, DispatchError> {
+            let vault_id =
+                Self::asset_vault(asset).ok_or(Error::<T>::AssetDoesNotHaveAnAssociatedVault)?;
+            let snapshots = Self::share_price_snapshots(&vault_id).unwrap_or_default();
+
+            let latest = snapshots
+                .iter()
+                .max_by_key(|snapshot| snapshot.block_number)
+                .ok_or(Error::<T>::NotEnoughSnapshots)?;
+            let window_length = T::BlocksPerDay::get().saturating_mul(window.days().into());
+            let window_start = latest.block_number.saturating_sub(window_length);
+            let earliest = snapshots
+                .iter()
+                .filter(|snapshot| {
+                    snapshot.block_number <= window_start
+                        && snapshot.block_number < latest.block_number
+                })
+                .max_by_key(|snapshot| snapshot.block_number)
+                .ok_or(Error::<T>::NotEnoughSnapshots)?;
+
+            Self::annualized_return(earliest, latest)
+        }
+
+        /// Returns the realized APY of the vault associated with `asset` over every
+        /// [`ApyWindow`].
+        pub fn realized_apys(asset: &T::AssetId) -> Result<RealizedApy, DispatchError> {
+            ensure!(
+                AssetVault::<T>::contains_key(asset),
+                Error::<T>::AssetDoesNotHaveAnAssociatedVault
+            );
+
+            Ok(RealizedApy {
+                one_day: Self::realized_apy(asset, ApyWindow::OneDay).ok(),
+                seven_days: Self::realized_apy(asset, ApyWindow::SevenDays).ok(),
+                thirty_days: Self::realized_apy(asset, ApyWindow::ThirtyDays).ok(),
+            })
+        }
+
+        fn annualized_return(
+            from: &SharePriceSnapshotOf<T>,
+            to: &SharePriceSnapshotOf<T>,
+        ) -> Result<FixedI128, DispatchError> {
+            let elapsed: u128 = to
+                .block_number
+                .saturating_sub(from.block_number)
+                .unique_saturated_into();
+            let blocks_per_year: u128 = T::BlocksPerDay::get()
+                .saturating_mul(365_u32.into())
+                .unique_saturated_into();
+            let from_price: u128 = from.share_price.unique_saturated_into();
+            let to_price: u128 = to.share_price.unique_saturated_into();
+
+            let growth = FixedI128::checked_from_rational(to_price, from_price)
+                .ok_or(ArithmeticError::DivisionByZero)?
+                .saturating_sub(FixedI128::one());
+            let periods_per_year = FixedI128::checked_from_rational(blocks_per_year, elapsed)
+                .ok_or(ArithmeticError::DivisionByZero)?;
+
+            growth
+                .checked_mul(&periods_per_year)
+                .ok_or_else(|| ArithmeticError::Overflow.into())
+        }
     }
 }
 
@@ -4,7 +4,7 @@ use frame_support::{
 };
 use primitives::currency::CurrencyId;
 use sp_runtime::Perquintill;
-use traits::instrumental::InstrumentalVaultConfig;
+use traits::instrumental::{InstrumentalVaultConfig, StrategyAllocation};
 
 use super::{
     account_id::{AccountId, ADMIN},
@@ -18,6 +18,7 @@ use super::{
 pub struct InstrumentalVaultConfigBuilder {
     pub asset_id: CurrencyId,
     pub percent_deployable: Perquintill,
+    pub strategies: StrategyAllocation<AccountId, Perquintill>,
 }
 
 impl Default for InstrumentalVaultConfigBuilder {
@@ -25,16 +26,18 @@ impl Default for InstrumentalVaultConfigBuilder {
         InstrumentalVaultConfigBuilder {
             asset_id: CurrencyId::USDC,
             percent_deployable: Perquintill::zero(),
+            strategies: StrategyAllocation::Optimum,
         }
     }
 }
 
 #[allow(dead_code)]
 impl InstrumentalVaultConfigBuilder {
-    pub fn build(self) -> InstrumentalVaultConfig<CurrencyId, Perquintill> {
+    pub fn build(self) -> InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill> {
         InstrumentalVaultConfig {
             asset_id: self.asset_id,
             percent_deployable: self.percent_deployable,
+            strategies: self.strategies,
         }
     }
 
@@ -47,6 +50,11 @@ impl InstrumentalVaultConfigBuilder {
         self.percent_deployable = percent_deployable;
         self
     }
+
+    pub fn strategies(mut self, strategies: StrategyAllocation<AccountId, Perquintill>) -> Self {
+        self.strategies = strategies;
+        self
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -108,7 +116,7 @@ impl VaultConfigBuilder {
 // -------------------------------------------------------------------------------------------------
 
 pub struct InstrumentalVaultBuilder {
-    pub configs: Vec<InstrumentalVaultConfig<CurrencyId, Perquintill>>,
+    pub configs: Vec<InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>>,
 }
 
 #[allow(dead_code)]
@@ -119,12 +127,15 @@ impl InstrumentalVaultBuilder {
         }
     }
 
-    fn add(mut self, config: InstrumentalVaultConfig<CurrencyId, Perquintill>) -> Self {
+    fn add(mut self, config: InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>) -> Self {
         self.configs.push(config);
         self
     }
 
-    fn group_add(mut self, configs: Vec<InstrumentalVaultConfig<CurrencyId, Perquintill>>) -> Self {
+    fn group_add(
+        mut self,
+        configs: Vec<InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>>,
+    ) -> Self {
         configs.into_iter().for_each(|config| {
             self.configs.push(config);
         });
@@ -134,7 +145,7 @@ impl InstrumentalVaultBuilder {
     fn build(self) {
         // TODO: (Nevin)
         //  - remove duplicate assets
-        self.configs.iter().for_each(|&config| {
+        self.configs.into_iter().for_each(|config| {
             Instrumental::create(Origin::signed(ADMIN), config).ok();
         })
     }
@@ -145,10 +156,13 @@ impl InstrumentalVaultBuilder {
 // -------------------------------------------------------------------------------------------------
 
 pub trait InstrumentalVaultInitializer {
-    fn initialize_vault(self, config: InstrumentalVaultConfig<CurrencyId, Perquintill>) -> Self;
+    fn initialize_vault(
+        self,
+        config: InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>,
+    ) -> Self;
     fn initialize_vaults(
         self,
-        configs: Vec<InstrumentalVaultConfig<CurrencyId, Perquintill>>,
+        configs: Vec<InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>>,
     ) -> Self;
 
     fn initialize_reserve(self, asset: CurrencyId, balance: Balance) -> Self;
@@ -156,7 +170,7 @@ pub trait InstrumentalVaultInitializer {
 
     fn initialize_vaults_with_reserves(
         self,
-        configs: Vec<InstrumentalVaultConfig<CurrencyId, Perquintill>>,
+        configs: Vec<InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>>,
         reserves: Vec<(CurrencyId, Balance)>,
     ) -> Self;
 }
@@ -164,7 +178,7 @@ pub trait InstrumentalVaultInitializer {
 impl InstrumentalVaultInitializer for sp_io::TestExternalities {
     fn initialize_vault(
         mut self,
-        config: InstrumentalVaultConfig<CurrencyId, Perquintill>,
+        config: InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>,
     ) -> Self {
         self.execute_with(|| Instrumental::create(Origin::signed(ADMIN), config).ok());
 
@@ -173,10 +187,10 @@ impl InstrumentalVaultInitializer for sp_io::TestExternalities {
 
     fn initialize_vaults(
         mut self,
-        configs: Vec<InstrumentalVaultConfig<CurrencyId, Perquintill>>,
+        configs: Vec<InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>>,
     ) -> Self {
         self.execute_with(|| {
-            configs.iter().for_each(|&config| {
+            configs.into_iter().for_each(|config| {
                 Instrumental::create(Origin::signed(ADMIN), config).ok();
             });
         });
@@ -220,7 +234,7 @@ impl InstrumentalVaultInitializer for sp_io::TestExternalities {
 
     fn initialize_vaults_with_reserves(
         self,
-        configs: Vec<InstrumentalVaultConfig<CurrencyId, Perquintill>>,
+        configs: Vec<InstrumentalVaultConfig<AccountId, CurrencyId, Perquintill>>,
         reserves: Vec<(CurrencyId, Balance)>,
     ) -> Self {
         self.initialize_vaults(configs)
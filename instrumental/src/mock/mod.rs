@@ -5,4 +5,6 @@ pub mod fnft; // FIXME(saruman9): should be deledeted when there is a public int
 #[cfg(test)]
 pub mod helpers;
 #[cfg(test)]
+pub mod network;
+#[cfg(test)]
 pub mod runtime;
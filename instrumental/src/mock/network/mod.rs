@@ -0,0 +1,131 @@
+//! XCM test network made of the relay chain, the Instrumental parachain (running [`MockRuntime`])
+//! and a sibling parachain.
+
+use primitives::currency::CurrencyId;
+use sp_runtime::traits::Convert;
+use xcm::latest::prelude::*;
+use xcm_simulator::{decl_test_network, decl_test_parachain, decl_test_relay_chain};
+
+use super::runtime::{ExtBuilder, MockRuntime, MsgQueue, System};
+
+pub mod msg_queue;
+pub mod relay;
+pub mod sibling;
+
+pub const INSTRUMENTAL_PARA_ID: u32 = 1;
+pub const SIBLING_PARA_ID: u32 = 2;
+
+decl_test_parachain! {
+    pub struct InstrumentalPara {
+        Runtime = MockRuntime,
+        XcmpMessageHandler = MsgQueue,
+        DmpMessageHandler = MsgQueue,
+        new_ext = instrumental_ext(),
+    }
+}
+
+decl_test_parachain! {
+    pub struct SiblingPara {
+        Runtime = sibling::Runtime,
+        XcmpMessageHandler = sibling::MsgQueue,
+        DmpMessageHandler = sibling::MsgQueue,
+        new_ext = sibling_ext(),
+    }
+}
+
+decl_test_relay_chain! {
+    pub struct Relay {
+        Runtime = relay::Runtime,
+        XcmConfig = relay::XcmConfig,
+        new_ext = relay_ext(),
+    }
+}
+
+decl_test_network! {
+    pub struct MockNet {
+        relay_chain = Relay,
+        parachains = vec![
+            (INSTRUMENTAL_PARA_ID, InstrumentalPara),
+            (SIBLING_PARA_ID, SiblingPara),
+        ],
+    }
+}
+
+pub fn instrumental_ext() -> sp_io::TestExternalities {
+    let mut ext = ExtBuilder::default().build();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        MsgQueue::set_para_id(INSTRUMENTAL_PARA_ID.into());
+    });
+    ext
+}
+
+pub fn sibling_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::default()
+        .build_storage::<sibling::Runtime>()
+        .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| {
+        sibling::System::set_block_number(1);
+        sibling::MsgQueue::set_para_id(SIBLING_PARA_ID.into());
+    });
+    ext
+}
+
+pub fn relay_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::default()
+        .build_storage::<relay::Runtime>()
+        .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| relay::System::set_block_number(1));
+    ext
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                        Currency Locations
+// -------------------------------------------------------------------------------------------------
+
+/// Every currency of the test network is reserved on the Instrumental parachain.
+pub fn currency_location(currency_id: CurrencyId) -> MultiLocation {
+    MultiLocation::new(
+        1,
+        X2(Parachain(INSTRUMENTAL_PARA_ID), GeneralIndex(currency_id.0)),
+    )
+}
+
+pub struct CurrencyIdConvert;
+
+impl Convert<CurrencyId, Option<MultiLocation>> for CurrencyIdConvert {
+    fn convert(currency_id: CurrencyId) -> Option<MultiLocation> {
+        Some(currency_location(currency_id))
+    }
+}
+
+impl Convert<MultiLocation, Option<CurrencyId>> for CurrencyIdConvert {
+    fn convert(location: MultiLocation) -> Option<CurrencyId> {
+        match location {
+            MultiLocation {
+                parents: 0,
+                interior: X1(GeneralIndex(index)),
+            } => Some(CurrencyId(index)),
+            MultiLocation {
+                parents: 1,
+                interior: X2(Parachain(para_id), GeneralIndex(index)),
+            } if para_id == INSTRUMENTAL_PARA_ID => Some(CurrencyId(index)),
+            _ => None,
+        }
+    }
+}
+
+impl Convert<MultiAsset, Option<CurrencyId>> for CurrencyIdConvert {
+    fn convert(asset: MultiAsset) -> Option<CurrencyId> {
+        match asset.id {
+            Concrete(location) => {
+                <Self as Convert<MultiLocation, Option<CurrencyId>>>::convert(location)
+            }
+            Abstract(_) => None,
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Minimal message queue of the mock parachains, handling the XCMP and DMP messages delivered by
+//! the simulator.
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use codec::{Decode, Encode};
+    use frame_support::{pallet_prelude::*, weights::Weight};
+    use polkadot_parachain::primitives::{
+        DmpMessageHandler, Id as ParaId, XcmpMessageFormat, XcmpMessageHandler,
+    };
+    use sp_runtime::traits::Hash;
+    use sp_std::prelude::*;
+    use xcm::{latest::prelude::*, VersionedXcm};
+
+    type RelayBlockNumber = u32;
+
+    pub type MessageId = [u8; 32];
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        type XcmExecutor: ExecuteXcm<Self::Call>;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(_);
+
+    #[pallet::storage]
+    #[pallet::getter(fn parachain_id)]
+    #[allow(clippy::disallowed_types)]
+    pub(super) type ParachainId<T: Config> = StorageValue<_, ParaId, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Some XCMP message was executed successfully.
+        Success(Option<T::Hash>),
+        /// Some XCMP message failed.
+        Fail(Option<T::Hash>, XcmError),
+        /// Some XCMP message used an unsupported version of XCM.
+        BadVersion(Option<T::Hash>),
+        /// Some downward message is not a valid XCM.
+        InvalidFormat(MessageId),
+        /// Some downward message used an unsupported version of XCM.
+        UnsupportedVersion(MessageId),
+        /// Some downward message was executed with the given outcome.
+        ExecutedDownward(MessageId, Outcome),
+    }
+
+    impl<T: Config> Get<ParaId> for Pallet<T> {
+        fn get() -> ParaId {
+            Self::parachain_id()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        pub fn set_para_id(para_id: ParaId) {
+            ParachainId::<T>::put(para_id);
+        }
+
+        fn handle_xcmp_message(
+            sender: ParaId,
+            xcm: VersionedXcm<T::Call>,
+            max_weight: Weight,
+        ) -> Result<Weight, XcmError> {
+            let hash = Encode::using_encoded(&xcm, T::Hashing::hash);
+            let (result, event) = match Xcm::<T::Call>::try_from(xcm) {
+                Ok(xcm) => {
+                    let location = (Parent, Parachain(sender.into()));
+                    match T::XcmExecutor::execute_xcm(location, xcm, max_weight) {
+                        Outcome::Error(error) => {
+                            (Err(error.clone()), Event::Fail(Some(hash), error))
+                        }
+                        Outcome::Complete(weight) => (Ok(weight), Event::Success(Some(hash))),
+                        Outcome::Incomplete(weight, error) => {
+                            (Ok(weight), Event::Fail(Some(hash), error))
+                        }
+                    }
+                }
+                Err(()) => (
+                    Err(XcmError::UnhandledXcmVersion),
+                    Event::BadVersion(Some(hash)),
+                ),
+            };
+            Self::deposit_event(event);
+            result
+        }
+    }
+
+    impl<T: Config> XcmpMessageHandler for Pallet<T> {
+        fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+            iter: I,
+            max_weight: Weight,
+        ) -> Weight {
+            for (sender, _sent_at, mut data) in iter {
+                let _format = XcmpMessageFormat::decode(&mut data)
+                    .expect("the simulator encodes with the versioned XCM format");
+
+                while !data.is_empty() {
+                    match VersionedXcm::<T::Call>::decode(&mut data) {
+                        Ok(xcm) => {
+                            let _ = Self::handle_xcmp_message(sender, xcm, max_weight);
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+            max_weight
+        }
+    }
+
+    impl<T: Config> DmpMessageHandler for Pallet<T> {
+        fn handle_dmp_messages(
+            iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+            limit: Weight,
+        ) -> Weight {
+            for (_sent_at, data) in iter {
+                let id = sp_io::hashing::blake2_256(&data[..]);
+                let event = match VersionedXcm::<T::Call>::decode(&mut &data[..])
+                    .map(Xcm::<T::Call>::try_from)
+                {
+                    Err(_) => Event::InvalidFormat(id),
+                    Ok(Err(())) => Event::UnsupportedVersion(id),
+                    Ok(Ok(xcm)) => {
+                        let outcome = T::XcmExecutor::execute_xcm(Parent, xcm, limit);
+                        Event::ExecutedDownward(id, outcome)
+                    }
+                };
+                Self::deposit_event(event);
+            }
+            limit
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Relay chain of the test network. The parachains talk to each other directly, so it only has to
+//! be able to execute the (unused) upward messages.
+
+use frame_support::{
+    parameter_types,
+    traits::{ConstU32, Everything},
+    weights::Weight,
+};
+use polkadot_parachain::primitives::Id as ParaId;
+use polkadot_runtime_parachains::{configuration, shared};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32};
+use xcm::latest::prelude::*;
+use xcm_builder::{
+    AccountId32Aliases, AllowUnpaidExecutionFrom, ChildParachainConvertsVia,
+    CurrencyAdapter as XcmCurrencyAdapter, FixedRateOfFungible, FixedWeightBounds, IsConcrete,
+    LocationInverter, SovereignSignedViaLocation,
+};
+
+use super::RelayChainXcmRouter;
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+
+// -------------------------------------------------------------------------------------------------
+//                                              Config
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type AccountId = AccountId;
+    type BaseCallFilter = Everything;
+    type BlockHashCount = BlockHashCount;
+    type BlockLength = ();
+    type BlockNumber = u64;
+    type BlockWeights = ();
+    type Call = Call;
+    type DbWeight = ();
+    type Event = Event;
+    type Hash = H256;
+    type Hashing = ::sp_runtime::traits::BlakeTwo256;
+    type Header = Header;
+    type Index = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type MaxConsumers = ConstU32<16>;
+    type OnKilledAccount = ();
+    type OnNewAccount = ();
+    type OnSetCode = ();
+    type Origin = Origin;
+    type PalletInfo = PalletInfo;
+    type SS58Prefix = ();
+    type SystemWeightInfo = ();
+    type Version = ();
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                             Balances
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Runtime {
+    type AccountStore = System;
+    type Balance = Balance;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type WeightInfo = ();
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                            Parachains
+// -------------------------------------------------------------------------------------------------
+
+impl shared::Config for Runtime {}
+
+impl configuration::Config for Runtime {
+    type WeightInfo = configuration::TestWeightInfo;
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                                XCM
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const RelayLocation: MultiLocation = Here.into();
+    pub const RelayNetwork: NetworkId = NetworkId::Any;
+    pub Ancestry: MultiLocation = Here.into();
+    pub const UnitWeightCost: Weight = 10;
+    pub const MaxInstructions: u32 = 100;
+    pub RelayPerSecond: (AssetId, u128) = (Concrete(RelayLocation::get()), 1);
+}
+
+pub type LocationToAccountId = (
+    ChildParachainConvertsVia<ParaId, AccountId>,
+    AccountId32Aliases<RelayNetwork, AccountId>,
+);
+
+pub type LocalAssetTransactor =
+    XcmCurrencyAdapter<Balances, IsConcrete<RelayLocation>, LocationToAccountId, AccountId, ()>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type AssetClaims = ();
+    type AssetTransactor = LocalAssetTransactor;
+    type AssetTrap = ();
+    type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    type Call = Call;
+    type IsReserve = ();
+    type IsTeleporter = ();
+    type LocationInverter = LocationInverter<Ancestry>;
+    type OriginConverter = SovereignSignedViaLocation<LocationToAccountId, Origin>;
+    type ResponseHandler = ();
+    type SubscriptionService = ();
+    type Trader = FixedRateOfFungible<RelayPerSecond, ()>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type XcmSender = RelayChainXcmRouter;
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                         Construct Runtime
+// -------------------------------------------------------------------------------------------------
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        ParasShared: shared::{Pallet, Call, Storage},
+        Configuration: configuration::{Pallet, Call, Storage, Config<T>},
+    }
+);
@@ -0,0 +1,166 @@
+//! Sibling parachain holding its users' assets in `orml_tokens`, the reserve of which is the
+//! Instrumental parachain.
+
+use frame_support::{
+    parameter_types,
+    traits::{ConstU32, Everything},
+    weights::Weight,
+};
+use orml_traits::{location::AbsoluteReserveProvider, parameter_type_with_key};
+use orml_xcm_support::{IsNativeConcrete, MultiCurrencyAdapter, MultiNativeAsset};
+use polkadot_parachain::primitives::Sibling;
+use primitives::currency::CurrencyId;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32};
+use xcm::latest::prelude::*;
+use xcm_builder::{
+    AccountId32Aliases, AllowUnpaidExecutionFrom, FixedRateOfFungible, FixedWeightBounds,
+    LocationInverter, ParentIsPreset, SiblingParachainConvertsVia, SignedAccountId32AsNative,
+    SovereignSignedViaLocation,
+};
+use xcm_executor::XcmExecutor;
+
+use super::{currency_location, msg_queue, CurrencyIdConvert, ParachainXcmRouter, SIBLING_PARA_ID};
+
+pub type AccountId = AccountId32;
+pub type Amount = i128;
+pub type Balance = u128;
+
+// -------------------------------------------------------------------------------------------------
+//                                              Config
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+    type AccountData = ();
+    type AccountId = AccountId;
+    type BaseCallFilter = Everything;
+    type BlockHashCount = BlockHashCount;
+    type BlockLength = ();
+    type BlockNumber = u64;
+    type BlockWeights = ();
+    type Call = Call;
+    type DbWeight = ();
+    type Event = Event;
+    type Hash = H256;
+    type Hashing = ::sp_runtime::traits::BlakeTwo256;
+    type Header = Header;
+    type Index = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type MaxConsumers = ConstU32<16>;
+    type OnKilledAccount = ();
+    type OnNewAccount = ();
+    type OnSetCode = ();
+    type Origin = Origin;
+    type PalletInfo = PalletInfo;
+    type SS58Prefix = ();
+    type SystemWeightInfo = ();
+    type Version = ();
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                              Tokens
+// -------------------------------------------------------------------------------------------------
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+        0_u128
+    };
+}
+
+impl orml_tokens::Config for Runtime {
+    type Amount = Amount;
+    type Balance = Balance;
+    type CurrencyId = CurrencyId;
+    type DustRemovalWhitelist = Everything;
+    type Event = Event;
+    type ExistentialDeposits = ExistentialDeposits;
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type OnDust = ();
+    type OnKilledTokenAccount = ();
+    type OnNewTokenAccount = ();
+    type ReserveIdentifier = [u8; 8];
+    type WeightInfo = ();
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                                XCM
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const RelayNetwork: NetworkId = NetworkId::Any;
+    pub Ancestry: MultiLocation = Parachain(SIBLING_PARA_ID).into();
+    pub const UnitWeightCost: Weight = 10;
+    pub const MaxInstructions: u32 = 100;
+    pub UsdcPerSecond: (AssetId, u128) = (Concrete(currency_location(CurrencyId::USDC)), 1);
+}
+
+pub type LocationToAccountId = (
+    ParentIsPreset<AccountId>,
+    SiblingParachainConvertsVia<Sibling, AccountId>,
+    AccountId32Aliases<RelayNetwork, AccountId>,
+);
+
+pub type LocalAssetTransactor = MultiCurrencyAdapter<
+    Tokens,
+    (),
+    IsNativeConcrete<CurrencyId, CurrencyIdConvert>,
+    AccountId,
+    LocationToAccountId,
+    CurrencyId,
+    CurrencyIdConvert,
+    (),
+>;
+
+pub type XcmOriginToCallOrigin = (
+    SovereignSignedViaLocation<LocationToAccountId, Origin>,
+    SignedAccountId32AsNative<RelayNetwork, Origin>,
+);
+
+pub type XcmRouter = ParachainXcmRouter<MsgQueue>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type AssetClaims = ();
+    type AssetTransactor = LocalAssetTransactor;
+    type AssetTrap = ();
+    type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    type Call = Call;
+    type IsReserve = MultiNativeAsset<AbsoluteReserveProvider>;
+    type IsTeleporter = ();
+    type LocationInverter = LocationInverter<Ancestry>;
+    type OriginConverter = XcmOriginToCallOrigin;
+    type ResponseHandler = ();
+    type SubscriptionService = ();
+    type Trader = FixedRateOfFungible<UsdcPerSecond, ()>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type XcmSender = XcmRouter;
+}
+
+impl msg_queue::Config for Runtime {
+    type Event = Event;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                         Construct Runtime
+// -------------------------------------------------------------------------------------------------
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+        MsgQueue: msg_queue::{Pallet, Storage, Event<T>},
+    }
+);
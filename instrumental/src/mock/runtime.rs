@@ -1,21 +1,39 @@
+use codec::Encode;
 use frame_support::{
     ord_parameter_types, parameter_types,
-    traits::{Everything, GenesisBuild},
+    traits::{Contains, Everything, GenesisBuild, Nothing},
+    weights::Weight,
     PalletId,
 };
-use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
-use orml_traits::parameter_type_with_key;
+use frame_system::{offchain::SendTransactionTypes, EnsureRoot, EnsureSigned, EnsureSignedBy};
+use orml_traits::{location::AbsoluteReserveProvider, parameter_type_with_key};
+use orml_xcm_support::{IsNativeConcrete, MultiCurrencyAdapter, MultiNativeAsset};
 use pallet_collective::EnsureProportionAtLeast;
 use pallet_instrumental::mock::account_id::{AccountId, ADMIN};
 use primitives::currency::{CurrencyId, ValidateCurrencyId};
 use sp_core::H256;
+use sp_io::hashing::blake2_256;
 use sp_runtime::{
-    testing::Header,
-    traits::{ConvertInto, IdentityLookup},
-    Permill,
+    testing::{Header, TestXt},
+    traits::{Convert, ConvertInto, IdentityLookup},
+    transaction_validity::TransactionPriority,
+    FixedU128, Percent, Permill, Perquintill,
 };
+use sp_std::borrow::Borrow;
+use traits::strategy::InstrumentalProtocolStrategy;
+use xcm::latest::prelude::*;
+use xcm_builder::{
+    AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds,
+    LocationInverter, SovereignSignedViaLocation,
+};
+use xcm_executor::XcmExecutor;
 
-use super::fnft;
+use super::{
+    fnft,
+    network::{
+        currency_location, msg_queue, CurrencyIdConvert, ParachainXcmRouter, INSTRUMENTAL_PARA_ID,
+    },
+};
 use crate as pallet_instrumental;
 
 pub type Amount = i128;
@@ -35,6 +53,12 @@ pub const HOURS: BlockNumber = MINUTES * 60;
 pub const DAYS: BlockNumber = HOURS * 24;
 
 pub const MAX_ASSOCIATED_VAULTS: u32 = 10;
+// Enough hourly snapshots to cover the longest realized APY window.
+pub const MAX_SNAPSHOTS: u32 = 31 * 24;
+pub const MAX_SCHEDULES_PER_BLOCK: u32 = 10;
+pub const MAX_SCHEDULES_PER_ACCOUNT: u32 = 3;
+// Room for two scheduled deposits per block.
+pub const MAX_SCHEDULED_DEPOSITS_WEIGHT: Weight = 20_000;
 pub const NATIVE_ASSET: CurrencyId = CurrencyId::PICA;
 
 // -------------------------------------------------------------------------------------------------
@@ -318,6 +342,11 @@ impl pallet_collective::Config<InstrumentalPabloCollective> for MockRuntime {
 parameter_types! {
     pub const MaxAssociatedVaults: u32 = MAX_ASSOCIATED_VAULTS;
     pub const InstrumentalPabloStrategyPalletId: PalletId = PalletId(*b"strmxpab");
+    pub const PabloRiskScore: Percent = Percent::from_percent(50);
+    pub const PabloRebalanceInterval: BlockNumber = 10;
+    pub const PabloRebalanceThreshold: Balance = 1_000;
+    pub const PabloKeeperBountyShare: Percent = Percent::from_percent(10);
+    pub const PabloMaxKeeperBounty: Balance = 1_000;
 }
 
 impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
@@ -327,10 +356,15 @@ impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
     type Currency = Tokens;
     type Event = Event;
     type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type KeeperBountyShare = PabloKeeperBountyShare;
     type MaxAssociatedVaults = MaxAssociatedVaults;
+    type MaxKeeperBounty = PabloMaxKeeperBounty;
     type Pablo = Pablo;
     type PalletId = InstrumentalPabloStrategyPalletId;
     type PoolId = PoolId;
+    type RebalanceInterval = PabloRebalanceInterval;
+    type RebalanceThreshold = PabloRebalanceThreshold;
+    type RiskScore = PabloRiskScore;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
@@ -342,38 +376,243 @@ impl pallet_instrumental_strategy_pablo::Config for MockRuntime {
 
 parameter_types! {
     pub const InstrumentalStrategyPalletId: PalletId = PalletId(*b"dynamic_");
+    pub const MaxWhitelistedStrategies: u32 = 8;
+    pub const MaxReallocationPerBlock: Perquintill = Perquintill::from_percent(25);
+    // 1% of yearly yield
+    pub const MinApyImprovement: FixedU128 = FixedU128::from_inner(10_000_000_000_000_000);
+    pub const ReallocationDelay: BlockNumber = 10;
+    pub const ApySamplingPeriod: BlockNumber = 10;
+    pub const ApySmoothingFactor: Perquintill = Perquintill::from_percent(20);
+    pub const MaxApySamples: u32 = 24;
+    pub const InstrumentalStrategyUnsignedPriority: TransactionPriority = TransactionPriority::MAX;
+    pub const MaxRebalanceBackoff: u32 = 4;
 }
 
 impl pallet_instrumental_strategy::Config for MockRuntime {
+    type ApySamplingPeriod = ApySamplingPeriod;
+    type ApySmoothingFactor = ApySmoothingFactor;
     type AssetId = CurrencyId;
     type Balance = Balance;
+    type Currency = Tokens;
     type Event = Event;
+    type ExternalOrigin = EnsureProportionAtLeast<AccountId, InstrumentalPabloCollective, 2, 3>;
+    type MaxApySamples = MaxApySamples;
     type MaxAssociatedVaults = MaxAssociatedVaults;
-    type PabloStrategy = PabloStrategy;
+    type MaxReallocationPerBlock = MaxReallocationPerBlock;
+    type MaxRebalanceBackoff = MaxRebalanceBackoff;
+    type MaxWhitelistedStrategies = MaxWhitelistedStrategies;
+    type MinApyImprovement = MinApyImprovement;
     type PalletId = InstrumentalStrategyPalletId;
     type PoolId = PoolId;
+    type ReallocationDelay = ReallocationDelay;
+    type Scoring = pallet_instrumental_strategy::scoring::RawApy;
+    type Strategies = (PabloStrategy,);
+    type UnsignedPriority = InstrumentalStrategyUnsignedPriority;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
 }
 
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for MockRuntime
+where
+    Call: From<LocalCall>,
+{
+    type Extrinsic = Extrinsic;
+    type OverarchingCall = Call;
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                                XCM
+// -------------------------------------------------------------------------------------------------
+
+parameter_types! {
+    pub const RelayNetwork: NetworkId = NetworkId::Any;
+    pub Ancestry: MultiLocation = Parachain(INSTRUMENTAL_PARA_ID).into();
+    pub SelfLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(INSTRUMENTAL_PARA_ID)));
+    pub const UnitWeightCost: Weight = 10;
+    pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsForTransfer: usize = 2;
+    pub UsdcPerSecond: (AssetId, u128) = (Concrete(currency_location(CurrencyId::USDC)), 1);
+}
+
+/// Local `AccountId32` junctions are converted to the same account, any other location to an
+/// account derived from its hash.
+pub struct LocationToAccountId;
+
+impl LocationToAccountId {
+    fn account_of(location: &MultiLocation) -> AccountId {
+        match location {
+            MultiLocation {
+                parents: 0,
+                interior: X1(AccountId32 { id, .. }),
+            } => AccountId::from_raw(*id),
+            _ => AccountId::from_raw(("multiloc", location).using_encoded(blake2_256)),
+        }
+    }
+}
+
+impl Convert<MultiLocation, AccountId> for LocationToAccountId {
+    fn convert(location: MultiLocation) -> AccountId {
+        Self::account_of(&location)
+    }
+}
+
+impl xcm_executor::traits::Convert<MultiLocation, AccountId> for LocationToAccountId {
+    fn convert_ref(location: impl Borrow<MultiLocation>) -> Result<AccountId, ()> {
+        Ok(Self::account_of(location.borrow()))
+    }
+}
+
+pub struct AccountIdToMultiLocation;
+
+impl Convert<AccountId, MultiLocation> for AccountIdToMultiLocation {
+    fn convert(account: AccountId) -> MultiLocation {
+        X1(AccountId32 {
+            network: NetworkId::Any,
+            id: account.0,
+        })
+        .into()
+    }
+}
+
+/// Accounts of sibling parachains.
+pub struct SiblingAccounts;
+
+impl Contains<MultiLocation> for SiblingAccounts {
+    fn contains(location: &MultiLocation) -> bool {
+        matches!(
+            location,
+            MultiLocation {
+                parents: 1,
+                interior: X2(Parachain(_), AccountId32 { .. }),
+            }
+        )
+    }
+}
+
+pub type LocalAssetTransactor = MultiCurrencyAdapter<
+    Tokens,
+    (),
+    IsNativeConcrete<CurrencyId, CurrencyIdConvert>,
+    AccountId,
+    LocationToAccountId,
+    CurrencyId,
+    CurrencyIdConvert,
+    (),
+>;
+
+pub type XcmOriginToCallOrigin = (
+    SovereignSignedViaLocation<LocationToAccountId, Origin>,
+    pallet_xcm::XcmPassthrough<Origin>,
+);
+
+pub type XcmRouter = ParachainXcmRouter<MsgQueue>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type AssetClaims = ();
+    type AssetTransactor = LocalAssetTransactor;
+    type AssetTrap = ();
+    type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    type Call = Call;
+    type IsReserve = MultiNativeAsset<AbsoluteReserveProvider>;
+    type IsTeleporter = ();
+    type LocationInverter = LocationInverter<Ancestry>;
+    type OriginConverter = XcmOriginToCallOrigin;
+    type ResponseHandler = ();
+    type SubscriptionService = ();
+    type Trader = FixedRateOfFungible<UsdcPerSecond, ()>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type XcmSender = XcmRouter;
+}
+
+impl msg_queue::Config for MockRuntime {
+    type Event = Event;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+}
+
+impl pallet_xcm::Config for MockRuntime {
+    type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Call = Call;
+    type Event = Event;
+    type ExecuteXcmOrigin = EnsureXcmOrigin<Origin, ()>;
+    type LocationInverter = LocationInverter<Ancestry>;
+    type Origin = Origin;
+    type SendXcmOrigin = EnsureXcmOrigin<Origin, ()>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type XcmExecuteFilter = Nothing;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+    type XcmReserveTransferFilter = Everything;
+    type XcmRouter = XcmRouter;
+    type XcmTeleportFilter = Nothing;
+
+    const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+}
+
+parameter_type_with_key! {
+    pub ParachainMinFee: |_location: MultiLocation| -> Option<u128> {
+        None
+    };
+}
+
+impl orml_xtokens::Config for MockRuntime {
+    type AccountIdToMultiLocation = AccountIdToMultiLocation;
+    type Balance = Balance;
+    type BaseXcmWeight = UnitWeightCost;
+    type CurrencyId = CurrencyId;
+    type CurrencyIdConvert = CurrencyIdConvert;
+    type Event = Event;
+    type LocationInverter = LocationInverter<Ancestry>;
+    type MaxAssetsForTransfer = MaxAssetsForTransfer;
+    type MinXcmFee = ParachainMinFee;
+    type MultiLocationsFilter = Everything;
+    type ReserveProvider = AbsoluteReserveProvider;
+    type SelfLocation = SelfLocation;
+    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+}
+
 // -------------------------------------------------------------------------------------------------
 //                                           Instrumental
 // -------------------------------------------------------------------------------------------------
 
 parameter_types! {
     pub const InstrumentalPalletId: PalletId = PalletId(*b"strm____");
+    pub const SnapshotInterval: BlockNumber = HOURS;
+    pub const MaxSnapshots: u32 = MAX_SNAPSHOTS;
+    pub const BlocksPerDay: BlockNumber = DAYS;
+    pub const ShareUnit: Balance = 1_000_000_000_000;
+    pub const XcmDestWeight: Weight = 1_000_000;
+    pub const MaxSchedulesPerBlock: u32 = MAX_SCHEDULES_PER_BLOCK;
+    pub const MaxSchedulesPerAccount: u32 = MAX_SCHEDULES_PER_ACCOUNT;
+    pub const MaxScheduledDepositsWeight: Weight = MAX_SCHEDULED_DEPOSITS_WEIGHT;
 }
 
 impl pallet_instrumental::Config for MockRuntime {
     type AssetId = CurrencyId;
     type Balance = Balance;
+    type BlocksPerDay = BlocksPerDay;
+    type Currency = Assets;
     type Event = Event;
+    type FinancialNft = fnft::MockFnft;
+    type FinancialNftInstanceId = u64;
     type InstrumentalStrategy = InstrumentalStrategy;
+    type LocationToAccountId = LocationToAccountId;
+    type MaxScheduledDepositsWeight = MaxScheduledDepositsWeight;
+    type MaxSchedulesPerAccount = MaxSchedulesPerAccount;
+    type MaxSchedulesPerBlock = MaxSchedulesPerBlock;
+    type MaxSnapshots = MaxSnapshots;
     type PalletId = InstrumentalPalletId;
+    type ShareUnit = ShareUnit;
+    type SnapshotInterval = SnapshotInterval;
     type Vault = Vault;
     type VaultId = VaultId;
     type WeightInfo = ();
+    type XcmDestWeight = XcmDestWeight;
+    type XcmOrigin = pallet_xcm::EnsureXcm<SiblingAccounts>;
+    type XcmTransfer = XTokens;
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -403,8 +642,13 @@ frame_support::construct_runtime!(
         StakingRewards: pallet_staking_rewards::{Pallet, Storage, Call, Event<T>},
         Pablo: pallet_pablo::{Pallet, Call, Storage, Event<T>},
 
+        MsgQueue: msg_queue::{Pallet, Storage, Event<T>},
+        PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin},
+        XTokens: orml_xtokens::{Pallet, Storage, Call, Event<T>},
+
         PabloStrategy: pallet_instrumental_strategy_pablo::{Pallet, Call, Storage, Event<T>},
-        InstrumentalStrategy: pallet_instrumental_strategy::{Pallet, Call, Storage, Event<T>},
+        InstrumentalStrategy:
+        pallet_instrumental_strategy::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
         Instrumental: pallet_instrumental::{Pallet, Call, Storage, Event<T>},
     }
 );
@@ -421,6 +665,12 @@ impl ExtBuilder {
             .build_storage::<MockRuntime>()
             .unwrap();
 
+        GenesisBuild::<MockRuntime>::assimilate_storage(
+            &pallet_instrumental_strategy_pablo::GenesisConfig::default(),
+            &mut storage,
+        )
+        .unwrap();
+
         pallet_balances::GenesisConfig::<MockRuntime> {
             balances: self.native_balances,
         }
@@ -433,6 +683,16 @@ impl ExtBuilder {
         .assimilate_storage(&mut storage)
         .unwrap();
 
+        // Whitelist every registered strategy, as governance would do on a live chain
+        pallet_instrumental_strategy::GenesisConfig::<MockRuntime> {
+            whitelisted_strategies: vec![
+                <PabloStrategy as InstrumentalProtocolStrategy>::account_id(),
+            ],
+            ..Default::default()
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
         storage.into()
     }
 
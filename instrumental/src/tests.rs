@@ -92,7 +92,22 @@ prop_compose! {
 // -------------------------------------------------------------------------------------------------
 
 mod create {
+    use frame_support::{sp_std::collections::btree_map::BTreeMap, traits::Get};
+    use sp_runtime::Perquintill;
+    use traits::{
+        instrumental::{InstrumentalDynamicStrategy, StrategyAllocation},
+        strategy::InstrumentalProtocolStrategy,
+    };
+
     use super::*;
+    use crate::mock::{
+        account_id::ALICE,
+        runtime::{InstrumentalStrategy, PabloStrategy},
+    };
+
+    fn pablo_strategy() -> AccountId {
+        <PabloStrategy as InstrumentalProtocolStrategy>::account_id()
+    }
 
     #[test]
     fn create_extrinsic_emits_event() {
@@ -100,7 +115,7 @@ mod create {
             System::set_block_number(1);
 
             let config = InstrumentalVaultConfigBuilder::default().build();
-            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config.clone()));
 
             System::assert_last_event(Event::Instrumental(pallet::Event::Created {
                 vault_id: 1_u64,
@@ -113,7 +128,7 @@ mod create {
     fn create_extrinsic_enforces_you_cannot_create_more_than_one_vault_for_an_asset() {
         ExtBuilder::default().build().execute_with(|| {
             let config = InstrumentalVaultConfigBuilder::default().build();
-            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config.clone()));
 
             assert_noop!(
                 Instrumental::create(Origin::signed(ADMIN), config),
@@ -134,6 +149,107 @@ mod create {
         });
     }
 
+    #[test]
+    fn create_extrinsic_allocates_to_explicit_strategies() {
+        ExtBuilder::default().build().execute_with(|| {
+            let config = InstrumentalVaultConfigBuilder::default()
+                .percent_deployable(Perquintill::from_percent(50))
+                .strategies(StrategyAllocation::Explicit(BTreeMap::from([(
+                    pablo_strategy(),
+                    Perquintill::from_percent(50),
+                )])))
+                .build();
+            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+            let vault_id = Instrumental::asset_vault(CurrencyId::USDC).unwrap();
+            assert_eq!(
+                pallet_vault::Allocations::<MockRuntime>::get(vault_id, pablo_strategy()),
+                Perquintill::from_percent(50)
+            );
+        });
+    }
+
+    #[test]
+    fn create_extrinsic_rejects_strategies_that_are_not_eligible() {
+        ExtBuilder::default().build().execute_with(|| {
+            let config = InstrumentalVaultConfigBuilder::default()
+                .percent_deployable(Perquintill::from_percent(50))
+                .strategies(StrategyAllocation::Explicit(BTreeMap::from([
+                    (pablo_strategy(), Perquintill::from_percent(30)),
+                    (ALICE, Perquintill::from_percent(20)),
+                ])))
+                .build();
+
+            assert_noop!(
+                Instrumental::create(Origin::signed(ADMIN), config),
+                Error::<MockRuntime>::StrategyNotEligible
+            );
+        });
+    }
+
+    #[test]
+    fn create_extrinsic_rejects_more_strategies_than_the_dynamic_strategy_allows() {
+        ExtBuilder::default().build().execute_with(|| {
+            let max_strategies =
+                <InstrumentalStrategy as InstrumentalDynamicStrategy>::MaxStrategies::get() as u8;
+            let strategies = (0..=max_strategies)
+                .map(|seed| (AccountId::from_raw([seed; 32]), Perquintill::zero()))
+                .collect::<BTreeMap<_, _>>();
+            let config = InstrumentalVaultConfigBuilder::default()
+                .percent_deployable(Perquintill::zero())
+                .strategies(StrategyAllocation::Explicit(strategies))
+                .build();
+
+            assert_noop!(
+                Instrumental::create(Origin::signed(ADMIN), config),
+                Error::<MockRuntime>::TooManyStrategies
+            );
+        });
+    }
+
+    #[test]
+    fn create_extrinsic_uses_diversified_allocation_of_dynamic_strategy() {
+        ExtBuilder::default().build().execute_with(|| {
+            let percent_deployable = Perquintill::from_percent(50);
+            let config = InstrumentalVaultConfigBuilder::default()
+                .percent_deployable(percent_deployable)
+                .strategies(StrategyAllocation::Diversified)
+                .build();
+            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+            let vault_id = Instrumental::asset_vault(CurrencyId::USDC).unwrap();
+            let allocation = InstrumentalStrategy::get_diversified_allocation_for(
+                CurrencyId::USDC,
+                percent_deployable,
+            )
+            .unwrap();
+            allocation.into_iter().for_each(|(strategy, weight)| {
+                assert_eq!(
+                    pallet_vault::Allocations::<MockRuntime>::get(vault_id, strategy),
+                    weight
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn create_extrinsic_rejects_strategy_weights_not_summing_to_percent_deployable() {
+        ExtBuilder::default().build().execute_with(|| {
+            let config = InstrumentalVaultConfigBuilder::default()
+                .percent_deployable(Perquintill::from_percent(50))
+                .strategies(StrategyAllocation::Explicit(BTreeMap::from([(
+                    pablo_strategy(),
+                    Perquintill::from_percent(30),
+                )])))
+                .build();
+
+            assert_noop!(
+                Instrumental::create(Origin::signed(ADMIN), config),
+                Error::<MockRuntime>::InvalidStrategyAllocation
+            );
+        });
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(100))]
 
@@ -426,6 +542,822 @@ mod remove_liquidity {
     }
 }
 
+// -------------------------------------------------------------------------------------------------
+//                                       Share Price Snapshots
+// -------------------------------------------------------------------------------------------------
+
+mod share_price_snapshots {
+    use frame_support::traits::{fungibles::Mutate, Hooks};
+    use sp_runtime::{traits::Zero, FixedI128, FixedPointNumber};
+
+    use super::*;
+    use crate::{
+        mock::{
+            account_id::ALICE,
+            runtime::{BlockNumber, VaultId, DAYS, HOURS, MAX_SNAPSHOTS},
+        },
+        pallet::{ApyWindow, RealizedApy},
+    };
+
+    fn create_vault_with_deposit(asset: CurrencyId, amount: Balance) -> VaultId {
+        let config = InstrumentalVaultConfigBuilder::default()
+            .asset_id(asset)
+            .build();
+        assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+        let vault_id = Instrumental::asset_vault(asset).unwrap();
+        // Deposit directly into the vault, so that shares are issued
+        assert_ok!(Vault::deposit(Origin::signed(ALICE), vault_id, amount));
+
+        vault_id
+    }
+
+    fn run_to_block(block_number: BlockNumber) {
+        System::set_block_number(block_number);
+        Instrumental::on_initialize(block_number);
+    }
+
+    #[test]
+    fn snapshots_are_taken_every_interval() {
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, amount)
+            .build()
+            .execute_with(|| {
+                let vault_id = create_vault_with_deposit(CurrencyId::USDC, amount);
+
+                run_to_block(HOURS - 1);
+                assert!(Instrumental::share_price_snapshots(vault_id).is_none());
+
+                run_to_block(HOURS);
+                let snapshots = Instrumental::share_price_snapshots(vault_id).unwrap();
+                assert_eq!(snapshots.len(), 1);
+                assert_eq!(snapshots[0].block_number, HOURS);
+                assert_eq!(snapshots[0].share_price, CurrencyId::unit::<Balance>());
+            });
+    }
+
+    #[test]
+    fn full_ring_buffer_overwrites_the_oldest_snapshot() {
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, amount)
+            .build()
+            .execute_with(|| {
+                let vault_id = create_vault_with_deposit(CurrencyId::USDC, amount);
+                let last_block = (MAX_SNAPSHOTS as BlockNumber + 1) * HOURS;
+
+                (1..=MAX_SNAPSHOTS as BlockNumber + 1).for_each(|i| run_to_block(i * HOURS));
+
+                let snapshots = Instrumental::share_price_snapshots(vault_id).unwrap();
+                assert_eq!(snapshots.len(), MAX_SNAPSHOTS as usize);
+                assert_eq!(snapshots[0].block_number, last_block);
+                assert!(snapshots
+                    .iter()
+                    .all(|snapshot| snapshot.block_number != HOURS));
+            });
+    }
+
+    #[test]
+    fn realized_apy_reflects_share_price_growth() {
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, amount)
+            .build()
+            .execute_with(|| {
+                let vault_id = create_vault_with_deposit(CurrencyId::USDC, amount);
+                run_to_block(HOURS);
+
+                // The vault earns 1% in a day
+                let vault_account = Vault::account_id(&vault_id);
+                assert_ok!(<Assets as Mutate<AccountId>>::mint_into(
+                    CurrencyId::USDC,
+                    &vault_account,
+                    amount / 100
+                ));
+                run_to_block(HOURS + DAYS);
+
+                assert_eq!(
+                    Instrumental::realized_apy(&CurrencyId::USDC, ApyWindow::OneDay),
+                    Ok(FixedI128::saturating_from_rational(365, 100))
+                );
+            });
+    }
+
+    #[test]
+    fn realized_apy_requires_snapshots_covering_the_window() {
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, amount)
+            .build()
+            .execute_with(|| {
+                create_vault_with_deposit(CurrencyId::USDC, amount);
+                run_to_block(HOURS);
+                run_to_block(HOURS + DAYS);
+
+                assert_eq!(
+                    Instrumental::realized_apy(&CurrencyId::USDC, ApyWindow::SevenDays),
+                    Err(Error::<MockRuntime>::NotEnoughSnapshots.into())
+                );
+                assert_eq!(
+                    Instrumental::realized_apys(&CurrencyId::USDC),
+                    Ok(RealizedApy {
+                        one_day: Some(FixedI128::zero()),
+                        seven_days: None,
+                        thirty_days: None,
+                    })
+                );
+            });
+    }
+
+    #[test]
+    fn realized_apy_asset_must_have_an_associated_vault() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(
+                Instrumental::realized_apy(&CurrencyId::USDC, ApyWindow::OneDay),
+                Err(Error::<MockRuntime>::AssetDoesNotHaveAnAssociatedVault.into())
+            );
+        });
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                             Positions
+// -------------------------------------------------------------------------------------------------
+
+mod positions {
+    use composable_traits::fnft::FinancialNft;
+    use frame_support::traits::tokens::nonfungibles::Inspect as NftInspect;
+    use traits::instrumental::Instrumental as InstrumentalTrait;
+
+    use super::*;
+    use crate::mock::{
+        account_id::{ALICE, BOB},
+        fnft::MockFnft,
+    };
+
+    fn mint_position(owner: AccountId, asset: CurrencyId, amount: Balance) -> u64 {
+        let config = InstrumentalVaultConfigBuilder::default()
+            .asset_id(asset)
+            .build();
+        assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+        let position = MockFnft::get_next_nft_id(&asset).unwrap();
+        assert_ok!(Instrumental::mint_position(
+            Origin::signed(owner),
+            asset,
+            amount
+        ));
+
+        position
+    }
+
+    #[test]
+    fn mint_position_locks_vault_shares_in_the_fnft() {
+        let asset = CurrencyId::USDC;
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, asset, amount)
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+
+                let position = mint_position(ALICE, asset, amount);
+
+                let vault_id = Instrumental::asset_vault(asset).unwrap();
+                let lp_token = <Vault as VaultTrait>::lp_asset_id(&vault_id).unwrap();
+                let shares = Assets::balance(lp_token, &MockFnft::asset_account(&asset, &position));
+
+                assert!(shares > 0);
+                assert_eq!(Assets::balance(lp_token, &ALICE), 0);
+                assert_eq!(Assets::balance(asset, &ALICE), 0);
+                assert_eq!(MockFnft::owner(&asset, &position), Some(ALICE));
+
+                System::assert_last_event(Event::Instrumental(pallet::Event::PositionMinted {
+                    asset,
+                    position,
+                    owner: ALICE,
+                    amount,
+                    shares,
+                }));
+            });
+    }
+
+    #[test]
+    fn mint_position_creates_the_missing_collection_of_older_vaults() {
+        let asset = CurrencyId::USDC;
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, asset, amount)
+            .build()
+            .execute_with(|| {
+                let config = InstrumentalVaultConfigBuilder::default()
+                    .asset_id(asset)
+                    .build();
+                assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+                // Vaults created before positions existed have no collection
+                MockFnft::remove_collection(&asset);
+
+                let position = MockFnft::get_next_nft_id(&asset).unwrap();
+                assert_ok!(Instrumental::mint_position(
+                    Origin::signed(ALICE),
+                    asset,
+                    amount
+                ));
+
+                assert_eq!(
+                    MockFnft::collection_owner(&asset),
+                    Some(<Instrumental as InstrumentalTrait>::account_id())
+                );
+                assert_eq!(MockFnft::owner(&asset, &position), Some(ALICE));
+            });
+    }
+
+    #[test]
+    fn mint_position_asset_must_have_an_associated_vault() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_noop!(
+                Instrumental::mint_position(
+                    Origin::signed(ALICE),
+                    CurrencyId::USDC,
+                    100 * CurrencyId::unit::<Balance>()
+                ),
+                Error::<MockRuntime>::AssetDoesNotHaveAnAssociatedVault
+            );
+        });
+    }
+
+    #[test]
+    fn transferred_position_is_redeemed_by_its_new_owner() {
+        let asset = CurrencyId::USDC;
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, asset, amount)
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+
+                let position = mint_position(ALICE, asset, amount);
+
+                assert_ok!(Instrumental::transfer_position(
+                    Origin::signed(ALICE),
+                    asset,
+                    position,
+                    BOB
+                ));
+                assert_eq!(MockFnft::owner(&asset, &position), Some(BOB));
+
+                assert_noop!(
+                    Instrumental::redeem_position(Origin::signed(ALICE), asset, position),
+                    Error::<MockRuntime>::NotPositionOwner
+                );
+
+                assert_ok!(Instrumental::redeem_position(
+                    Origin::signed(BOB),
+                    asset,
+                    position
+                ));
+
+                assert_eq!(Assets::balance(asset, &BOB), amount);
+                assert_eq!(MockFnft::owner(&asset, &position), None);
+
+                System::assert_last_event(Event::Instrumental(pallet::Event::PositionRedeemed {
+                    asset,
+                    position,
+                    owner: BOB,
+                    amount,
+                }));
+            });
+    }
+
+    #[test]
+    fn only_the_owner_can_transfer_a_position() {
+        let asset = CurrencyId::USDC;
+        let amount = 100 * CurrencyId::unit::<Balance>();
+
+        ExtBuilder::default()
+            .initialize_balance(ALICE, asset, amount)
+            .build()
+            .execute_with(|| {
+                let position = mint_position(ALICE, asset, amount);
+
+                assert_noop!(
+                    Instrumental::transfer_position(Origin::signed(BOB), asset, position, BOB),
+                    Error::<MockRuntime>::NotPositionOwner
+                );
+                assert_noop!(
+                    Instrumental::transfer_position(
+                        Origin::signed(ALICE),
+                        asset,
+                        position + 1,
+                        BOB
+                    ),
+                    Error::<MockRuntime>::PositionDoesNotExist
+                );
+            });
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                         Deposit Schedules
+// -------------------------------------------------------------------------------------------------
+
+mod deposit_schedules {
+    use frame_support::{
+        traits::{fungibles::Mutate, Hooks},
+        BoundedVec,
+    };
+
+    use super::*;
+    use crate::{
+        mock::{
+            account_id::{ALICE, BOB, CHARLIE},
+            runtime::{BlockNumber, MAX_SCHEDULES_PER_ACCOUNT, MAX_SCHEDULES_PER_BLOCK},
+        },
+        pallet::{DepositScheduleId, DepositScheduleQueue, PendingDepositSchedules},
+    };
+
+    const PERIOD: BlockNumber = 10;
+
+    fn unit() -> Balance {
+        CurrencyId::unit::<Balance>()
+    }
+
+    fn create_vault(asset: CurrencyId) -> u64 {
+        let config = InstrumentalVaultConfigBuilder::default()
+            .asset_id(asset)
+            .build();
+        assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+        Instrumental::asset_vault(asset).unwrap()
+    }
+
+    fn create_schedule(owner: AccountId, amount: Balance, total: Balance) -> DepositScheduleId {
+        assert_ok!(Instrumental::create_deposit_schedule(
+            Origin::signed(owner),
+            CurrencyId::USDC,
+            amount,
+            PERIOD,
+            total
+        ));
+
+        pallet::NextDepositScheduleId::<MockRuntime>::get().unwrap() - 1
+    }
+
+    fn run_to_block(block_number: BlockNumber) {
+        while System::block_number() < block_number {
+            let next_block = System::block_number() + 1;
+            System::set_block_number(next_block);
+            Instrumental::on_initialize(next_block);
+        }
+    }
+
+    fn vault_balance(vault_id: u64) -> Balance {
+        Assets::balance(
+            CurrencyId::USDC,
+            &<Vault as VaultTrait>::account_id(&vault_id),
+        )
+    }
+
+    #[test]
+    fn schedule_deposits_every_period_until_total() {
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, 1_000 * unit())
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+                let vault_id = create_vault(CurrencyId::USDC);
+                let schedule_id = create_schedule(ALICE, 100 * unit(), 250 * unit());
+
+                run_to_block(PERIOD);
+                assert_eq!(vault_balance(vault_id), 0);
+
+                run_to_block(1 + PERIOD);
+                assert_eq!(vault_balance(vault_id), 100 * unit());
+                assert_eq!(
+                    Instrumental::deposit_schedules(ALICE, schedule_id)
+                        .unwrap()
+                        .deposited,
+                    100 * unit()
+                );
+
+                run_to_block(1 + 2 * PERIOD);
+                assert_eq!(vault_balance(vault_id), 200 * unit());
+
+                // The last deposit only tops up to the total
+                run_to_block(1 + 3 * PERIOD);
+                assert_eq!(vault_balance(vault_id), 250 * unit());
+                assert_eq!(Assets::balance(CurrencyId::USDC, &ALICE), 750 * unit());
+                assert!(Instrumental::deposit_schedules(ALICE, schedule_id).is_none());
+
+                System::assert_last_event(Event::Instrumental(
+                    pallet::Event::DepositScheduleCompleted {
+                        owner: ALICE,
+                        schedule_id,
+                    },
+                ));
+
+                run_to_block(1 + 4 * PERIOD);
+                assert_eq!(vault_balance(vault_id), 250 * unit());
+            });
+    }
+
+    #[test]
+    fn schedule_is_skipped_and_flagged_when_balance_is_insufficient() {
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, 50 * unit())
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+                let vault_id = create_vault(CurrencyId::USDC);
+                let schedule_id = create_schedule(ALICE, 100 * unit(), 200 * unit());
+
+                run_to_block(1 + PERIOD);
+
+                assert_eq!(vault_balance(vault_id), 0);
+                let schedule = Instrumental::deposit_schedules(ALICE, schedule_id).unwrap();
+                assert!(schedule.insufficient_balance);
+                assert_eq!(schedule.deposited, 0);
+                assert_eq!(schedule.next_execution, 1 + 2 * PERIOD);
+
+                System::assert_has_event(Event::Instrumental(
+                    pallet::Event::ScheduledDepositSkipped {
+                        owner: ALICE,
+                        schedule_id,
+                    },
+                ));
+
+                // Once the balance is topped up, the schedule resumes and the flag is cleared
+                assert_ok!(<Assets as Mutate<AccountId>>::mint_into(
+                    CurrencyId::USDC,
+                    &ALICE,
+                    50 * unit()
+                ));
+                run_to_block(1 + 2 * PERIOD);
+
+                assert_eq!(vault_balance(vault_id), 100 * unit());
+                let schedule = Instrumental::deposit_schedules(ALICE, schedule_id).unwrap();
+                assert!(!schedule.insufficient_balance);
+            });
+    }
+
+    #[test]
+    fn cancelled_schedule_is_not_executed() {
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, 1_000 * unit())
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+                let vault_id = create_vault(CurrencyId::USDC);
+                let schedule_id = create_schedule(ALICE, 100 * unit(), 200 * unit());
+
+                assert_noop!(
+                    Instrumental::cancel_deposit_schedule(Origin::signed(BOB), schedule_id),
+                    Error::<MockRuntime>::DepositScheduleDoesNotExist
+                );
+
+                assert_ok!(Instrumental::cancel_deposit_schedule(
+                    Origin::signed(ALICE),
+                    schedule_id
+                ));
+                assert!(Instrumental::deposit_schedules(ALICE, schedule_id).is_none());
+                assert!(DepositScheduleQueue::<MockRuntime>::get(1 + PERIOD).is_none());
+
+                run_to_block(1 + PERIOD);
+                assert_eq!(vault_balance(vault_id), 0);
+            });
+    }
+
+    #[test]
+    fn schedules_exceeding_the_weight_budget_are_postponed() {
+        ExtBuilder::default()
+            .initialize_balances(vec![
+                (ALICE, CurrencyId::USDC, 1_000 * unit()),
+                (BOB, CurrencyId::USDC, 1_000 * unit()),
+                (CHARLIE, CurrencyId::USDC, 1_000 * unit()),
+            ])
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+                let vault_id = create_vault(CurrencyId::USDC);
+                create_schedule(ALICE, 100 * unit(), 100 * unit());
+                create_schedule(BOB, 100 * unit(), 100 * unit());
+                let postponed = create_schedule(CHARLIE, 100 * unit(), 100 * unit());
+
+                // The budget allows two scheduled deposits per block
+                run_to_block(1 + PERIOD);
+                assert_eq!(vault_balance(vault_id), 200 * unit());
+                assert_eq!(
+                    Instrumental::deposit_schedules(CHARLIE, postponed)
+                        .unwrap()
+                        .next_execution,
+                    2 + PERIOD
+                );
+
+                run_to_block(2 + PERIOD);
+                assert_eq!(vault_balance(vault_id), 300 * unit());
+            });
+    }
+
+    #[test]
+    fn schedules_are_kept_pending_while_the_queue_is_full() {
+        ExtBuilder::default()
+            .initialize_balance(ALICE, CurrencyId::USDC, 1_000 * unit())
+            .build()
+            .execute_with(|| {
+                System::set_block_number(1);
+                let vault_id = create_vault(CurrencyId::USDC);
+                let schedule_id = create_schedule(ALICE, 100 * unit(), 200 * unit());
+
+                // Every block the schedule may be queued at after its first deposit is full
+                let next_execution = 1 + 2 * PERIOD;
+                let probes = MAX_SCHEDULES_PER_BLOCK as BlockNumber;
+                for block_number in next_execution..next_execution + probes {
+                    let entries: Vec<_> = (0..MAX_SCHEDULES_PER_BLOCK as DepositScheduleId)
+                        .map(|id| (BOB, 1_000 + id))
+                        .collect();
+                    DepositScheduleQueue::<MockRuntime>::insert(
+                        block_number,
+                        BoundedVec::try_from(entries).unwrap(),
+                    );
+                }
+
+                run_to_block(1 + PERIOD);
+                assert_eq!(vault_balance(vault_id), 100 * unit());
+                assert!(Instrumental::deposit_schedules(ALICE, schedule_id).is_some());
+                assert_eq!(
+                    PendingDepositSchedules::<MockRuntime>::get(ALICE, schedule_id),
+                    Some(next_execution)
+                );
+                System::assert_has_event(Event::Instrumental(
+                    pallet::Event::DepositSchedulePending {
+                        owner: ALICE,
+                        schedule_id,
+                    },
+                ));
+
+                // The first full block is executed, leaving room at the end of the probed blocks
+                run_to_block(next_execution);
+                assert!(PendingDepositSchedules::<MockRuntime>::get(ALICE, schedule_id).is_none());
+                let queued_at = next_execution + probes;
+                assert_eq!(
+                    Instrumental::deposit_schedules(ALICE, schedule_id)
+                        .unwrap()
+                        .next_execution,
+                    queued_at
+                );
+
+                run_to_block(queued_at);
+                assert_eq!(vault_balance(vault_id), 200 * unit());
+            });
+    }
+
+    #[test]
+    fn create_deposit_schedule_is_bounded_per_account() {
+        ExtBuilder::default().build().execute_with(|| {
+            System::set_block_number(1);
+            create_vault(CurrencyId::USDC);
+            for _ in 0..MAX_SCHEDULES_PER_ACCOUNT {
+                create_schedule(ALICE, 100, 100);
+            }
+
+            assert_noop!(
+                Instrumental::create_deposit_schedule(
+                    Origin::signed(ALICE),
+                    CurrencyId::USDC,
+                    100,
+                    PERIOD,
+                    100
+                ),
+                Error::<MockRuntime>::TooManyDepositSchedules
+            );
+            create_schedule(BOB, 100, 100);
+        });
+    }
+
+    #[test]
+    fn create_deposit_schedule_validates_its_parameters() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_noop!(
+                Instrumental::create_deposit_schedule(
+                    Origin::signed(ALICE),
+                    CurrencyId::USDC,
+                    100,
+                    PERIOD,
+                    100
+                ),
+                Error::<MockRuntime>::AssetDoesNotHaveAnAssociatedVault
+            );
+
+            create_vault(CurrencyId::USDC);
+            for (amount, period, total) in [(0, PERIOD, 100), (100, 0, 100), (100, PERIOD, 99)] {
+                assert_noop!(
+                    Instrumental::create_deposit_schedule(
+                        Origin::signed(ALICE),
+                        CurrencyId::USDC,
+                        amount,
+                        period,
+                        total
+                    ),
+                    Error::<MockRuntime>::InvalidDepositSchedule
+                );
+            }
+        });
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//                                                XCM
+// -------------------------------------------------------------------------------------------------
+
+mod xcm {
+    use ::xcm::latest::prelude::*;
+    use codec::Encode;
+    use frame_support::traits::fungibles::Mutate;
+    use orml_traits::MultiCurrency;
+    use sp_runtime::{traits::Convert, DispatchError};
+    use xcm_simulator::TestExt;
+
+    use super::*;
+    use crate::mock::{
+        account_id::ALICE,
+        network::{
+            sibling, InstrumentalPara, MockNet, SiblingPara, INSTRUMENTAL_PARA_ID, SIBLING_PARA_ID,
+        },
+        runtime::{Call, LocationToAccountId},
+    };
+
+    const REMOTE_ACCOUNT: [u8; 32] = [42; 32];
+
+    /// Location of the remote account, as seen from the Instrumental parachain.
+    fn remote_location() -> MultiLocation {
+        MultiLocation::new(
+            1,
+            X2(
+                Parachain(SIBLING_PARA_ID),
+                AccountId32 {
+                    network: NetworkId::Any,
+                    id: REMOTE_ACCOUNT,
+                },
+            ),
+        )
+    }
+
+    fn sibling_sovereign_account() -> AccountId {
+        LocationToAccountId::convert(MultiLocation::new(1, X1(Parachain(SIBLING_PARA_ID))))
+    }
+
+    fn transact(call: pallet::Call<MockRuntime>) -> Vec<Instruction<()>> {
+        vec![
+            DescendOrigin(X1(AccountId32 {
+                network: NetworkId::Any,
+                id: REMOTE_ACCOUNT,
+            })),
+            Transact {
+                origin_type: OriginKind::Xcm,
+                require_weight_at_most: 1_000_000_000,
+                call: Call::Instrumental(call).encode().into(),
+            },
+        ]
+    }
+
+    fn send_to_instrumental(instructions: Vec<Instruction<()>>) {
+        SiblingPara::execute_with(|| {
+            assert_ok!(sibling::XcmRouter::send_xcm(
+                (Parent, Parachain(INSTRUMENTAL_PARA_ID)),
+                Xcm(instructions),
+            ));
+        });
+    }
+
+    /// Moves `amount` of `asset` from the reserves of the sibling parachain to the local account of
+    /// the remote account and deposits it into the vault of `asset`, all within a single message.
+    fn deposit_from_sibling(asset: CurrencyId, amount: Balance) {
+        let multi_asset: MultiAsset = (X1(GeneralIndex(asset.0)), amount).into();
+        let mut instructions = vec![
+            WithdrawAsset(multi_asset.into()),
+            DepositAsset {
+                assets: All.into(),
+                max_assets: 1,
+                beneficiary: remote_location(),
+            },
+        ];
+        instructions.extend(transact(pallet::Call::xcm_add_liquidity { asset, amount }));
+
+        send_to_instrumental(instructions);
+    }
+
+    fn create_vault_with_sibling_reserves(asset: CurrencyId, amount: Balance) {
+        InstrumentalPara::execute_with(|| {
+            let config = InstrumentalVaultConfigBuilder::default()
+                .asset_id(asset)
+                .build();
+            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+            assert_ok!(<Assets as Mutate<AccountId>>::mint_into(
+                asset,
+                &sibling_sovereign_account(),
+                amount
+            ));
+        });
+    }
+
+    #[test]
+    fn sibling_parachain_deposits_on_behalf_of_remote_account() {
+        MockNet::reset();
+
+        let asset = CurrencyId::USDC;
+        let amount = 100 * CurrencyId::unit::<Balance>();
+        create_vault_with_sibling_reserves(asset, amount);
+
+        deposit_from_sibling(asset, amount);
+
+        InstrumentalPara::execute_with(|| {
+            let vault_id = Instrumental::asset_vault(asset).unwrap();
+            let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+            let remote_account = LocationToAccountId::convert(remote_location());
+
+            assert_eq!(Assets::balance(asset, &vault_account), amount);
+            assert_eq!(Assets::balance(asset, &remote_account), 0);
+            assert_eq!(Assets::balance(asset, &sibling_sovereign_account()), 0);
+
+            System::assert_last_event(Event::Instrumental(
+                pallet::Event::AddedLiquidityFromRemote {
+                    location: remote_location(),
+                    asset,
+                    amount,
+                },
+            ));
+        });
+    }
+
+    #[test]
+    fn remote_account_withdraws_to_sibling_parachain() {
+        MockNet::reset();
+
+        let asset = CurrencyId::USDC;
+        let amount = 100 * CurrencyId::unit::<Balance>();
+        create_vault_with_sibling_reserves(asset, amount);
+        deposit_from_sibling(asset, amount);
+
+        send_to_instrumental(transact(pallet::Call::xcm_remove_liquidity {
+            asset,
+            amount,
+        }));
+
+        InstrumentalPara::execute_with(|| {
+            let vault_id = Instrumental::asset_vault(asset).unwrap();
+            let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+
+            assert_eq!(Assets::balance(asset, &vault_account), 0);
+            // The withdrawn assets are back in the reserves of the sibling parachain
+            assert_eq!(Assets::balance(asset, &sibling_sovereign_account()), amount);
+
+            System::assert_has_event(Event::Instrumental(
+                pallet::Event::RemovedLiquidityToRemote {
+                    location: remote_location(),
+                    asset,
+                    amount,
+                },
+            ));
+        });
+
+        SiblingPara::execute_with(|| {
+            assert_eq!(
+                sibling::Tokens::free_balance(asset, &REMOTE_ACCOUNT.into()),
+                amount
+            );
+        });
+    }
+
+    #[test]
+    fn xcm_extrinsics_reject_local_origins() {
+        ExtBuilder::default().build().execute_with(|| {
+            let asset = CurrencyId::USDC;
+            let config = InstrumentalVaultConfigBuilder::default()
+                .asset_id(asset)
+                .build();
+            assert_ok!(Instrumental::create(Origin::signed(ADMIN), config));
+
+            assert_noop!(
+                Instrumental::xcm_add_liquidity(Origin::signed(ALICE), asset, 1_000),
+                DispatchError::BadOrigin
+            );
+            assert_noop!(
+                Instrumental::xcm_remove_liquidity(Origin::signed(ALICE), asset, 1_000),
+                DispatchError::BadOrigin
+            );
+        });
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 //                                            ExtBuilder
 // -------------------------------------------------------------------------------------------------
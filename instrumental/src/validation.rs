@@ -1,8 +1,6 @@
 use core::marker::PhantomData;
 
 use composable_support::validation::Validate;
-use sp_runtime::Perquintill;
-use traits::instrumental::InstrumentalVaultConfig;
 
 use crate::pallet::{AssetVault, Config, InstrumentalVaultConfigFor};
 
@@ -38,8 +36,8 @@ impl<T: Config> Validate<InstrumentalVaultConfigFor<T>, ValidateVaultDoesNotExis
     for ValidateVaultDoesNotExist<T>
 {
     fn validate(
-        input: InstrumentalVaultConfig<T::AssetId, Perquintill>,
-    ) -> Result<InstrumentalVaultConfig<T::AssetId, Perquintill>, &'static str> {
+        input: InstrumentalVaultConfigFor<T>,
+    ) -> Result<InstrumentalVaultConfigFor<T>, &'static str> {
         if AssetVault::<T>::contains_key(input.asset_id) {
             return Err("Vault Already Exists");
         }
@@ -7,6 +7,15 @@ pub trait WeightInfo {
     fn create() -> Weight;
     fn add_liquidity() -> Weight;
     fn remove_liquidity() -> Weight;
+    fn snapshot_share_prices(vaults: u32) -> Weight;
+    fn xcm_add_liquidity() -> Weight;
+    fn xcm_remove_liquidity() -> Weight;
+    fn mint_position() -> Weight;
+    fn transfer_position() -> Weight;
+    fn redeem_position() -> Weight;
+    fn create_deposit_schedule() -> Weight;
+    fn cancel_deposit_schedule() -> Weight;
+    fn execute_deposit_schedule() -> Weight;
 }
 
 /// Weights for pallet_instrumental using the Substrate node and recommended hardware.
@@ -23,6 +32,42 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn remove_liquidity() -> Weight {
         I_HAVENT_CALCULATED_YET as Weight
     }
+
+    fn snapshot_share_prices(vaults: u32) -> Weight {
+        (I_HAVENT_CALCULATED_YET as Weight).saturating_mul(vaults as Weight)
+    }
+
+    fn xcm_add_liquidity() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn xcm_remove_liquidity() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn mint_position() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn transfer_position() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn redeem_position() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn create_deposit_schedule() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn cancel_deposit_schedule() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn execute_deposit_schedule() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
 }
 
 // For backwards compatibility and tests
@@ -38,4 +83,40 @@ impl WeightInfo for () {
     fn remove_liquidity() -> Weight {
         I_HAVENT_CALCULATED_YET as Weight
     }
+
+    fn snapshot_share_prices(vaults: u32) -> Weight {
+        (I_HAVENT_CALCULATED_YET as Weight).saturating_mul(vaults as Weight)
+    }
+
+    fn xcm_add_liquidity() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn xcm_remove_liquidity() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn mint_position() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn transfer_position() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn redeem_position() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn create_deposit_schedule() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn cancel_deposit_schedule() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
+
+    fn execute_deposit_schedule() -> Weight {
+        I_HAVENT_CALCULATED_YET as Weight
+    }
 }
@@ -4,7 +4,11 @@
 //! scenes, their assets will be sent to Picasso and further dispersed into the numerous other
 //! pallets in the parachain to earn yield.
 use codec::{Codec, Decode, Encode, MaxEncodedLen};
-use frame_support::{sp_std::fmt::Debug, Parameter, RuntimeDebug};
+use frame_support::{
+    sp_std::{collections::btree_map::BTreeMap, fmt::Debug},
+    traits::Get,
+    Parameter, RuntimeDebug,
+};
 use scale_info::TypeInfo;
 use sp_runtime::{DispatchError, DispatchResult, Perquintill};
 
@@ -20,10 +24,30 @@ pub enum State {
     Transferring,
 }
 
-#[derive(Clone, Copy, Encode, Decode, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct InstrumentalVaultConfig<AssetId, Percent> {
+/// Describes how the deployable funds of a new vault are split between the strategies.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum StrategyAllocation<AccountId: Ord, Percent> {
+    /// Deploy everything into the optimum strategy for the asset.
+    Optimum,
+    /// Let the dynamic strategy spread the funds over several strategies.
+    Diversified,
+    /// Use the given strategies and weights. The strategies must be eligible for the dynamic
+    /// strategy, at most [`MaxStrategies`](InstrumentalDynamicStrategy::MaxStrategies) of them,
+    /// and their weights must sum to the `percent_deployable` of the vault.
+    Explicit(BTreeMap<AccountId, Percent>),
+}
+
+impl<AccountId: Ord, Percent> Default for StrategyAllocation<AccountId, Percent> {
+    fn default() -> Self {
+        StrategyAllocation::Optimum
+    }
+}
+
+#[derive(Clone, Encode, Decode, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct InstrumentalVaultConfig<AccountId: Ord, AssetId, Percent> {
     pub asset_id: AssetId,
     pub percent_deployable: Percent,
+    pub strategies: StrategyAllocation<AccountId, Percent>,
 }
 
 /// Provide functionality for working with Instrumental pallet.
@@ -43,7 +67,7 @@ pub trait Instrumental {
     /// Create a new Instrumental vault for the specified asset; throws an error if the asset
     /// already has an associated vault.
     fn create(
-        config: InstrumentalVaultConfig<Self::AssetId, Perquintill>,
+        config: InstrumentalVaultConfig<Self::AccountId, Self::AssetId, Perquintill>,
     ) -> Result<Self::VaultId, DispatchError>;
 
     /// Specify an asset ID and amount to deposit. Behind the scenes the function will connect with
@@ -67,5 +91,19 @@ pub trait InstrumentalDynamicStrategy {
     type AccountId: core::cmp::Ord;
     type AssetId;
 
+    /// The maximum number of strategies a vault can be split between.
+    type MaxStrategies: Get<u32>;
+
     fn get_optimum_strategy_for(asset: Self::AssetId) -> Result<Self::AccountId, DispatchError>;
+
+    /// Split `percent_deployable` between several strategies suitable for `asset`. The returned
+    /// weights sum to `percent_deployable`.
+    fn get_diversified_allocation_for(
+        asset: Self::AssetId,
+        percent_deployable: Perquintill,
+    ) -> Result<BTreeMap<Self::AccountId, Perquintill>, DispatchError>;
+
+    /// Whether vaults may allocate funds to `strategy`, i.e. it is a registered strategy that
+    /// governance allows.
+    fn is_eligible(strategy: &Self::AccountId) -> bool;
 }
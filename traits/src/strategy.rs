@@ -3,9 +3,122 @@
 //! Common traits for working with strategies. For each protocol that wants to be reached through
 //! Instrumental, a unique strategy pallet needs to be developed and traits below should be
 //! implemented.
-use codec::Codec;
-use frame_support::{sp_std::fmt::Debug, Parameter};
-use sp_runtime::{DispatchError, DispatchResult};
+use codec::{Codec, Decode, Encode, MaxEncodedLen};
+use frame_support::{
+    sp_std::{fmt::Debug, vec::Vec},
+    Parameter, RuntimeDebug,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Zero, DispatchError, DispatchResult, FixedU128, Percent};
+
+/// Annual percentage yield of a strategy.
+///
+/// `rate` is the yield earned over one year, as a fraction of the deployed funds:
+/// `FixedU128::from_rational(5, 100)` is a 5% APY and `FixedU128::one()` doubles the funds in a
+/// year. Strategies measuring their yield over a shorter window must annualize it linearly,
+/// without compounding, before reporting, so that APYs of different strategies and the realized
+/// APY of instrumental vaults can be compared directly.
+#[derive(
+    Clone, Copy, Encode, Decode, MaxEncodedLen, Default, RuntimeDebug, PartialEq, Eq, TypeInfo,
+)]
+pub struct Apy<BlockNumber> {
+    /// The annualized rate.
+    pub rate: FixedU128,
+    /// The block at which `rate` was last measured, `None` if it is an estimation that was never
+    /// observed on chain.
+    pub last_updated: Option<BlockNumber>,
+}
+
+impl<BlockNumber> Apy<BlockNumber> {
+    /// An APY measured at block `last_updated`.
+    pub fn new(rate: FixedU128, last_updated: BlockNumber) -> Self {
+        Self {
+            rate,
+            last_updated: Some(last_updated),
+        }
+    }
+
+    /// An APY that has never been measured.
+    pub fn estimated(rate: FixedU128) -> Self {
+        Self {
+            rate,
+            last_updated: None,
+        }
+    }
+
+    /// A zero APY that has never been measured.
+    pub fn zero() -> Self {
+        Self::estimated(FixedU128::zero())
+    }
+
+    /// Whether `self` yields strictly more than `other`. When both rates are equal, a measured APY
+    /// is preferred to an estimated one.
+    pub fn is_better_than(&self, other: &Self) -> bool {
+        self.rate > other.rate
+            || (self.rate == other.rate
+                && self.last_updated.is_some()
+                && other.last_updated.is_none())
+    }
+}
+
+/// Operational metrics recorded by a strategy.
+///
+/// "Deposited" and "withdrawn" are seen from the protocol the strategy deploys funds into: funds
+/// taken from the vaults are deposited, funds returned to them are withdrawn.
+#[derive(
+    Clone, Copy, Encode, Decode, MaxEncodedLen, Default, RuntimeDebug, PartialEq, Eq, TypeInfo,
+)]
+pub struct StrategyHealth<BlockNumber, Balance> {
+    /// The last block at which a rebalance of the strategy succeeded for every vault.
+    pub last_rebalance: Option<BlockNumber>,
+    /// The number of rebalances that failed for at least one vault since the last successful one.
+    pub consecutive_failures: u32,
+    /// The funds deposited into the protocol since the strategy was deployed.
+    pub total_deposited: Balance,
+    /// The funds withdrawn from the protocol since the strategy was deployed.
+    pub total_withdrawn: Balance,
+    /// The funds returned to the vaults above what was taken from them.
+    pub realized_profit: Balance,
+    /// The funds lost, i.e. taken from the vaults and never returned to them.
+    pub realized_loss: Balance,
+}
+
+/// The thresholds above which a strategy halts itself.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct CircuitBreakerThresholds<BlockNumber> {
+    /// The largest drop of the value the strategy holds for a vault tolerated within `window`,
+    /// funds moved between the vault and the strategy excluded.
+    pub max_value_drop: Percent,
+    /// The number of blocks the value of a vault is compared over.
+    pub window: BlockNumber,
+    /// The number of rebalances failing in a row after which the strategy is halted, `0` never
+    /// halts it.
+    pub max_consecutive_failures: u32,
+}
+
+/// Why a circuit breaker halted its strategy.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum TripReason<VaultId> {
+    /// The value held for the vault dropped by more than
+    /// [`max_value_drop`](CircuitBreakerThresholds::max_value_drop).
+    ValueDrop {
+        /// Vault ID of the vault whose value dropped.
+        vault_id: VaultId,
+    },
+    /// Rebalances failed
+    /// [`max_consecutive_failures`](CircuitBreakerThresholds::max_consecutive_failures) times in
+    /// a row.
+    ConsecutiveFailures,
+}
+
+/// A tripped circuit breaker, the strategy staying halted until governance resets it.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct CircuitBreakerTrip<BlockNumber, VaultId> {
+    /// The block at which the breaker tripped.
+    pub at: BlockNumber,
+    /// Why the breaker tripped.
+    pub reason: TripReason<VaultId>,
+}
 
 /// Provide functionality for working with the strategy.
 pub trait InstrumentalProtocolStrategy {
@@ -13,6 +126,10 @@ pub trait InstrumentalProtocolStrategy {
     type AccountId: core::cmp::Ord;
     /// The ID that uniquely identify an asset.
     type AssetId;
+    /// The type used for bookkeeping.
+    type Balance;
+    /// The type used to mark when an [`Apy`] was measured.
+    type BlockNumber;
     /// The ID that uniquely identify a pool.
     type PoolId;
     /// The ID that uniquely identify a vault associated with the strategy.
@@ -24,12 +141,27 @@ pub trait InstrumentalProtocolStrategy {
     /// Associate a vault with this strategy.
     fn associate_vault(vault_id: &Self::VaultId) -> DispatchResult;
 
+    /// Remove a vault from this strategy.
+    fn dissociate_vault(vault_id: &Self::VaultId) -> DispatchResult;
+
     /// Queries the total assets under management by the strategies associated vault (reserved
     /// balance plus the amount in the strategy) and performs any rebalancing if required.
     fn rebalance() -> DispatchResult;
 
-    /// Returns the optimum (estimated) APY value for a provided asset id.
-    fn get_apy(asset: Self::AssetId) -> Result<u128, DispatchError>;
+    /// Performs any rebalancing required by a single associated vault.
+    fn rebalance_vault(vault_id: &Self::VaultId) -> DispatchResult;
+
+    /// Returns the amount of the vault's asset currently managed by the strategy on behalf of the
+    /// vault.
+    fn assets_under_management(vault_id: &Self::VaultId) -> Result<Self::Balance, DispatchError>;
+
+    /// Sets the funds the strategy manages on behalf of an associated vault to `amount`, taking
+    /// the missing funds from the vault or returning the excess to it. Later rebalances of the
+    /// vault keep it at `amount` instead of following the allocation of the vault.
+    fn allot(vault_id: &Self::VaultId, amount: Self::Balance) -> DispatchResult;
+
+    /// Returns the optimum annualized [`Apy`] for a provided asset id.
+    fn get_apy(asset: Self::AssetId) -> Result<Apy<Self::BlockNumber>, DispatchError>;
 
     /// Halts the strategy (freezes deposit/withdrawal of funds).
     fn halt() -> DispatchResult;
@@ -37,6 +169,325 @@ pub trait InstrumentalProtocolStrategy {
     /// Resume the strategy after halting.
     fn start() -> DispatchResult;
 
+    /// Halts a single associated vault, leaving the other vaults of the strategy untouched.
+    fn halt_vault(vault_id: &Self::VaultId) -> DispatchResult;
+
+    /// Resume a single associated vault after halting.
+    fn start_vault(vault_id: &Self::VaultId) -> DispatchResult;
+
     /// Returns information about whether the strategy has been halted.
     fn is_halted() -> Result<bool, DispatchError>;
 }
+
+/// The assets a strategy is able to put to work.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum SupportedAssets<AssetId> {
+    /// Any asset can be deployed into the strategy.
+    All,
+    /// Only the listed assets can be deployed into the strategy.
+    Only(Vec<AssetId>),
+}
+
+impl<AssetId: PartialEq> SupportedAssets<AssetId> {
+    /// Whether `asset` can be deployed into the strategy.
+    pub fn contains(&self, asset: &AssetId) -> bool {
+        match self {
+            SupportedAssets::All => true,
+            SupportedAssets::Only(assets) => assets.contains(asset),
+        }
+    }
+}
+
+/// Describes what a strategy is and what it is able to do.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct StrategyMetadata<AssetId, Balance> {
+    /// Human readable name of the strategy.
+    pub name: Vec<u8>,
+    /// The assets that can be deployed into the strategy.
+    pub supported_assets: SupportedAssets<AssetId>,
+    /// How risky deploying funds into the strategy is, from 0% (risk free) to 100%.
+    pub risk_score: Percent,
+    /// Whether all the funds of a vault can be returned to it within a single call, without
+    /// waiting for an unbonding period or a remote chain.
+    pub synchronous_unwind: bool,
+    /// The maximum amount the strategy can manage per vault, `None` if it is unbounded.
+    pub max_capacity: Option<Balance>,
+}
+
+/// Provide information about the capabilities of a strategy.
+pub trait InstrumentalStrategyMetadata {
+    /// The ID that uniquely identify an asset.
+    type AssetId: PartialEq;
+    /// The type used for bookkeeping.
+    type Balance;
+
+    /// Returns the [`StrategyMetadata`] of the strategy.
+    fn metadata() -> StrategyMetadata<Self::AssetId, Self::Balance>;
+
+    /// Whether `asset` can be deployed into the strategy.
+    fn supports_asset(asset: &Self::AssetId) -> bool {
+        Self::metadata().supported_assets.contains(asset)
+    }
+}
+
+/// Error returned by a [`StrategyRegistry`].
+#[derive(Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// No registered strategy has the requested account.
+    StrategyNotRegistered,
+    /// The strategy failed to perform the call.
+    Strategy(DispatchError),
+}
+
+impl From<DispatchError> for RegistryError {
+    fn from(error: DispatchError) -> Self {
+        RegistryError::Strategy(error)
+    }
+}
+
+/// Result of a call forwarded by a [`StrategyRegistry`].
+pub type RegistryResult<T = ()> = Result<T, RegistryError>;
+
+/// A collection of strategies, addressed by their account ids.
+///
+/// Implemented for tuples of up to eight strategies, so a runtime can register its strategies as
+/// `type Strategies = (PabloStrategy, DefaultStrategy)`. Every call is forwarded to the strategy
+/// whose [`account_id`](InstrumentalProtocolStrategy::account_id) is `strategy`, or fails with
+/// [`RegistryError::StrategyNotRegistered`].
+pub trait StrategyRegistry {
+    /// The ID that uniquely identify a strategy.
+    type AccountId: Ord;
+    /// The ID that uniquely identify an asset.
+    type AssetId: PartialEq;
+    /// The type used for bookkeeping.
+    type Balance;
+    /// The type used to mark when an [`Apy`] was measured.
+    type BlockNumber;
+    /// The ID that uniquely identify a vault associated with the strategies.
+    type VaultId;
+
+    /// Returns the accounts of the registered strategies, in registration order.
+    fn accounts() -> Vec<Self::AccountId>;
+
+    /// Whether a strategy with the account `strategy` is registered.
+    fn contains(strategy: &Self::AccountId) -> bool {
+        Self::accounts().contains(strategy)
+    }
+
+    /// See [`InstrumentalStrategyMetadata::metadata`].
+    fn metadata(
+        strategy: &Self::AccountId,
+    ) -> RegistryResult<StrategyMetadata<Self::AssetId, Self::Balance>>;
+
+    /// See [`InstrumentalProtocolStrategy::associate_vault`].
+    fn associate_vault(strategy: &Self::AccountId, vault_id: &Self::VaultId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::dissociate_vault`].
+    fn dissociate_vault(strategy: &Self::AccountId, vault_id: &Self::VaultId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::rebalance`].
+    fn rebalance(strategy: &Self::AccountId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::rebalance_vault`].
+    fn rebalance_vault(strategy: &Self::AccountId, vault_id: &Self::VaultId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::assets_under_management`].
+    fn assets_under_management(
+        strategy: &Self::AccountId,
+        vault_id: &Self::VaultId,
+    ) -> RegistryResult<Self::Balance>;
+
+    /// See [`InstrumentalProtocolStrategy::allot`].
+    fn allot(
+        strategy: &Self::AccountId,
+        vault_id: &Self::VaultId,
+        amount: Self::Balance,
+    ) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::get_apy`].
+    fn get_apy(
+        strategy: &Self::AccountId,
+        asset: Self::AssetId,
+    ) -> RegistryResult<Apy<Self::BlockNumber>>;
+
+    /// See [`InstrumentalProtocolStrategy::halt`].
+    fn halt(strategy: &Self::AccountId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::start`].
+    fn start(strategy: &Self::AccountId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::halt_vault`].
+    fn halt_vault(strategy: &Self::AccountId, vault_id: &Self::VaultId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::start_vault`].
+    fn start_vault(strategy: &Self::AccountId, vault_id: &Self::VaultId) -> RegistryResult;
+
+    /// See [`InstrumentalProtocolStrategy::is_halted`].
+    fn is_halted(strategy: &Self::AccountId) -> RegistryResult<bool>;
+}
+
+/// Calls `$trait::$method` with `$arg`s on the strategy among `$strategy`s whose account is
+/// `$account`.
+macro_rules! dispatch_to_strategy {
+    ($account:expr, [$($strategy:ident),+], $trait:ident::$method:ident($($arg:expr),*)) => {{
+        $(
+            if &<$strategy as InstrumentalProtocolStrategy>::account_id() == $account {
+                return <$strategy as $trait>::$method($($arg),*).map_err(RegistryError::from);
+            }
+        )+
+        Err(RegistryError::StrategyNotRegistered)
+    }};
+}
+
+macro_rules! impl_strategy_registry_for_tuple {
+    ($($strategy:ident),+) => {
+        impl<AccountId, AssetId, Balance, BlockNumber, VaultId, $($strategy),+> StrategyRegistry
+            for ($($strategy,)+)
+        where
+            AccountId: Ord,
+            AssetId: PartialEq,
+            VaultId: Clone + Codec + Debug + PartialEq + Default + Parameter,
+            $(
+                $strategy: InstrumentalProtocolStrategy<
+                        AccountId = AccountId,
+                        AssetId = AssetId,
+                        Balance = Balance,
+                        BlockNumber = BlockNumber,
+                        VaultId = VaultId,
+                    > + InstrumentalStrategyMetadata<AssetId = AssetId, Balance = Balance>,
+            )+
+        {
+            type AccountId = AccountId;
+            type AssetId = AssetId;
+            type Balance = Balance;
+            type BlockNumber = BlockNumber;
+            type VaultId = VaultId;
+
+            fn accounts() -> Vec<AccountId> {
+                Vec::from([$(<$strategy as InstrumentalProtocolStrategy>::account_id()),+])
+            }
+
+            fn metadata(
+                strategy: &AccountId,
+            ) -> RegistryResult<StrategyMetadata<AssetId, Balance>> {
+                $(
+                    if &<$strategy as InstrumentalProtocolStrategy>::account_id() == strategy {
+                        return Ok(<$strategy as InstrumentalStrategyMetadata>::metadata());
+                    }
+                )+
+                Err(RegistryError::StrategyNotRegistered)
+            }
+
+            fn associate_vault(strategy: &AccountId, vault_id: &VaultId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::associate_vault(vault_id)
+                )
+            }
+
+            fn dissociate_vault(strategy: &AccountId, vault_id: &VaultId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::dissociate_vault(vault_id)
+                )
+            }
+
+            fn rebalance(strategy: &AccountId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::rebalance()
+                )
+            }
+
+            fn rebalance_vault(strategy: &AccountId, vault_id: &VaultId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::rebalance_vault(vault_id)
+                )
+            }
+
+            fn assets_under_management(
+                strategy: &AccountId,
+                vault_id: &VaultId,
+            ) -> RegistryResult<Balance> {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::assets_under_management(vault_id)
+                )
+            }
+
+            fn allot(strategy: &AccountId, vault_id: &VaultId, amount: Balance) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::allot(vault_id, amount)
+                )
+            }
+
+            fn get_apy(
+                strategy: &AccountId,
+                asset: AssetId,
+            ) -> RegistryResult<Apy<BlockNumber>> {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::get_apy(asset)
+                )
+            }
+
+            fn halt(strategy: &AccountId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::halt()
+                )
+            }
+
+            fn start(strategy: &AccountId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::start()
+                )
+            }
+
+            fn halt_vault(strategy: &AccountId, vault_id: &VaultId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::halt_vault(vault_id)
+                )
+            }
+
+            fn start_vault(strategy: &AccountId, vault_id: &VaultId) -> RegistryResult {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::start_vault(vault_id)
+                )
+            }
+
+            fn is_halted(strategy: &AccountId) -> RegistryResult<bool> {
+                dispatch_to_strategy!(
+                    strategy,
+                    [$($strategy),+],
+                    InstrumentalProtocolStrategy::is_halted()
+                )
+            }
+        }
+    };
+}
+
+impl_strategy_registry_for_tuple!(A);
+impl_strategy_registry_for_tuple!(A, B);
+impl_strategy_registry_for_tuple!(A, B, C);
+impl_strategy_registry_for_tuple!(A, B, C, D);
+impl_strategy_registry_for_tuple!(A, B, C, D, E);
+impl_strategy_registry_for_tuple!(A, B, C, D, E, F);
+impl_strategy_registry_for_tuple!(A, B, C, D, E, F, G);
+impl_strategy_registry_for_tuple!(A, B, C, D, E, F, G, H);